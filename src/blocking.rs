@@ -0,0 +1,478 @@
+//! Blocking client of the M2Web API, for the programs which do not run an async runtime.
+
+use crate::m2web::{
+    account::{AccountInfo, Pool},
+    alarm::Alarm,
+    client::Client as AsyncClient,
+    config::{ConfigBackup, ConfigBackupDiff},
+    device::{DeviceInfo, ScriptState},
+    ebd::{Ebd, EbdTime},
+    error,
+    event::EventEntry,
+    ewon::{Ewon, EwonRef, WakeupOutcome, WakeupSummary},
+    filter::EwonFilter,
+    history::LogRecord,
+    proxy::ProxyResponse,
+    tag::{Tag, TagData, TagGroup, TagValue},
+};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::AsyncWrite,
+    runtime::{Builder, Runtime},
+};
+
+/// Blocking M2Web API client.
+///
+/// The client wraps the async client, with a runtime of its own which runs its requests: the caller does
+/// not have to set up any runtime. Its methods mirror those of the async client, and block until their
+/// response; they must not be called from an async context, where the async client should be used instead.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{blocking::Client, m2web::{client::ClientBuilder, error}};
+/// # fn get_ewons_blocking() -> Result<(), error::Error> {
+/// let client = Client::new(ClientBuilder::default().build().unwrap())?;
+///
+/// // Do something useful, for example:
+/// let ewons = client.get_ewons(None)?;
+/// ewons
+///     .iter()
+///     .for_each(|ewon| println!("#{}: {}", ewon.id, ewon.name));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Client<'a> {
+    /// The async client sending the requests.
+    inner: AsyncClient<'a>,
+    /// The runtime running the requests.
+    runtime: Runtime,
+}
+
+impl<'a> Client<'a> {
+    /// Wrap the async client, with a runtime of its own.
+    pub fn new(client: AsyncClient<'a>) -> Result<Self, error::Error> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+
+        Ok(Client {
+            inner: client,
+            runtime,
+        })
+    }
+
+    /// Return the async client.
+    pub fn into_inner(self) -> AsyncClient<'a> {
+        self.inner
+    }
+
+    /// Return the API base url serving the requests.
+    pub fn serving_t2m_url(&self) -> &str {
+        self.inner.serving_t2m_url()
+    }
+
+    /// Open a stateful session.
+    pub fn login(&mut self) -> Result<&str, error::Error> {
+        self.runtime.block_on(self.inner.login())
+    }
+
+    /// Close a stateful session.
+    pub fn logout(self) -> Result<(), error::Error> {
+        let Client { inner, runtime } = self;
+
+        runtime.block_on(inner.logout())
+    }
+
+    /// Return the list of all eWONs registered for the corporate account.
+    pub fn get_ewons(&self, pool: Option<&str>) -> Result<Vec<Ewon>, error::Error> {
+        self.runtime.block_on(self.inner.get_ewons(pool))
+    }
+
+    /// Return the list of all eWONs registered for the corporate account, bypassing the cache.
+    pub fn refresh_ewons(&self, pool: Option<&str>) -> Result<Vec<Ewon>, error::Error> {
+        self.runtime.block_on(self.inner.refresh_ewons(pool))
+    }
+
+    /// Return all eWONs registered for the corporate account, keyed by their id.
+    pub fn get_ewons_by_id_map(
+        &self,
+        pool: Option<&str>,
+    ) -> Result<HashMap<u32, Ewon>, error::Error> {
+        self.runtime.block_on(self.inner.get_ewons_by_id_map(pool))
+    }
+
+    /// Return all eWONs registered for the corporate account, keyed by their name.
+    pub fn get_ewons_by_name_map(
+        &self,
+        pool: Option<&str>,
+    ) -> Result<HashMap<String, Ewon>, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_ewons_by_name_map(pool))
+    }
+
+    /// Return all eWONs registered for the corporate account, grouped by pool.
+    pub fn get_ewons_by_pool(&self) -> Result<HashMap<String, Vec<Ewon>>, error::Error> {
+        self.runtime.block_on(self.inner.get_ewons_by_pool())
+    }
+
+    /// Return the list of the eWONs matching a filter.
+    pub fn get_ewons_filtered(
+        &self,
+        pool: Option<&str>,
+        filter: &EwonFilter,
+    ) -> Result<Vec<Ewon>, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_ewons_filtered(pool, filter))
+    }
+
+    /// Return the eWON selected by its name.
+    pub fn get_ewon_by_name(&self, name: &str) -> Result<Ewon, error::Error> {
+        self.runtime.block_on(self.inner.get_ewon_by_name(name))
+    }
+
+    /// Return the eWON selected by its url-encoded name.
+    pub fn get_ewon_by_encoded_name(&self, encoded_name: &str) -> Result<Ewon, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_ewon_by_encoded_name(encoded_name))
+    }
+
+    /// Return the eWON selected by its id.
+    pub fn get_ewon_by_id(&self, id: u32) -> Result<Ewon, error::Error> {
+        self.runtime.block_on(self.inner.get_ewon_by_id(id))
+    }
+
+    /// Return the eWON selected by its id or its name, bypassing the cache.
+    pub fn refresh_ewon<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<Ewon, error::Error> {
+        self.runtime.block_on(self.inner.refresh_ewon(ewon))
+    }
+
+    /// Return the information of the corporate account.
+    pub fn get_account_info(&self) -> Result<AccountInfo, error::Error> {
+        self.runtime.block_on(self.inner.get_account_info())
+    }
+
+    /// Return the list of all pools defined for the corporate account.
+    pub fn get_pools(&self) -> Result<Vec<Pool>, error::Error> {
+        self.runtime.block_on(self.inner.get_pools())
+    }
+
+    /// Wake up an eWON.
+    pub fn wakeup<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<WakeupOutcome, error::Error> {
+        self.runtime.block_on(self.inner.wakeup(ewon))
+    }
+
+    /// Wake up an eWON and wait for it to be online.
+    pub fn wakeup_and_wait<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        timeout: Duration,
+    ) -> Result<Ewon, error::Error> {
+        self.runtime
+            .block_on(self.inner.wakeup_and_wait(ewon, timeout))
+    }
+
+    /// Wake up all the eWONs belonging to a pool.
+    pub fn wakeup_pool(
+        &self,
+        pool: &str,
+        interval: Duration,
+    ) -> Result<WakeupSummary, error::Error> {
+        self.runtime
+            .block_on(self.inner.wakeup_pool(pool, interval))
+    }
+
+    /// Queue an action for an offline eWON.
+    pub fn send_offline_action<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        action: &str,
+    ) -> Result<(), error::Error> {
+        self.runtime
+            .block_on(self.inner.send_offline_action(ewon, action))
+    }
+
+    /// Send a GET request to the web server of an eWON, through the M2Web API.
+    pub fn proxy_get<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        device_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ProxyResponse, error::Error> {
+        self.runtime
+            .block_on(self.inner.proxy_get(ewon, device_path, req_query_params))
+    }
+
+    /// Export data from an eWON to a writer, as described by an Export Block Descriptor.
+    pub fn download_ebd_to<'e, W>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: Write + ?Sized,
+    {
+        self.runtime.block_on(self.inner.download_ebd_to(
+            ewon,
+            ebd,
+            &mut SyncWriter(writer),
+            progress,
+        ))
+    }
+
+    /// Download a file stored on an eWON.
+    pub fn download_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+    ) -> Result<Vec<u8>, error::Error> {
+        self.runtime.block_on(self.inner.download_file(ewon, path))
+    }
+
+    /// Download a file stored on an eWON to a writer.
+    pub fn download_file_to<'e, W>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: Write + ?Sized,
+    {
+        self.runtime.block_on(self.inner.download_file_to(
+            ewon,
+            path,
+            &mut SyncWriter(writer),
+            progress,
+        ))
+    }
+
+    /// Upload a file to an eWON.
+    pub fn upload_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+        body: impl Into<Vec<u8>>,
+    ) -> Result<(), error::Error> {
+        self.runtime
+            .block_on(self.inner.upload_file(ewon, path, body))
+    }
+
+    /// Back up the configuration of an eWON.
+    pub fn backup_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<ConfigBackup, error::Error> {
+        self.runtime.block_on(self.inner.backup_config(ewon))
+    }
+
+    /// Restore the configuration of an eWON from a backup.
+    pub fn restore_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+    ) -> Result<(), error::Error> {
+        self.runtime
+            .block_on(self.inner.restore_config(ewon, backup))
+    }
+
+    /// Compare the configuration of an eWON with a backup, without changing the eWON.
+    pub fn diff_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+        sections: Option<&[&str]>,
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        self.runtime
+            .block_on(self.inner.diff_config(ewon, backup, sections))
+    }
+
+    /// Restore the selected sections of the configuration of an eWON from a backup.
+    pub fn restore_config_sections<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+        sections: &[&str],
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        self.runtime
+            .block_on(self.inner.restore_config_sections(ewon, backup, sections))
+    }
+
+    /// Export data from an eWON, as described by an Export Block Descriptor.
+    pub fn export<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<String, error::Error> {
+        self.runtime.block_on(self.inner.export(ewon, ebd))
+    }
+
+    /// Return the historical logging of an eWON.
+    pub fn get_historical_data<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_historical_data(ewon, ebd))
+    }
+
+    /// Return the real-time logging of an eWON.
+    pub fn get_realtime_data<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_realtime_data(ewon, ebd))
+    }
+
+    /// Return the event log of an eWON, between both times.
+    pub fn get_events<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        start: EbdTime,
+        end: EbdTime,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        self.runtime
+            .block_on(self.inner.get_events(ewon, start, end))
+    }
+
+    /// Return the events of the event file of an eWON.
+    pub fn get_event_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        self.runtime.block_on(self.inner.get_event_file(ewon))
+    }
+
+    /// Return the system information of an eWON.
+    pub fn get_device_info<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<DeviceInfo, error::Error> {
+        self.runtime.block_on(self.inner.get_device_info(ewon))
+    }
+
+    /// Return the instant values of the tags of an eWON.
+    pub fn read_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<TagValue>, error::Error> {
+        self.runtime.block_on(self.inner.read_tags(ewon))
+    }
+
+    /// Return the instant values of the tags of an eWON which belong to the given groups.
+    pub fn read_group_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        groups: &[TagGroup],
+    ) -> Result<Vec<TagValue>, error::Error> {
+        self.runtime
+            .block_on(self.inner.read_group_tags(ewon, groups))
+    }
+
+    /// Return the tags configured on an eWON.
+    pub fn list_tags<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<Vec<Tag>, error::Error> {
+        self.runtime.block_on(self.inner.list_tags(ewon))
+    }
+
+    /// Return the tags configured on an eWON which belong to the given groups.
+    pub fn list_group_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        groups: &[TagGroup],
+    ) -> Result<Vec<Tag>, error::Error> {
+        self.runtime
+            .block_on(self.inner.list_group_tags(ewon, groups))
+    }
+
+    /// Write the value of a tag of an eWON.
+    pub fn write_tag<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        name: &str,
+        value: impl Into<TagData>,
+    ) -> Result<(), error::Error> {
+        self.runtime
+            .block_on(self.inner.write_tag(ewon, name, value))
+    }
+
+    /// Write the values of several tags of an eWON at once.
+    pub fn write_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        tags: &[(&str, TagData)],
+    ) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.write_tags(ewon, tags))
+    }
+
+    /// Return the active alarms of an eWON.
+    pub fn get_alarms<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<Vec<Alarm>, error::Error> {
+        self.runtime.block_on(self.inner.get_alarms(ewon))
+    }
+
+    /// Acknowledge the alarm of a tag of an eWON.
+    pub fn ack_alarm<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        tag: &str,
+    ) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.ack_alarm(ewon, tag))
+    }
+
+    /// Start the BASIC script of an eWON.
+    pub fn start_script<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.start_script(ewon))
+    }
+
+    /// Stop the BASIC script of an eWON.
+    pub fn stop_script<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<(), error::Error> {
+        self.runtime.block_on(self.inner.stop_script(ewon))
+    }
+
+    /// Return the run state of the BASIC script of an eWON.
+    pub fn get_script_state<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<ScriptState, error::Error> {
+        self.runtime.block_on(self.inner.get_script_state(ewon))
+    }
+
+    /// Request an endpoint of the API, and return the HTTP status and the JSON body of its response.
+    pub fn request_raw(
+        &self,
+        endpoint: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<(reqwest::StatusCode, serde_json::Value), error::Error> {
+        self.runtime
+            .block_on(self.inner.request_raw(endpoint, req_query_params))
+    }
+}
+
+/// Writer adapting a blocking writer to the async downloads.
+struct SyncWriter<'w, W: Write + ?Sized>(&'w mut W);
+
+impl<W: Write + ?Sized> AsyncWrite for SyncWriter<'_, W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, io::Error>> {
+        Poll::Ready(self.0.write(buf))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        Poll::Ready(self.0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        self.poll_flush(cx)
+    }
+}
@@ -0,0 +1,294 @@
+//! Conversion of the values of the tags to Polars data frames.
+
+use crate::{
+    dmweb::data::{DataPoint, DataRecord, TagValue},
+    m2web::{error, history::LogRecord, tag::TagData},
+};
+use chrono::{DateTime, Utc};
+use polars::prelude::{
+    Column, DataFrame, Int64Chunked, IntoColumn, IntoSeries, NamedFrom, PolarsError, Series,
+    TimeUnit,
+};
+
+/// Conversion of values to a Polars data frame, with a row per value.
+///
+/// The times are held by a `ts` column of UTC datetimes, to the millisecond. The numbers and booleans are
+/// held by a `value` column of floating point numbers, the strings by a `text_value` column; the other
+/// column is null. The columns naming the eWON and the tag depend on the values:
+/// - the `DataPoint`s only hold the `ts`, `value`, `text_value` and `quality` columns;
+/// - the `DataRecord`s, synchronized from the Data Mailbox, also hold the `ewon_id`, `ewon`, `tag_id` and
+///   `tag` columns;
+/// - the `LogRecord`s, exported by an eWON, hold the `tag_id` and `tag` columns, their OPC `quality` as
+///   an integer, and the `initial` column.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dataframe::IntoDataFrame, dmweb::{client::DmClientBuilder, query::DataQuery}, m2web::error};
+/// # #[tokio::test]
+/// # async fn analyze_dm_data() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let data = client.get_data(&DataQuery::default()).await?;
+///
+/// let values = data.into_records().collect::<Vec<_>>().into_dataframe()?;
+/// println!("{}", values.head(Some(10)));
+/// # Ok(())
+/// # }
+/// ```
+pub trait IntoDataFrame {
+    /// Return the values as a data frame.
+    fn into_dataframe(self) -> Result<DataFrame, error::Error>;
+}
+
+impl IntoDataFrame for Vec<DataPoint> {
+    fn into_dataframe(self) -> Result<DataFrame, error::Error> {
+        let mut columns = vec![];
+        columns.extend(data_point_columns(self.iter()));
+        columns.push(column(
+            "quality",
+            self.iter()
+                .map(|data_point| data_point.quality.as_str())
+                .collect::<Vec<_>>(),
+        ));
+
+        DataFrame::new(columns).map_err(polars_error)
+    }
+}
+
+impl IntoDataFrame for Vec<DataRecord> {
+    fn into_dataframe(self) -> Result<DataFrame, error::Error> {
+        let mut columns = vec![
+            column(
+                "ewon_id",
+                self.iter().map(|record| record.ewon_id).collect::<Vec<_>>(),
+            ),
+            column(
+                "ewon",
+                self.iter()
+                    .map(|record| record.ewon_name.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            column(
+                "tag_id",
+                self.iter().map(|record| record.tag_id).collect::<Vec<_>>(),
+            ),
+            column(
+                "tag",
+                self.iter()
+                    .map(|record| record.tag_name.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+        ];
+        columns.extend(data_point_columns(
+            self.iter().map(|record| &record.data_point),
+        ));
+        columns.push(column(
+            "quality",
+            self.iter()
+                .map(|record| record.data_point.quality.as_str())
+                .collect::<Vec<_>>(),
+        ));
+
+        DataFrame::new(columns).map_err(polars_error)
+    }
+}
+
+impl IntoDataFrame for Vec<LogRecord> {
+    fn into_dataframe(self) -> Result<DataFrame, error::Error> {
+        let columns = vec![
+            column(
+                "tag_id",
+                self.iter().map(|record| record.tag_id).collect::<Vec<_>>(),
+            ),
+            column(
+                "tag",
+                self.iter()
+                    .map(|record| record.tag_name.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            ts_column(
+                self.iter()
+                    .map(|record| DateTime::<Utc>::from(record.timestamp).timestamp_millis()),
+            ),
+            column(
+                "value",
+                self.iter()
+                    .map(|record| match record.value {
+                        TagData::Number(number) => Some(number),
+                        TagData::Text(_) => None,
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            column(
+                "text_value",
+                self.iter()
+                    .map(|record| match record.value {
+                        TagData::Number(_) => None,
+                        TagData::Text(ref text) => Some(text.as_str()),
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            column(
+                "quality",
+                self.iter()
+                    .map(|record| u32::from(record.quality))
+                    .collect::<Vec<_>>(),
+            ),
+            column(
+                "initial",
+                self.iter().map(|record| record.initial).collect::<Vec<_>>(),
+            ),
+        ];
+
+        DataFrame::new(columns).map_err(polars_error)
+    }
+}
+
+/// Return the `ts`, `value` and `text_value` columns of the data points.
+fn data_point_columns<'d>(data_points: impl Iterator<Item = &'d DataPoint> + Clone) -> [Column; 3] {
+    [
+        ts_column(
+            data_points
+                .clone()
+                .map(|data_point| data_point.ts.timestamp_millis()),
+        ),
+        column(
+            "value",
+            data_points
+                .clone()
+                .map(|data_point| data_point.value.as_f64())
+                .collect::<Vec<_>>(),
+        ),
+        column(
+            "text_value",
+            data_points
+                .map(|data_point| match data_point.value {
+                    TagValue::String(ref text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        ),
+    ]
+}
+
+/// Return a column holding the values.
+fn column<T, P: ?Sized>(name: &str, values: T) -> Column
+where
+    Series: NamedFrom<T, P>,
+{
+    Series::new(name.into(), values).into_column()
+}
+
+/// Return the `ts` column, of UTC datetimes, from times in milliseconds since the Unix epoch.
+fn ts_column(millis: impl Iterator<Item = i64>) -> Column {
+    Int64Chunked::new("ts".into(), millis.collect::<Vec<_>>())
+        .into_datetime(TimeUnit::Milliseconds, Some("UTC".into()))
+        .into_series()
+        .into_column()
+}
+
+/// Map an error of Polars to an internal error.
+fn polars_error(error: PolarsError) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("Polars error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dataframe::IntoDataFrame,
+        dmweb::data::{DataPoint, DataRecord, TagValue},
+        m2web::{history::LogRecord, tag::TagData},
+    };
+    use chrono::{TimeZone, Utc};
+    use polars::prelude::{DataType, TimeUnit};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn into_dataframe_ok() {
+        let data_point = |value| DataPoint {
+            ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+            value,
+            quality: "good".to_string(),
+        };
+        let records = vec![
+            DataRecord {
+                ewon_id: 7,
+                ewon_name: "bea-test".to_string(),
+                tag_id: 12,
+                tag_name: "Temperature".to_string(),
+                data_point: data_point(TagValue::Float(21.5)),
+            },
+            DataRecord {
+                ewon_id: 7,
+                ewon_name: "bea-test".to_string(),
+                tag_id: 13,
+                tag_name: "Recipe".to_string(),
+                data_point: data_point(TagValue::String("PET-500".to_string())),
+            },
+        ];
+
+        let values = records.into_dataframe().unwrap();
+        assert_eq!(
+            values.get_column_names(),
+            [
+                "ewon_id",
+                "ewon",
+                "tag_id",
+                "tag",
+                "ts",
+                "value",
+                "text_value",
+                "quality"
+            ]
+        );
+        assert_eq!(values.height(), 2);
+        assert_eq!(
+            values.column("ts").unwrap().dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, Some("UTC".into()))
+        );
+        assert_eq!(
+            values.column("value").unwrap().f64().unwrap().get(0),
+            Some(21.5)
+        );
+        assert_eq!(values.column("value").unwrap().f64().unwrap().get(1), None);
+        assert_eq!(
+            values.column("text_value").unwrap().str().unwrap().get(1),
+            Some("PET-500")
+        );
+
+        let data_points = vec![data_point(TagValue::Bool(true))]
+            .into_dataframe()
+            .unwrap();
+        assert_eq!(
+            data_points.get_column_names(),
+            ["ts", "value", "text_value", "quality"]
+        );
+        assert_eq!(
+            data_points.column("value").unwrap().f64().unwrap().get(0),
+            Some(1.0)
+        );
+
+        let log = vec![LogRecord {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+            tag_id: 1,
+            tag_name: "Temperature".to_string(),
+            value: TagData::Number(21.5),
+            quality: 192,
+            initial: true,
+        }]
+        .into_dataframe()
+        .unwrap();
+        assert_eq!(
+            log.column("ts").unwrap().datetime().unwrap().get(0),
+            Some(1700000000000)
+        );
+        assert_eq!(
+            log.column("initial").unwrap().bool().unwrap().get(0),
+            Some(true)
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use crate::{
+    dmweb::{
+        data::{
+            merge_ewons, Boundary, DmEwon, DmEwonsResponse, HistoricalData, MailboxStatus,
+            PageProgress, SyncData,
+        },
+        query::{ConfirmedDelete, DataQuery},
+        sync::{CheckpointStore, SyncStream},
+    },
+    m2web::{
+        breaker::CircuitBreaker,
+        client::{default_headers, http_client_setters, HttpClientOptions},
+        error,
+        failover::Failover,
+        interceptor::Interceptor,
+        limiter::RateLimiter,
+        metrics::MetricsObserver,
+        retry::{Middleware, RetryPolicy},
+        service::HttpService,
+        timeouts::Timeouts,
+    },
+};
+use derive_builder::Builder;
+use reqwest::{header::HeaderMap, Client as HttpClient};
+use serde::de::DeserializeOwned;
+use std::{sync::Arc, time::SystemTime};
+use tokio::sync::Semaphore;
+
+/// The default API base url of the Data Mailbox.
+pub const DEFAULT_DM_URL: &str = "https://data.talk2m.com";
+
+/// DMWeb API client.
+///
+/// Connect to the DMWeb API of the Talk2M Data Mailbox, which stores the historical data pushed by the
+/// eWONs. Unlike the M2Web API, the DMWeb API authenticates the requests with a Talk2M token, generated for
+/// the account, rather than with the credentials of a user.
+///
+/// # Example
+/// ```rust
+/// # use libewon::dmweb::client::DmClientBuilder;
+/// let client = DmClientBuilder::default()
+///     .t2m_token("3dtWJGqmaaNw1FDVQUKz0RXIqdNoObS4HwLfo13SNPhfaKPdFg")
+///     .t2m_developer_id("731e38ec-981f-4f31-9cb5-e87f0d571816")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Builder)]
+pub struct DmClient<'a> {
+    /// The API base url.
+    #[builder(default = "DEFAULT_DM_URL")]
+    t2m_url: &'a str,
+    /// The Talk2M token of the account, which grants access to its Data Mailbox.
+    #[builder(default = "\"token1\"")]
+    t2m_token: &'a str,
+    /// The Talk2M API key used to check the user is authorized to use the API.
+    #[builder(default = "\"731e38ec-981f-4f31-9cb5-e87f0d571816\"")]
+    t2m_developer_id: &'a str,
+    /// The policy of retry of the idempotent requests failing on a transient error, which never retries them
+    /// by default.
+    #[builder(default = "RetryPolicy::never()")]
+    retry_policy: RetryPolicy,
+    /// The rate limiter of the requests, which may be shared with other clients.
+    #[builder(setter(strip_option), default = "None")]
+    rate_limiter: Option<RateLimiter>,
+    /// The circuit breaker of the requests to the failing endpoints, which may be shared with other clients.
+    #[builder(setter(strip_option), default = "None")]
+    circuit_breaker: Option<CircuitBreaker>,
+    /// The tower service sending the requests, which sends them with the HTTP client by default.
+    #[builder(setter(strip_option), default = "None")]
+    http_service: Option<HttpService>,
+    /// The failover of the requests to the fallback urls of the API, in order, when the API base url is
+    /// unreachable.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Vec<&'a str>",
+            build = "Failover::new(self.t2m_url.unwrap_or(DEFAULT_DM_URL), &self.failover)"
+        )
+    )]
+    failover: Option<Failover>,
+    /// The observer of the requests, feeding the metrics of the application, which may be shared with other
+    /// clients.
+    #[builder(setter(strip_option), default = "None")]
+    metrics_observer: Option<Arc<dyn MetricsObserver>>,
+    /// The hooks called around each request, in order.
+    #[builder(
+        setter(custom),
+        field(ty = "Vec<Arc<dyn Interceptor>>", build = "self.interceptors.clone()")
+    )]
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    /// The slots of the requests in flight, whose number is unlimited by default.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Option<usize>",
+            build = "self.in_flight.map(|max| Semaphore::new(max.clamp(1, Semaphore::MAX_PERMITS)))"
+        )
+    )]
+    in_flight: Option<Semaphore>,
+    /// The timeouts of the requests, unlimited by default.
+    #[builder(default)]
+    timeouts: Timeouts,
+    /// The headers sent with each request, identifying the application, starting with its User-Agent.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Vec<(&'a str, &'a str)>",
+            build = "default_headers(&self.headers)?"
+        )
+    )]
+    headers: HeaderMap,
+    /// HTTP client to connect to the API, built with the connect and read timeouts, the proxy and the TLS
+    /// settings unless provided.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "HttpClientOptions<'a>",
+            build = "self.http_client.build(self.timeouts.unwrap_or_default())?"
+        )
+    )]
+    http_client: HttpClient,
+}
+
+impl<'a> DmClientBuilder<'a> {
+    http_client_setters!('a, "the data synchronized with `syncdata` and fetched with `getdata`");
+
+    /// Cap the number of requests in flight at once, the other requests waiting for one of them to complete.
+    pub fn max_concurrent_requests(&mut self, max_concurrent_requests: usize) -> &mut Self {
+        self.in_flight = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Fail over to this API base url when the previous ones are unreachable, after those added before.
+    ///
+    /// A request failing to connect to the API is sent again to the next url, and the client keeps sending
+    /// its requests to the url which served it, until it fails in turn.
+    pub fn t2m_fallback_url(&mut self, t2m_fallback_url: &'a str) -> &mut Self {
+        self.failover.push(t2m_fallback_url);
+        self
+    }
+}
+
+impl<'a> DmClient<'a> {
+    /// Return the API base url serving the requests: the primary one, unless the client failed over to one of
+    /// its fallback urls.
+    pub fn serving_t2m_url(&self) -> &str {
+        match self.failover {
+            Some(ref failover) => failover.active_url(),
+            None => self.t2m_url,
+        }
+    }
+
+    /// Return the eWONs pushing their data to the Data Mailbox.
+    ///
+    /// Unlike the eWONs of the M2Web API, the eWONs of the Data Mailbox hold the time of their last push and
+    /// their tags, without their values. Their ids are the ids of the Data Mailbox, not those of M2Web.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::client::DmClientBuilder, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn get_dm_ewons() -> Result<(), error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let ewons = client.get_ewons().await?;
+    ///
+    /// // Do something useful, for example:
+    /// ewons.iter().for_each(|ewon| {
+    ///     println!("{}: {} tags, last push {}", ewon.name, ewon.tag_count(), ewon.last_synchro_date)
+    /// });
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewons(&self) -> Result<Vec<DmEwon>, error::Error> {
+        let api_response: DmEwonsResponse = self.request("getewons", None).await?;
+
+        if api_response.ewons.is_empty() {
+            Err(error::Error {
+                http_status: 204,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::NoContent("No eWON were returned by API".to_string()),
+            })
+        } else {
+            Ok(api_response.ewons)
+        }
+    }
+
+    /// Return the data pushed to the Data Mailbox since the last transaction.
+    ///
+    /// The Data Mailbox keeps track of the data already synchronized with transactions: when a transaction is
+    /// created, the returned data holds its id, which is given to the next synchronization to only return the
+    /// data pushed since. Without the id of the last transaction, the oldest data of the Data Mailbox is
+    /// returned. When the data is too large for a single response, `more_data_available` is set and the next
+    /// synchronization returns the remaining data.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::client::DmClientBuilder, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn sync_dm_data() -> Result<(), error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let mut last_transaction_id = None;
+    /// loop {
+    ///     let data = client.sync_data(last_transaction_id, true).await?;
+    ///
+    ///     // Do something useful, for example:
+    ///     data.ewons
+    ///         .iter()
+    ///         .flat_map(|ewon| ewon.tags.iter().map(move |tag| (ewon, tag)))
+    ///         .for_each(|(ewon, tag)| println!("{}/{}: {} values", ewon.name, tag.name, tag.history.len()));
+    ///
+    ///     last_transaction_id = data.transaction_id;
+    ///     if !data.more_data_available {
+    ///         break;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn sync_data(
+        &self,
+        last_transaction_id: Option<u64>,
+        create_transaction: bool,
+    ) -> Result<SyncData, error::Error> {
+        let last_transaction_id = last_transaction_id.map(|id| id.to_string());
+        let mut query_params = vec![(
+            "createTransaction",
+            match create_transaction {
+                true => "true",
+                false => "false",
+            },
+        )];
+        if let Some(ref last_transaction_id) = last_transaction_id {
+            query_params.push(("lastTransactionId", last_transaction_id));
+        }
+
+        self.request("syncdata", Some(query_params)).await
+    }
+
+    /// Return all the data pushed to the Data Mailbox since the last transaction.
+    ///
+    /// Like `sync_data()`, but call it again as long as more data is available, and merge the pages into a
+    /// single response holding the id of the last transaction. The progress callback is called after each
+    /// page. At most `max_pages` pages are requested: when the limit is reached before all the data is
+    /// synchronized, `more_data_available` remains set on the returned data, and its transaction id allows
+    /// to resume the synchronization.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::{client::DmClientBuilder, data::SyncData}, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn sync_all_dm_data() -> Result<SyncData, error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let data = client
+    ///     .sync_all(None, 100, |progress| println!("{} values received", progress.values))
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn sync_all(
+        &self,
+        last_transaction_id: Option<u64>,
+        max_pages: u32,
+        mut progress: impl FnMut(&PageProgress),
+    ) -> Result<SyncData, error::Error> {
+        let mut all_data = SyncData {
+            transaction_id: last_transaction_id,
+            more_data_available: true,
+            ewons: vec![],
+        };
+        let mut page_progress = PageProgress::default();
+        while all_data.more_data_available && page_progress.pages < max_pages {
+            let data = self.sync_data(all_data.transaction_id, true).await?;
+
+            page_progress.pages += 1;
+            page_progress.values += data.ewons.iter().map(DmEwon::value_count).sum::<usize>();
+            all_data.transaction_id = data.transaction_id.or(all_data.transaction_id);
+            all_data.more_data_available = data.more_data_available;
+            merge_ewons(&mut all_data.ewons, data.ewons);
+            progress(&page_progress);
+        }
+
+        Ok(all_data)
+    }
+
+    /// Return all the historical data stored in the Data Mailbox, selected by a query.
+    ///
+    /// Like `get_data()`, but request the next values as long as more data is available, from the time of
+    /// the latest value received, and merge the pages into a single response. The values received twice,
+    /// at the boundary between two pages, are dropped. The progress callback is called after each page. At
+    /// most `max_pages` pages are requested: when the limit is reached before all the data is received,
+    /// `more_data_available` remains set on the returned data.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::{client::DmClientBuilder, data::HistoricalData, query::DataQuery}, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn get_all_dm_data() -> Result<HistoricalData, error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let data = client
+    ///     .get_all_data(&DataQuery::new().ewon(7), 100, |progress| {
+    ///         println!("{} pages received", progress.pages)
+    ///     })
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_all_data(
+        &self,
+        query: &DataQuery,
+        max_pages: u32,
+        mut progress: impl FnMut(&PageProgress),
+    ) -> Result<HistoricalData, error::Error> {
+        let mut all_data = HistoricalData {
+            more_data_available: true,
+            ewons: vec![],
+        };
+        let mut page_progress = PageProgress::default();
+        let mut boundary = Boundary::default();
+        while all_data.more_data_available && page_progress.pages < max_pages {
+            let page_query = match boundary.time() {
+                Some(time) => query.clone().from(SystemTime::from(time)),
+                None => query.clone(),
+            };
+            let mut data = self.get_data(&page_query).await?;
+            let new_values = boundary.advance(&mut data.ewons);
+
+            page_progress.pages += 1;
+            page_progress.values += new_values;
+            all_data.more_data_available = data.more_data_available;
+            merge_ewons(&mut all_data.ewons, data.ewons);
+            progress(&page_progress);
+            // A page without any new value would be requested again and again.
+            if new_values == 0 {
+                break;
+            }
+        }
+
+        Ok(all_data)
+    }
+
+    /// Return a stream of the values synchronized from the Data Mailbox.
+    ///
+    /// See `SyncStream`: the synchronization resumes from the transaction found in the store, and saves the
+    /// id of each transaction once its values are consumed.
+    pub fn sync_stream<'c, S: CheckpointStore + Send + 'c>(
+        &'c self,
+        store: S,
+    ) -> SyncStream<'c, 'a, S> {
+        SyncStream::new(self, store)
+    }
+
+    /// Return the historical data stored in the Data Mailbox, selected by a query.
+    ///
+    /// Unlike `sync_data()`, the data is selected by eWON, tag and time window, and the transactions are left
+    /// untouched, which suits the ad-hoc queries.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::{client::DmClientBuilder, data::HistoricalData, query::DataQuery}, m2web::error};
+    /// # use std::time::{Duration, SystemTime};
+    /// # #[tokio::test]
+    /// # async fn get_dm_data() -> Result<HistoricalData, error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let query = DataQuery::new()
+    ///     .ewon(7)
+    ///     .window(SystemTime::now() - Duration::from_secs(24 * 3600), SystemTime::now());
+    /// let data = client.get_data(&query).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_data(&self, query: &DataQuery) -> Result<HistoricalData, error::Error> {
+        let query_params = query.query_params();
+
+        self.request(
+            "getdata",
+            Some(
+                query_params
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_str()))
+                    .collect(),
+            ),
+        )
+        .await
+    }
+
+    /// Delete data from the Data Mailbox.
+    ///
+    /// Purge the data already synchronized, up to a transaction, or clean all the data of an eWON or of the
+    /// whole account. The deletion can not be undone, hence the confirmation.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::{client::DmClientBuilder, query::DeleteScope}, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn purge_dm_data() -> Result<(), error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let data = client.sync_data(None, true).await?;
+    ///
+    /// // Once the data is safely stored, for example:
+    /// if let Some(transaction_id) = data.transaction_id {
+    ///     client
+    ///         .delete(DeleteScope::UpToTransaction(transaction_id).confirm())
+    ///         .await?;
+    /// }
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn delete(&self, deletion: ConfirmedDelete) -> Result<(), error::Error> {
+        let query_params = deletion.query_params();
+
+        self.request::<serde_json::Value>(
+            "delete",
+            Some(
+                query_params
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_str()))
+                    .collect(),
+            ),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Return the usage of the Data Mailbox of the account.
+    ///
+    /// The Data Mailbox drops the values it can not store anymore: checking its usage allows to alert before
+    /// it fills up.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::client::DmClientBuilder, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn check_dm_usage() -> Result<(), error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let status = client.get_status().await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{} values of {} eWONs stored", status.history_count, status.ewon_count);
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_status(&self) -> Result<MailboxStatus, error::Error> {
+        self.request("getstatus", None).await
+    }
+
+    /// Request an endpoint of the DMWeb API and decode its response.
+    ///
+    /// The authentication parameters are added to the given parameters. An unsuccessful response is mapped
+    /// to an error; a successful one is decoded as a whole into the requested type, which allows to request
+    /// the endpoints not covered by this crate.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::client::DmClientBuilder, m2web::error};
+    /// # #[tokio::test]
+    /// # async fn request_dm_endpoint() -> Result<(), error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let status: serde_json::Value = client.request("getstatus", None).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(endpoint = url_path)))]
+    pub async fn request<T: DeserializeOwned>(
+        &self,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<T, error::Error> {
+        // Check if the endpoint is provided.
+        if url_path.is_empty() {
+            return Err(error::Error {
+                http_status: 500,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::InternalError("no API endpoint provided".to_string()),
+            });
+        }
+        let mut query_params = vec![
+            ("t2mtoken", self.t2m_token),
+            ("t2mdevid", self.t2m_developer_id),
+        ];
+        query_params.extend(req_query_params.unwrap_or_default());
+
+        let request = self.timeouts.apply(
+            self.http_client
+                .get(format!("{}/{}", self.t2m_url, url_path))
+                .headers(self.headers.clone())
+                .query(&query_params),
+        );
+        let _in_flight = match self.in_flight {
+            Some(ref in_flight) => in_flight.acquire().await.ok(),
+            None => None,
+        };
+        // The deletions are not retried, a retry could delete the values pushed in the meantime.
+        let http_response = self
+            .retry_policy
+            .send(
+                request,
+                url_path != "delete",
+                Middleware {
+                    rate_limiter: self.rate_limiter.as_ref(),
+                    circuit_breaker: self.circuit_breaker.as_ref(),
+                    http_service: self.http_service.as_ref(),
+                    failover: self.failover.as_ref(),
+                    metrics_observer: self.metrics_observer.as_deref(),
+                    interceptors: &self.interceptors,
+                },
+            )
+            .await?;
+        let http_status = http_response.status();
+        let http_body = http_response.text().await?;
+        let api_response = serde_json::from_str::<serde_json::Value>(&http_body)
+            .map_err(|err| error::response_parsing_error(err, url_path, &http_body))?;
+
+        match api_response["success"].as_bool() {
+            Some(true) => Ok(serde_json::from_value(api_response)
+                .map_err(|err| error::response_parsing_error(err, url_path, &http_body))?),
+            _ => Err(error::Error::from_api_response(
+                http_status,
+                api_response["code"]
+                    .as_u64()
+                    .and_then(|api_code| u16::try_from(api_code).ok()),
+                api_response["message"].as_str().unwrap_or_default(),
+            )),
+        }
+    }
+}
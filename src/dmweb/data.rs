@@ -0,0 +1,388 @@
+use crate::m2web::tag;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+
+/// Value of a tag, typed after its JSON value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TagValue {
+    /// The value of a boolean tag.
+    Bool(bool),
+    /// The value of an integer tag.
+    Int(i64),
+    /// The value of a floating point tag.
+    Float(f64),
+    /// The value of a string tag.
+    String(String),
+}
+
+impl TagValue {
+    /// Return the value as a number, the booleans being 0 or 1, unless it is a string.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TagValue::Bool(value) => Some(f64::from(u8::from(*value))),
+            TagValue::Int(value) => Some(*value as f64),
+            TagValue::Float(value) => Some(*value),
+            TagValue::String(_) => None,
+        }
+    }
+}
+
+/// Display the value of a tag.
+impl fmt::Display for TagValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagValue::Bool(value) => write!(f, "{}", value),
+            TagValue::Int(value) => write!(f, "{}", value),
+            TagValue::Float(value) => write!(f, "{}", value),
+            TagValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Value of a tag stored in the Data Mailbox.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DataPoint {
+    /// The time at which the value has been logged.
+    #[serde(rename = "date")]
+    pub ts: DateTime<Utc>,
+    /// The logged value.
+    pub value: TagValue,
+    /// The quality of the value, for example "good".
+    #[serde(default)]
+    pub quality: String,
+}
+
+/// Convert an instant value read through the M2Web API.
+///
+/// The numbers are converted to floating point numbers, the M2Web API not telling them apart. The OPC
+/// quality is converted to "good", "uncertain" or "bad".
+impl From<&tag::TagValue> for DataPoint {
+    fn from(tag: &tag::TagValue) -> Self {
+        DataPoint {
+            ts: tag.timestamp.into(),
+            value: match tag.value {
+                tag::TagData::Number(number) => TagValue::Float(number),
+                tag::TagData::Text(ref text) => TagValue::String(text.to_owned()),
+            },
+            quality: match tag.quality & 0xC0 {
+                0xC0 => "good",
+                0x40 => "uncertain",
+                _ => "bad",
+            }
+            .to_string(),
+        }
+    }
+}
+
+/// Value of a tag, with the eWON and the tag it belongs to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DataRecord {
+    /// The id of the eWON in the Data Mailbox.
+    pub ewon_id: u32,
+    /// The name of the eWON.
+    pub ewon_name: String,
+    /// The id of the tag in the Data Mailbox.
+    pub tag_id: u32,
+    /// The name of the tag.
+    pub tag_name: String,
+    /// The value of the tag.
+    #[serde(flatten)]
+    pub data_point: DataPoint,
+}
+
+/// Tag of an eWON, with its values stored in the Data Mailbox.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DmTag {
+    /// The id of the tag in the Data Mailbox.
+    pub id: u32,
+    /// The id of the tag on the eWON.
+    pub ewon_tag_id: u32,
+    /// The name of the tag.
+    pub name: String,
+    /// The description of the tag.
+    pub description: String,
+    /// The type of the values of the tag, for example "Float" or "Boolean".
+    pub data_type: String,
+    /// The last value of the tag, if any.
+    pub value: Option<TagValue>,
+    /// The quality of the last value of the tag.
+    pub quality: String,
+    /// The values of the tag, oldest first.
+    pub history: Vec<DataPoint>,
+}
+
+/// eWON pushing its data to the Data Mailbox.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DmEwon {
+    /// The id of the eWON in the Data Mailbox.
+    pub id: u32,
+    /// The name of the eWON.
+    pub name: String,
+    /// The time at which the eWON last pushed its data, as an ISO 8601 UTC date.
+    pub last_synchro_date: String,
+    /// The tags of the eWON.
+    pub tags: Vec<DmTag>,
+}
+
+impl DmEwon {
+    /// Return the values of all the tags of the eWON, each with the eWON and the tag it belongs to.
+    pub fn into_records(self) -> impl Iterator<Item = DataRecord> {
+        let (ewon_id, ewon_name) = (self.id, self.name);
+        self.tags.into_iter().flat_map(move |tag| {
+            let ewon_name = ewon_name.to_owned();
+            let (tag_id, tag_name) = (tag.id, tag.name);
+            tag.history.into_iter().map(move |data_point| DataRecord {
+                ewon_id,
+                ewon_name: ewon_name.to_owned(),
+                tag_id,
+                tag_name: tag_name.to_owned(),
+                data_point,
+            })
+        })
+    }
+
+    /// Return the number of tags of the eWON.
+    pub fn tag_count(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Return the number of values of all the tags of the eWON.
+    pub fn value_count(&self) -> usize {
+        self.tags.iter().map(|tag| tag.history.len()).sum()
+    }
+}
+
+/// Merge the eWONs of a page of data into the eWONs of the previous pages.
+///
+/// The eWONs and their tags are matched by their id: the values of a tag are appended to its previous
+/// values, while the eWONs and tags which are new are appended as is.
+pub(crate) fn merge_ewons(ewons: &mut Vec<DmEwon>, page: Vec<DmEwon>) {
+    for page_ewon in page {
+        let ewon = match ewons.iter_mut().find(|ewon| ewon.id == page_ewon.id) {
+            Some(ewon) => ewon,
+            None => {
+                ewons.push(page_ewon);
+                continue;
+            }
+        };
+        if !page_ewon.last_synchro_date.is_empty() {
+            ewon.last_synchro_date = page_ewon.last_synchro_date;
+        }
+        for page_tag in page_ewon.tags {
+            match ewon.tags.iter_mut().find(|tag| tag.id == page_tag.id) {
+                Some(tag) => {
+                    tag.history.extend(page_tag.history);
+                    if page_tag.value.is_some() {
+                        tag.value = page_tag.value;
+                        tag.quality = page_tag.quality;
+                    }
+                }
+                None => ewon.tags.push(page_tag),
+            }
+        }
+    }
+}
+
+/// Time of the latest values received, with the tags which have a value at this time.
+///
+/// The next values are requested from this time, so the values at this time are received again: they are
+/// dropped, unless they belong to a tag without any value at this time yet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Boundary {
+    /// The time of the latest values received, if any.
+    time: Option<DateTime<Utc>>,
+    /// The ids of the eWONs and tags which have a value at this time.
+    tags: HashSet<(u32, u32)>,
+}
+
+impl Boundary {
+    /// Return the time of the latest values received, if any.
+    pub(crate) fn time(&self) -> Option<DateTime<Utc>> {
+        self.time
+    }
+
+    /// Drop the values already received, then move the boundary to the latest values kept.
+    ///
+    /// Return the number of values kept.
+    pub(crate) fn advance(&mut self, ewons: &mut [DmEwon]) -> usize {
+        let mut kept = vec![];
+        for ewon in ewons.iter_mut() {
+            for tag in ewon.tags.iter_mut() {
+                let key = (ewon.id, tag.id);
+                tag.history.retain(|data_point| match self.time {
+                    Some(time) => {
+                        data_point.ts > time || (data_point.ts == time && !self.tags.contains(&key))
+                    }
+                    None => true,
+                });
+                kept.extend(tag.history.iter().map(|data_point| (data_point.ts, key)));
+            }
+        }
+
+        let count = kept.len();
+        for (time, key) in kept {
+            if self.time.is_none_or(|boundary_time| time > boundary_time) {
+                self.time = Some(time);
+                self.tags.clear();
+            }
+            if self.time == Some(time) {
+                self.tags.insert(key);
+            }
+        }
+
+        count
+    }
+}
+
+/// Progress of a request spanning several pages of data.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PageProgress {
+    /// The number of pages received so far.
+    pub pages: u32,
+    /// The number of values received so far.
+    pub values: usize,
+}
+
+/// Response from the DMWeb API listing the eWONs.
+#[derive(Debug, Deserialize)]
+pub(crate) struct DmEwonsResponse {
+    /// The eWONs pushing their data to the Data Mailbox.
+    #[serde(default)]
+    pub(crate) ewons: Vec<DmEwon>,
+}
+
+/// Data returned by the Data Mailbox since the last transaction.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncData {
+    /// The id of the transaction created by this synchronization, to give to the next synchronization.
+    pub transaction_id: Option<u64>,
+    /// Indicates if more data is available, which requires another synchronization.
+    pub more_data_available: bool,
+    /// The eWONs which pushed data since the last transaction.
+    pub ewons: Vec<DmEwon>,
+}
+
+impl SyncData {
+    /// Return the values of all the tags of all the eWONs.
+    pub fn into_records(self) -> Vec<DataRecord> {
+        self.ewons
+            .into_iter()
+            .flat_map(DmEwon::into_records)
+            .collect()
+    }
+}
+
+/// Historical data selected in the Data Mailbox.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistoricalData {
+    /// Indicates if more data matches the query, which was not returned to honor the limit.
+    pub more_data_available: bool,
+    /// The selected eWONs, with the selected values of their tags.
+    pub ewons: Vec<DmEwon>,
+}
+
+impl HistoricalData {
+    /// Return the selected values of all the tags of all the eWONs.
+    pub fn into_records(self) -> Vec<DataRecord> {
+        self.ewons
+            .into_iter()
+            .flat_map(DmEwon::into_records)
+            .collect()
+    }
+}
+
+/// Usage of the Data Mailbox of the account.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MailboxStatus {
+    /// The number of values stored.
+    pub history_count: u64,
+    /// The number of eWONs with stored values.
+    pub ewon_count: u32,
+    /// The time of the oldest stored value, as an ISO 8601 UTC date, empty if none.
+    pub first_history_date: String,
+    /// The time of the latest stored value, as an ISO 8601 UTC date, empty if none.
+    pub last_history_date: String,
+    /// The maximum number of values the Data Mailbox can store, if limited.
+    pub history_quota: Option<u64>,
+}
+
+impl MailboxStatus {
+    /// Return the number of values which can still be stored, if limited.
+    pub fn remaining_quota(&self) -> Option<u64> {
+        self.history_quota
+            .map(|quota| quota.saturating_sub(self.history_count))
+    }
+
+    /// Return the share of the quota used, from 0 to 1, if limited.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::dmweb::data::MailboxStatus;
+    /// let status = MailboxStatus {
+    ///     history_count: 900_000,
+    ///     history_quota: Some(1_000_000),
+    ///     ..MailboxStatus::default()
+    /// };
+    ///
+    /// if status.usage().is_some_and(|usage| usage >= 0.9) {
+    ///     println!("Data Mailbox almost full: {:?} values left", status.remaining_quota());
+    /// }
+    /// ```
+    pub fn usage(&self) -> Option<f64> {
+        match self.history_quota {
+            Some(0) => Some(1.0),
+            Some(quota) => Some(self.history_count as f64 / quota as f64),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::data::{DataPoint, TagValue};
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn data_point_ok() {
+        let data_points: Vec<DataPoint> = serde_json::from_value(json!([
+            {"date": "2023-11-14T22:13:20Z", "value": true, "quality": "good"},
+            {"date": "2023-11-14T22:13:21Z", "value": 42},
+            {"date": "2023-11-14T22:13:22Z", "value": 21.0},
+            {"date": "2023-11-14T22:13:23Z", "value": "PET-500"},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            data_points[0].ts,
+            Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap()
+        );
+        assert_eq!(data_points[0].value, TagValue::Bool(true));
+        assert_eq!(data_points[0].quality, "good");
+        assert_eq!(data_points[1].value, TagValue::Int(42));
+        assert_eq!(data_points[1].quality, "");
+        assert_eq!(data_points[2].value, TagValue::Float(21.0));
+        assert_eq!(
+            data_points[3].value,
+            TagValue::String("PET-500".to_string())
+        );
+        assert_eq!(data_points[0].value.as_f64(), Some(1.0));
+        assert_eq!(data_points[3].value.as_f64(), None);
+        assert_eq!(data_points[3].value.to_string(), "PET-500");
+    }
+
+    #[test]
+    fn data_point_ko() {
+        let err = serde_json::from_value::<DataPoint>(json!({"date": "yesterday", "value": 1}))
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "input contains invalid characters");
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    dmweb::{
+        data::{DataRecord, TagValue},
+        sink::AsyncDataSink,
+    },
+    m2web::{clock, error},
+};
+use derive_builder::Builder;
+use reqwest::{header, Client as HttpClient, StatusCode};
+use std::time::Duration;
+
+/// Return the value as a line of the InfluxDB line protocol.
+///
+/// The line is made of the measurement, the `ewon`, `ewon_id`, `tag`, `tag_id` and `quality` tags, the
+/// `value` field and the time in milliseconds since the Unix epoch. The integers are written as integer
+/// fields, the strings as string fields.
+///
+/// # Example
+/// ```rust
+/// # use libewon::dmweb::{data::{DataPoint, DataRecord, TagValue}, influx::line_protocol};
+/// # use chrono::{TimeZone, Utc};
+/// let record = DataRecord {
+///     ewon_id: 7,
+///     ewon_name: "bea-test".to_string(),
+///     tag_id: 12,
+///     tag_name: "Temperature".to_string(),
+///     data_point: DataPoint {
+///         ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+///         value: TagValue::Float(21.5),
+///         quality: "good".to_string(),
+///     },
+/// };
+///
+/// assert_eq!(
+///     line_protocol("ewon", &record),
+///     "ewon,ewon=bea-test,ewon_id=7,tag=Temperature,tag_id=12,quality=good value=21.5 1700000000000"
+/// );
+/// ```
+pub fn line_protocol(measurement: &str, record: &DataRecord) -> String {
+    let value = match record.data_point.value {
+        TagValue::Bool(value) => value.to_string(),
+        TagValue::Int(value) => format!("{}i", value),
+        TagValue::Float(value) => value.to_string(),
+        TagValue::String(ref value) => {
+            format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    };
+
+    format!(
+        "{},ewon={},ewon_id={},tag={},tag_id={},quality={} value={} {}",
+        escape(measurement, &[',', ' ']),
+        escape(&record.ewon_name, &[',', '=', ' ']),
+        record.ewon_id,
+        escape(&record.tag_name, &[',', '=', ' ']),
+        record.tag_id,
+        escape(&record.data_point.quality, &[',', '=', ' ']),
+        value,
+        record.data_point.ts.timestamp_millis(),
+    )
+}
+
+/// Escape the special characters of a name of the line protocol with backslashes.
+fn escape(name: &str, special_chars: &[char]) -> String {
+    name.chars().fold(String::new(), |mut escaped, char| {
+        if special_chars.contains(&char) {
+            escaped.push('\\');
+        }
+        escaped.push(char);
+        escaped
+    })
+}
+
+/// Sink writing the values to an InfluxDB v2 bucket.
+///
+/// The values are converted to the line protocol, see `line_protocol()`, and sent to the write endpoint of
+/// the API by batches. A batch is sent once it holds `batch_size` values, and when the sink is flushed. The
+/// batches throttled by InfluxDB or failing with a server error are sent again, after a delay doubling at
+/// each retry unless the response sets it. A batch failing after its retries is kept, to be sent on the next
+/// flush.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, influx::InfluxSinkBuilder, sync::FileCheckpoint}, m2web::error};
+/// # #[tokio::test]
+/// # async fn write_dm_data_to_influx() -> Result<u64, error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut sink = InfluxSinkBuilder::default()
+///     .url("http://localhost:8086")
+///     .org("acme")
+///     .bucket("ewons")
+///     .token("JpXVd0xkZdBcLm3BYFbZ2bB0XUsvrP0wWBBYWkQR")
+///     .build()
+///     .unwrap();
+///
+/// client
+///     .sync_stream(FileCheckpoint::new("values.transaction"))
+///     .write_to(&mut sink)
+///     .await
+/// # }
+/// ```
+#[derive(Builder)]
+pub struct InfluxSink<'a> {
+    /// The base url of the InfluxDB API.
+    url: &'a str,
+    /// The organization owning the bucket.
+    org: &'a str,
+    /// The bucket the values are written to.
+    bucket: &'a str,
+    /// The API token allowed to write to the bucket.
+    token: &'a str,
+    /// The measurement of the values.
+    #[builder(default = "\"ewon\"")]
+    measurement: &'a str,
+    /// The number of values above which a batch is sent.
+    #[builder(default = "5000")]
+    batch_size: usize,
+    /// The number of times a failing batch is sent again.
+    #[builder(default = "3")]
+    max_retries: u32,
+    /// The delay before sending a failing batch again for the first time.
+    #[builder(default = "Duration::from_secs(1)")]
+    retry_delay: Duration,
+    /// The lines of the batch to send.
+    #[builder(setter(skip))]
+    lines: String,
+    /// The number of values in the batch to send.
+    #[builder(setter(skip))]
+    pending: usize,
+    /// HTTP client to connect to the API.
+    #[builder(setter(skip), default = "reqwest::Client::new()")]
+    http_client: HttpClient,
+}
+
+impl<'a> InfluxSink<'a> {
+    /// Return the number of values waiting to be sent.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Send the batch, retrying on the transient errors.
+    async fn send_batch(&mut self) -> Result<(), error::Error> {
+        if self.pending == 0 {
+            return Ok(());
+        }
+
+        let mut retry_delay = self.retry_delay;
+        let mut retries = 0;
+        loop {
+            let (err, delay) = match self.post_batch().await {
+                Ok(()) => {
+                    self.lines.clear();
+                    self.pending = 0;
+                    return Ok(());
+                }
+                Err((err, delay)) => (err, delay),
+            };
+            if retries >= self.max_retries || !err.is_retryable() {
+                return Err(err);
+            }
+            clock::sleep(delay.unwrap_or(retry_delay)).await;
+            retry_delay *= 2;
+            retries += 1;
+        }
+    }
+
+    /// Post the batch to the write endpoint, and return the error and the delay set by the API, if any.
+    async fn post_batch(&self) -> Result<(), (error::Error, Option<Duration>)> {
+        let http_response = self
+            .http_client
+            .post(format!("{}/api/v2/write", self.url.trim_end_matches('/')))
+            .query(&[
+                ("org", self.org),
+                ("bucket", self.bucket),
+                ("precision", "ms"),
+            ])
+            .header(header::AUTHORIZATION, format!("Token {}", self.token))
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(self.lines.to_owned())
+            .send()
+            .await
+            .map_err(|err| (error::Error::from(err), None))?;
+        let http_status = http_response.status();
+        if http_status.is_success() {
+            return Ok(());
+        }
+
+        let retry_after = http_response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|retry_after| retry_after.to_str().ok())
+            .and_then(|retry_after| retry_after.parse().ok())
+            .map(Duration::from_secs);
+        let http_body = http_response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&http_body)
+            .ok()
+            .and_then(|body| body["message"].as_str().map(str::to_owned))
+            .unwrap_or(http_body);
+
+        Err((influx_error(http_status, message), retry_after))
+    }
+}
+
+impl<'a> AsyncDataSink for InfluxSink<'a> {
+    async fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        for record in records {
+            self.lines
+                .push_str(&line_protocol(self.measurement, record));
+            self.lines.push('\n');
+            self.pending += 1;
+            if self.pending >= self.batch_size {
+                self.send_batch().await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), error::Error> {
+        self.send_batch().await
+    }
+}
+
+/// Map the HTTP status code and the message of an unsuccessful InfluxDB response to an error.
+fn influx_error(http_status: StatusCode, message: String) -> error::Error {
+    error::Error {
+        http_status: http_status.as_u16(),
+        api_code: None,
+        source: None,
+        kind: match http_status {
+            StatusCode::TOO_MANY_REQUESTS => error::ErrorKind::Throttled(message),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                error::ErrorKind::InvalidCredentials(message)
+            }
+            _ if http_status.is_client_error() => {
+                error::ErrorKind::MissingOrWrongParameter(message)
+            }
+            _ => error::ErrorKind::UnknownError(message),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        influx::line_protocol,
+    };
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn line_protocol_ok() {
+        let record = |tag_name: &str, value| DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea test".to_string(),
+            tag_id: 12,
+            tag_name: tag_name.to_string(),
+            data_point: DataPoint {
+                ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        };
+
+        assert_eq!(
+            line_protocol("eWON values", &record("Count", TagValue::Int(42))),
+            "eWON\\ values,ewon=bea\\ test,ewon_id=7,tag=Count,tag_id=12,quality=good value=42i 1700000000000"
+        );
+        assert_eq!(
+            line_protocol("ewon", &record("Recipe,a=b", TagValue::String("PET \"500\"".to_string()))),
+            "ewon,ewon=bea\\ test,ewon_id=7,tag=Recipe\\,a\\=b,tag_id=12,quality=good value=\"PET \\\"500\\\"\" 1700000000000"
+        );
+        assert_eq!(
+            line_protocol("ewon", &record("Running", TagValue::Bool(true))),
+            "ewon,ewon=bea\\ test,ewon_id=7,tag=Running,tag_id=12,quality=good value=true 1700000000000"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        sink::DataSink,
+    },
+    m2web::{error, tag},
+};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+/// Latest time and value of each tag, by eWON name and tag name.
+type ValuesByTag = HashMap<(String, String), (DateTime<Utc>, TagValue)>;
+
+/// Cache of the latest value of each tag, by eWON.
+///
+/// The cache is kept up to date by a `SyncStream`, see `SyncStream::latest_values()`, by the instant values
+/// read through the M2Web API, see `update_tags()`, or as a `DataSink`. An older value never replaces a
+/// newer one, so the sources can be mixed. The clones of a cache share its values, which lets a task
+/// serve them while another one updates them.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, latest::LatestValues, sync::MemoryCheckpoint}, m2web::error};
+/// # use futures::StreamExt;
+/// # #[tokio::test]
+/// # async fn cache_latest_values() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let latest_values = LatestValues::new();
+/// let mut records = client
+///     .sync_stream(MemoryCheckpoint::new(None))
+///     .latest_values(latest_values.clone());
+///
+/// while let Some(record) = records.next().await {
+///     record?;
+///     if let Some((ts, value)) = latest_values.latest("bea-test", "Temperature") {
+///         println!("{}: {}", ts, value);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LatestValues {
+    /// The latest time and value of each tag, by eWON name and tag name.
+    values: Arc<RwLock<ValuesByTag>>,
+}
+
+impl LatestValues {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        LatestValues::default()
+    }
+
+    /// Return the time and the latest value of the tag of the eWON, if any.
+    pub fn latest(&self, ewon: &str, tag: &str) -> Option<(DateTime<Utc>, TagValue)> {
+        self.values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(ewon.to_owned(), tag.to_owned()))
+            .cloned()
+    }
+
+    /// Return the names of the tags of the eWON and their latest time and value, sorted by name.
+    pub fn ewon(&self, ewon: &str) -> Vec<(String, DateTime<Utc>, TagValue)> {
+        let mut tags = self
+            .values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|((ewon_name, _), _)| ewon_name == ewon)
+            .map(|((_, tag_name), (ts, value))| (tag_name.to_owned(), *ts, value.to_owned()))
+            .collect::<Vec<_>>();
+        tags.sort_by(|(tag_name, _, _), (other_name, _, _)| tag_name.cmp(other_name));
+
+        tags
+    }
+
+    /// Return the number of tags cached.
+    pub fn len(&self) -> usize {
+        self.values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Return `true` if no value is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cache the values synchronized from the Data Mailbox, unless newer values are cached.
+    pub fn update(&self, records: &[DataRecord]) {
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        for record in records {
+            cache_value(
+                &mut values,
+                (record.ewon_name.to_owned(), record.tag_name.to_owned()),
+                record.data_point.ts,
+                &record.data_point.value,
+            );
+        }
+    }
+
+    /// Cache the instant values of the tags of an eWON, read through the M2Web API.
+    ///
+    /// The values are converted as data points, see `DataPoint::from()`.
+    pub fn update_tags(&self, ewon: &str, tags: &[tag::TagValue]) {
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        for tag in tags {
+            let data_point = DataPoint::from(tag);
+            cache_value(
+                &mut values,
+                (ewon.to_owned(), tag.name.to_owned()),
+                data_point.ts,
+                &data_point.value,
+            );
+        }
+    }
+}
+
+impl DataSink for LatestValues {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        self.update(records);
+
+        Ok(())
+    }
+}
+
+/// Cache the value of the tag, unless a newer value is cached.
+fn cache_value(
+    values: &mut ValuesByTag,
+    key: (String, String),
+    ts: DateTime<Utc>,
+    value: &TagValue,
+) {
+    match values.get(&key) {
+        Some((cached_ts, _)) if *cached_ts > ts => (),
+        _ => {
+            values.insert(key, (ts, value.to_owned()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dmweb::{
+            data::{DataPoint, DataRecord, TagValue},
+            latest::LatestValues,
+        },
+        m2web::tag,
+    };
+    use chrono::{TimeZone, Utc};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn latest_values_ok() {
+        let record = |tag_name: &str, secs, value| DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: tag_name.to_string(),
+            data_point: DataPoint {
+                ts: Utc.timestamp_opt(secs, 0).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        };
+        let latest_values = LatestValues::new();
+        let shared = latest_values.clone();
+
+        latest_values.update(&[
+            record("Temperature", 1700000060, TagValue::Float(22.5)),
+            record("Temperature", 1700000000, TagValue::Float(21.5)),
+            record("Running", 1700000000, TagValue::Bool(true)),
+        ]);
+        shared.update_tags(
+            "bea-test",
+            &[tag::TagValue {
+                id: 3,
+                name: "Recipe".to_string(),
+                value: tag::TagData::Text("PET-500".to_string()),
+                alarm_status: 0,
+                quality: 192,
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000120),
+            }],
+        );
+
+        assert_eq!(latest_values.len(), 3);
+        assert_eq!(
+            latest_values.latest("bea-test", "Temperature"),
+            Some((
+                Utc.timestamp_opt(1700000060, 0).unwrap(),
+                TagValue::Float(22.5)
+            ))
+        );
+        assert_eq!(
+            latest_values.latest("bea-test", "Recipe"),
+            Some((
+                Utc.timestamp_opt(1700000120, 0).unwrap(),
+                TagValue::String("PET-500".to_string())
+            ))
+        );
+        assert_eq!(latest_values.latest("bea-prod", "Temperature"), None);
+        assert_eq!(
+            latest_values
+                .ewon("bea-test")
+                .iter()
+                .map(|(tag_name, _, _)| tag_name.as_str())
+                .collect::<Vec<_>>(),
+            ["Recipe", "Running", "Temperature"]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+pub mod client;
+pub mod data;
+#[cfg(feature = "influx")]
+pub mod influx;
+pub mod latest;
+#[cfg(feature = "arrow")]
+pub mod parquet;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod query;
+#[cfg(feature = "s3")]
+pub mod s3;
+#[cfg(feature = "scheduler")]
+pub mod scheduler;
+pub mod sink;
+pub mod sync;
@@ -0,0 +1,232 @@
+use crate::{
+    dmweb::{
+        data::{DataRecord, TagValue},
+        sink::DataSink,
+    },
+    m2web::error,
+};
+use arrow::{
+    array::{ArrayRef, Float64Array, StringArray, TimestampMillisecondArray, UInt32Array},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use parquet::arrow::ArrowWriter;
+use std::{io::Write, sync::Arc};
+
+/// Return the schema of the values written by a `ParquetSink`.
+///
+/// Each value is a row, with the name and the id of its eWON and tag, its time in milliseconds since the Unix
+/// epoch in UTC, and its quality. The numbers and booleans are written to the `value` column, as floating
+/// point numbers, while the strings are written to the `text_value` column; the other column is null.
+pub fn data_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("ewon", DataType::Utf8, false),
+        Field::new("ewon_id", DataType::UInt32, false),
+        Field::new("tag", DataType::Utf8, false),
+        Field::new("tag_id", DataType::UInt32, false),
+        Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("value", DataType::Float64, true),
+        Field::new("text_value", DataType::Utf8, true),
+        Field::new("quality", DataType::Utf8, false),
+    ]))
+}
+
+/// Return the values as an Arrow record batch, following the schema of `data_schema()`.
+pub fn to_record_batch(records: &[DataRecord]) -> Result<RecordBatch, error::Error> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|record| &record.ewon_name),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            records.iter().map(|record| record.ewon_id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|record| &record.tag_name),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            records.iter().map(|record| record.tag_id),
+        )),
+        Arc::new(
+            TimestampMillisecondArray::from_iter_values(
+                records
+                    .iter()
+                    .map(|record| record.data_point.ts.timestamp_millis()),
+            )
+            .with_timezone("UTC"),
+        ),
+        Arc::new(Float64Array::from_iter(
+            records
+                .iter()
+                .map(|record| record.data_point.value.as_f64()),
+        )),
+        Arc::new(StringArray::from_iter(records.iter().map(
+            |record| match record.data_point.value {
+                TagValue::String(ref text) => Some(text),
+                _ => None,
+            },
+        ))),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|record| &record.data_point.quality),
+        )),
+    ];
+
+    RecordBatch::try_new(data_schema(), columns).map_err(arrow_error)
+}
+
+/// Sink writing the values to a Parquet file.
+///
+/// The values follow the schema of `data_schema()`. Each flush closes a row group, so the values of each
+/// response of the API are held by their own row groups. The file is only readable once the sink is
+/// closed, which writes its footer.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, parquet::ParquetSink, sync::FileCheckpoint}, m2web::error};
+/// # use std::fs::File;
+/// # #[tokio::test]
+/// # async fn write_dm_data_to_parquet() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut sink = ParquetSink::new(File::create("values.parquet")?)?;
+///
+/// client
+///     .sync_stream(FileCheckpoint::new("values.transaction"))
+///     .write_to(&mut sink)
+///     .await?;
+/// sink.close()?;
+/// # }
+/// ```
+pub struct ParquetSink<W: Write + Send> {
+    /// The writer of the Parquet file.
+    writer: ArrowWriter<W>,
+}
+
+impl<W: Write + Send> ParquetSink<W> {
+    /// Create a sink writing the values to a Parquet file.
+    pub fn new(writer: W) -> Result<Self, error::Error> {
+        Ok(ParquetSink {
+            writer: ArrowWriter::try_new(writer, data_schema(), None).map_err(parquet_error)?,
+        })
+    }
+
+    /// Write the footer of the Parquet file, and return its writer.
+    pub fn close(self) -> Result<W, error::Error> {
+        self.writer.into_inner().map_err(parquet_error)
+    }
+}
+
+impl<W: Write + Send> DataSink for ParquetSink<W> {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.writer
+            .write(&to_record_batch(records)?)
+            .map_err(parquet_error)
+    }
+
+    fn flush(&mut self) -> Result<(), error::Error> {
+        self.writer.flush().map_err(parquet_error)
+    }
+}
+
+/// Map an error of Arrow to an internal error.
+fn arrow_error(error: arrow::error::ArrowError) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("Arrow error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+/// Map an error of Parquet to an internal error.
+fn parquet_error(error: parquet::errors::ParquetError) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("Parquet error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        parquet::{data_schema, ParquetSink},
+        sink::DataSink,
+    };
+    use arrow::array::{Float64Array, StringArray, TimestampMillisecondArray};
+    use chrono::{TimeZone, Utc};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::fs::{self, File};
+
+    #[test]
+    fn parquet_sink_ok() {
+        let record = |tag_name: &str, value| DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: tag_name.to_string(),
+            data_point: DataPoint {
+                ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        };
+        let path = std::env::temp_dir().join(format!("libewon-{}.parquet", std::process::id()));
+
+        let mut sink = ParquetSink::new(File::create(&path).unwrap()).unwrap();
+        sink.write(&[
+            record("Temperature", TagValue::Float(21.5)),
+            record("Running", TagValue::Bool(true)),
+        ])
+        .unwrap();
+        sink.flush().unwrap();
+        sink.write(&[record("Recipe", TagValue::String("PET-500".to_string()))])
+            .unwrap();
+        sink.close().unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(builder.metadata().num_row_groups(), 2);
+        let batches = builder
+            .with_batch_size(2)
+            .build()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].schema(), data_schema());
+        assert_eq!(batches[0].num_rows(), 2);
+        let values = batches[0]
+            .column_by_name("value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(values.value(0), 21.5);
+        assert_eq!(values.value(1), 1.0);
+        let times = batches[0]
+            .column_by_name("ts")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(times.value(0), 1700000000000);
+        let text_values = batches[1]
+            .column_by_name("text_value")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(text_values.value(0), "PET-500");
+        assert!(batches[1].column_by_name("value").unwrap().is_null(0));
+    }
+}
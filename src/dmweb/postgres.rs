@@ -0,0 +1,227 @@
+use crate::{
+    dmweb::{
+        data::{DataRecord, TagValue},
+        sink::AsyncDataSink,
+    },
+    m2web::error,
+};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+/// The default table the values are written to.
+pub const DEFAULT_TABLE: &str = "ewon_values";
+
+/// Sink writing the values to a PostgreSQL table.
+///
+/// The table holds a row per value, with its `time`, the id and the name of its eWON (`ewon_id`, `ewon`)
+/// and of its tag (`tag_id`, `tag`), its `value` as a floating point number or its `text_value` for the
+/// strings, and its `quality`. Its primary key is made of the ids and of the time, so a value synchronized
+/// again, for example after a crash, is skipped. The table can be turned into a TimescaleDB hypertable,
+/// partitioned by time.
+///
+/// The values are inserted by batches of `batch_size` rows, in a transaction committed when the sink is
+/// flushed. A flush failing keeps the values, to be inserted on the next flush.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, postgres::PostgresSink, sync::FileCheckpoint}, m2web::error};
+/// # use sqlx::PgPool;
+/// # #[tokio::test]
+/// # async fn write_dm_data_to_postgres() -> Result<u64, error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let pool = PgPool::connect("postgres://ewon@localhost/history").await.unwrap();
+/// let mut sink = PostgresSink::new(pool);
+/// sink.create_table().await?;
+/// sink.create_hypertable().await?;
+///
+/// client
+///     .sync_stream(FileCheckpoint::new("values.transaction"))
+///     .write_to(&mut sink)
+///     .await
+/// # }
+/// ```
+pub struct PostgresSink {
+    /// The pool of connections to the database.
+    pool: PgPool,
+    /// The name of the table, which is not escaped.
+    table: String,
+    /// The maximum number of rows inserted by a statement.
+    batch_size: usize,
+    /// The values waiting to be inserted.
+    records: Vec<DataRecord>,
+}
+
+impl PostgresSink {
+    /// Create a sink writing the values to the default table, by batches of 1000 rows.
+    pub fn new(pool: PgPool) -> Self {
+        PostgresSink {
+            pool,
+            table: DEFAULT_TABLE.to_string(),
+            batch_size: 1000,
+            records: vec![],
+        }
+    }
+
+    /// Write the values to this table, which may be qualified by its schema.
+    pub fn table(mut self, table: &str) -> Self {
+        self.table = table.to_owned();
+        self
+    }
+
+    /// Insert at most this number of rows by statement.
+    ///
+    /// A statement binds 8 parameters by row, and PostgreSQL accepts at most 65535 parameters, so the batch
+    /// size is capped to 8191 rows.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, u16::MAX as usize / 8);
+        self
+    }
+
+    /// Return the number of values waiting to be inserted.
+    pub fn pending(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Create the table if it does not exist.
+    pub async fn create_table(&self) -> Result<(), error::Error> {
+        sqlx::query(&create_table_statement(&self.table))
+            .execute(&self.pool)
+            .await
+            .map_err(database_error)?;
+
+        Ok(())
+    }
+
+    /// Turn the table into a TimescaleDB hypertable, partitioned by time, if it is not already one.
+    ///
+    /// The TimescaleDB extension must be enabled on the database.
+    pub async fn create_hypertable(&self) -> Result<(), error::Error> {
+        sqlx::query("SELECT create_hypertable($1, 'time', if_not_exists => TRUE)")
+            .bind(&self.table)
+            .execute(&self.pool)
+            .await
+            .map_err(database_error)?;
+
+        Ok(())
+    }
+}
+
+impl AsyncDataSink for PostgresSink {
+    async fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        self.records.extend_from_slice(records);
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), error::Error> {
+        if self.records.is_empty() {
+            return Ok(());
+        }
+
+        let mut transaction = self.pool.begin().await.map_err(database_error)?;
+        for records in self.records.chunks(self.batch_size) {
+            insert_statement(&self.table, records)
+                .build()
+                .execute(&mut *transaction)
+                .await
+                .map_err(database_error)?;
+        }
+        transaction.commit().await.map_err(database_error)?;
+        self.records.clear();
+
+        Ok(())
+    }
+}
+
+/// Return the statement creating the table of the values.
+fn create_table_statement(table: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (\
+            time TIMESTAMPTZ NOT NULL, \
+            ewon_id BIGINT NOT NULL, \
+            ewon TEXT NOT NULL, \
+            tag_id BIGINT NOT NULL, \
+            tag TEXT NOT NULL, \
+            value DOUBLE PRECISION, \
+            text_value TEXT, \
+            quality TEXT NOT NULL, \
+            PRIMARY KEY (ewon_id, tag_id, time)\
+        )",
+        table
+    )
+}
+
+/// Return the statement inserting the values, skipping those already inserted.
+fn insert_statement<'r>(table: &str, records: &'r [DataRecord]) -> QueryBuilder<'r, Postgres> {
+    let mut statement = QueryBuilder::new(format!(
+        "INSERT INTO {} (time, ewon_id, ewon, tag_id, tag, value, text_value, quality) ",
+        table
+    ));
+    statement.push_values(records, |mut row, record| {
+        row.push_bind(record.data_point.ts)
+            .push_bind(i64::from(record.ewon_id))
+            .push_bind(&record.ewon_name)
+            .push_bind(i64::from(record.tag_id))
+            .push_bind(&record.tag_name)
+            .push_bind(record.data_point.value.as_f64())
+            .push_bind(match record.data_point.value {
+                TagValue::String(ref text) => Some(text),
+                _ => None,
+            })
+            .push_bind(&record.data_point.quality);
+    });
+    statement.push(" ON CONFLICT DO NOTHING");
+
+    statement
+}
+
+/// Map an error of the database to an internal error.
+fn database_error(error: sqlx::Error) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("Database error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        postgres::{create_table_statement, insert_statement},
+    };
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn postgres_statements_ok() {
+        let record = |tag_name: &str, value| DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: tag_name.to_string(),
+            data_point: DataPoint {
+                ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        };
+        let records = [
+            record("Temperature", TagValue::Float(21.5)),
+            record("Recipe", TagValue::String("PET-500".to_string())),
+        ];
+
+        assert_eq!(
+            create_table_statement("history.ewon_values"),
+            "CREATE TABLE IF NOT EXISTS history.ewon_values (time TIMESTAMPTZ NOT NULL, \
+            ewon_id BIGINT NOT NULL, ewon TEXT NOT NULL, tag_id BIGINT NOT NULL, tag TEXT NOT NULL, \
+            value DOUBLE PRECISION, text_value TEXT, quality TEXT NOT NULL, \
+            PRIMARY KEY (ewon_id, tag_id, time))"
+        );
+        assert_eq!(
+            insert_statement("ewon_values", &records).sql(),
+            "INSERT INTO ewon_values (time, ewon_id, ewon, tag_id, tag, value, text_value, quality) \
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8), ($9, $10, $11, $12, $13, $14, $15, $16) \
+            ON CONFLICT DO NOTHING"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::time::SystemTime;
+
+/// Query of the historical data stored in the Data Mailbox.
+///
+/// Select the data returned by `DmClient::get_data()`, regardless of the transactions of `sync_data()`. All
+/// the data of all the eWONs is selected, unless restricted to an eWON, a tag or a time window.
+///
+/// # Example
+/// ```rust
+/// # use libewon::dmweb::query::DataQuery;
+/// # use std::time::{Duration, SystemTime};
+/// let query = DataQuery::new()
+///     .ewon(7)
+///     .tag(12)
+///     .from(SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000))
+///     .limit(1000);
+///
+/// assert_eq!(
+///     query.query_params(),
+///     [
+///         ("ewonId", "7".to_string()),
+///         ("tagId", "12".to_string()),
+///         ("from", "2023-11-14T22:13:20Z".to_string()),
+///         ("limit", "1000".to_string()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DataQuery {
+    /// The id of the only selected eWON in the Data Mailbox.
+    ewon_id: Option<u32>,
+    /// The id of the only selected tag in the Data Mailbox.
+    tag_id: Option<u32>,
+    /// The start of the time window.
+    from: Option<SystemTime>,
+    /// The end of the time window.
+    to: Option<SystemTime>,
+    /// The maximum number of values returned.
+    limit: Option<u32>,
+}
+
+impl DataQuery {
+    /// Create a query selecting all the data of all the eWONs.
+    pub fn new() -> Self {
+        DataQuery::default()
+    }
+
+    /// Only select the data of the eWON with this id in the Data Mailbox.
+    pub fn ewon(mut self, ewon_id: u32) -> Self {
+        self.ewon_id = Some(ewon_id);
+        self
+    }
+
+    /// Only select the data of the tag with this id in the Data Mailbox.
+    pub fn tag(mut self, tag_id: u32) -> Self {
+        self.tag_id = Some(tag_id);
+        self
+    }
+
+    /// Only select the data logged from this time.
+    pub fn from(mut self, from: SystemTime) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only select the data logged until this time.
+    pub fn to(mut self, to: SystemTime) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Only select the data logged between both times.
+    pub fn window(self, from: SystemTime, to: SystemTime) -> Self {
+        self.from(from).to(to)
+    }
+
+    /// Return at most this number of values.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Return the parameters of the query, as expected by the `getdata` endpoint.
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut query_params = vec![];
+        if let Some(ewon_id) = self.ewon_id {
+            query_params.push(("ewonId", ewon_id.to_string()));
+        }
+        if let Some(tag_id) = self.tag_id {
+            query_params.push(("tagId", tag_id.to_string()));
+        }
+        if let Some(from) = self.from {
+            query_params.push(("from", iso_8601(from)));
+        }
+        if let Some(to) = self.to {
+            query_params.push(("to", iso_8601(to)));
+        }
+        if let Some(limit) = self.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+
+        query_params
+    }
+}
+
+/// Format the time as an ISO 8601 UTC date, as expected by the DMWeb API.
+fn iso_8601(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Data deleted from the Data Mailbox.
+///
+/// A deletion can not be undone, so it must be confirmed before being given to `DmClient::delete()`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::dmweb::query::DeleteScope;
+/// let deletion = DeleteScope::Ewon(7).confirm();
+///
+/// assert_eq!(deletion.scope(), DeleteScope::Ewon(7));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DeleteScope {
+    /// The data synchronized up to this transaction, included.
+    UpToTransaction(u64),
+    /// All the data of the eWON with this id in the Data Mailbox.
+    Ewon(u32),
+    /// All the data of all the eWONs.
+    All,
+}
+
+impl DeleteScope {
+    /// Confirm the deletion of this data.
+    pub fn confirm(self) -> ConfirmedDelete {
+        ConfirmedDelete { scope: self }
+    }
+}
+
+/// Deletion confirmed by the caller, created by `DeleteScope::confirm()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ConfirmedDelete {
+    /// The deleted data.
+    scope: DeleteScope,
+}
+
+impl ConfirmedDelete {
+    /// Return the deleted data.
+    pub fn scope(&self) -> DeleteScope {
+        self.scope
+    }
+
+    /// Return the parameters of the deletion, as expected by the `delete` endpoint.
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        match self.scope {
+            DeleteScope::UpToTransaction(transaction_id) => {
+                vec![("transactionId", transaction_id.to_string())]
+            }
+            DeleteScope::Ewon(ewon_id) => vec![("ewonId", ewon_id.to_string())],
+            DeleteScope::All => vec![],
+        }
+    }
+}
@@ -0,0 +1,347 @@
+#[cfg(feature = "arrow")]
+use crate::dmweb::parquet::ParquetSink;
+use crate::{
+    dmweb::{
+        data::DataRecord,
+        sink::{AsyncDataSink, CsvSink, DataSink},
+    },
+    m2web::error,
+};
+use chrono::{DateTime, Utc};
+use object_store::{
+    aws::AmazonS3Builder, path::Path, ObjectStore, ObjectStoreExt, PutPayload, WriteMultipart,
+};
+use std::sync::Arc;
+
+/// The default template of the keys of the uploaded files.
+pub const DEFAULT_KEY_TEMPLATE: &str = "ewon/{date}/{start}-{seq}.{ext}";
+
+/// The size of the parts of a multipart upload, the files up to this size being uploaded at once.
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Format of the files uploaded by an `S3Sink`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ExportFormat {
+    /// CSV files, as written by a `CsvSink`.
+    Csv,
+    /// Parquet files, as written by a `ParquetSink`.
+    #[cfg(feature = "arrow")]
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Return the extension of the files of this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            #[cfg(feature = "arrow")]
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// File being written by an `S3Sink`, until it is uploaded.
+enum Chunk {
+    /// A CSV file.
+    Csv(CsvSink<Vec<u8>>),
+    /// A Parquet file.
+    #[cfg(feature = "arrow")]
+    Parquet(Box<ParquetSink<Vec<u8>>>),
+}
+
+impl Chunk {
+    /// Create an empty file of this format.
+    fn new(format: ExportFormat) -> Result<Self, error::Error> {
+        Ok(match format {
+            ExportFormat::Csv => Chunk::Csv(CsvSink::new(vec![])),
+            #[cfg(feature = "arrow")]
+            ExportFormat::Parquet => Chunk::Parquet(Box::new(ParquetSink::new(vec![])?)),
+        })
+    }
+
+    /// Write the values to the file.
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        match self {
+            Chunk::Csv(sink) => DataSink::write(sink, records),
+            #[cfg(feature = "arrow")]
+            Chunk::Parquet(sink) => {
+                DataSink::write(sink.as_mut(), records)?;
+                DataSink::flush(sink.as_mut())
+            }
+        }
+    }
+
+    /// Return the content of the file.
+    fn into_bytes(self) -> Result<Vec<u8>, error::Error> {
+        match self {
+            Chunk::Csv(sink) => Ok(sink.into_inner()),
+            #[cfg(feature = "arrow")]
+            Chunk::Parquet(sink) => sink.close(),
+        }
+    }
+}
+
+/// Sink uploading the values to an S3-compatible bucket, by chunks.
+///
+/// The values are written to files of `chunk_size` values, in CSV or Parquet, which are uploaded to the
+/// bucket once full, when the sink is flushed. The files larger than 8 MiB are uploaded by parts. The key
+/// of a file is rendered from a template, where:
+/// - `{date}` is replaced by the date of the first value of the file, for example `2023-11-14`;
+/// - `{start}` is replaced by the time of the first value of the file, for example `20231114T221320Z`;
+/// - `{seq}` is replaced by the number of the file uploaded by the sink, starting from 0;
+/// - `{ext}` is replaced by the extension of the format, `csv` or `parquet`.
+///
+/// The values of a file not uploaded yet are held in memory: if the program stops, they are lost while
+/// their transaction may be saved. Uploading the file at each flush, with `upload_on_flush()`, prevents
+/// losing values, at the cost of a file per response of the API. Closing the sink uploads its last file.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, s3::{ExportFormat, S3Sink}, sync::FileCheckpoint}, m2web::error};
+/// # #[tokio::test]
+/// # async fn upload_dm_data_to_s3() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut sink = S3Sink::from_env("ewon-exports", ExportFormat::Csv)?
+///     .key_template("history/{date}/{seq}.{ext}")
+///     .chunk_size(500_000);
+///
+/// client
+///     .sync_stream(FileCheckpoint::new("values.transaction"))
+///     .write_to(&mut sink)
+///     .await?;
+/// sink.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct S3Sink {
+    /// The bucket the files are uploaded to.
+    store: Arc<dyn ObjectStore>,
+    /// The format of the files.
+    format: ExportFormat,
+    /// The template of the keys of the files.
+    key_template: String,
+    /// The number of values above which a file is uploaded.
+    chunk_size: usize,
+    /// Indicates if the file is uploaded at each flush, whatever its number of values.
+    upload_on_flush: bool,
+    /// The file being written, once a value is written.
+    chunk: Option<Chunk>,
+    /// The number of values of the file being written.
+    chunk_values: usize,
+    /// The time of the first value of the file being written.
+    chunk_start: Option<DateTime<Utc>>,
+    /// The number of files uploaded.
+    seq: u64,
+}
+
+impl S3Sink {
+    /// Create a sink uploading the files to the bucket, by chunks of 100000 values.
+    ///
+    /// The bucket may be any object store, for example an `AmazonS3` built for a MinIO endpoint.
+    pub fn new(store: Arc<dyn ObjectStore>, format: ExportFormat) -> Self {
+        S3Sink {
+            store,
+            format,
+            key_template: DEFAULT_KEY_TEMPLATE.to_string(),
+            chunk_size: 100_000,
+            upload_on_flush: false,
+            chunk: None,
+            chunk_values: 0,
+            chunk_start: None,
+            seq: 0,
+        }
+    }
+
+    /// Create a sink uploading the files to the S3 bucket with this name.
+    ///
+    /// The credentials, the region and the endpoint are read from the `AWS_*` environment variables, for
+    /// example `AWS_ENDPOINT` for an S3-compatible storage.
+    pub fn from_env(bucket: &str, format: ExportFormat) -> Result<Self, error::Error> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(object_store_error)?;
+
+        Ok(S3Sink::new(Arc::new(store), format))
+    }
+
+    /// Render the keys of the files from this template.
+    pub fn key_template(mut self, key_template: &str) -> Self {
+        self.key_template = key_template.to_owned();
+        self
+    }
+
+    /// Upload a file once it holds this number of values.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Upload the file at each flush, whatever its number of values.
+    pub fn upload_on_flush(mut self, upload_on_flush: bool) -> Self {
+        self.upload_on_flush = upload_on_flush;
+        self
+    }
+
+    /// Upload the file being written, if any, and return the number of files uploaded.
+    pub async fn close(mut self) -> Result<u64, error::Error> {
+        self.upload_chunk().await?;
+
+        Ok(self.seq)
+    }
+
+    /// Return the key of the file being written.
+    fn chunk_key(&self) -> String {
+        let start = self.chunk_start.unwrap_or_default();
+
+        self.key_template
+            .replace("{date}", &start.format("%Y-%m-%d").to_string())
+            .replace("{start}", &start.format("%Y%m%dT%H%M%SZ").to_string())
+            .replace("{seq}", &self.seq.to_string())
+            .replace("{ext}", self.format.extension())
+    }
+
+    /// Upload the file being written, if any.
+    async fn upload_chunk(&mut self) -> Result<(), error::Error> {
+        let chunk = match self.chunk.take() {
+            Some(chunk) => chunk,
+            None => return Ok(()),
+        };
+        let location = Path::from(self.chunk_key());
+        let bytes = chunk.into_bytes()?;
+
+        if bytes.len() <= PART_SIZE {
+            self.store
+                .put(&location, PutPayload::from(bytes))
+                .await
+                .map_err(object_store_error)?;
+        } else {
+            let upload = self
+                .store
+                .put_multipart(&location)
+                .await
+                .map_err(object_store_error)?;
+            let mut upload = WriteMultipart::new_with_chunk_size(upload, PART_SIZE);
+            upload.write(&bytes);
+            upload.finish().await.map_err(object_store_error)?;
+        }
+        self.chunk_values = 0;
+        self.chunk_start = None;
+        self.seq += 1;
+
+        Ok(())
+    }
+}
+
+impl AsyncDataSink for S3Sink {
+    async fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = match self.chunk {
+            Some(ref mut chunk) => chunk,
+            None => self.chunk.insert(Chunk::new(self.format)?),
+        };
+        chunk.write(records)?;
+        self.chunk_values += records.len();
+        self.chunk_start = self.chunk_start.or(Some(records[0].data_point.ts));
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), error::Error> {
+        if self.upload_on_flush || self.chunk_values >= self.chunk_size {
+            self.upload_chunk().await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Map an error of the object store to an internal error.
+fn object_store_error(error: object_store::Error) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("Object store error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        s3::{ExportFormat, S3Sink},
+        sink::AsyncDataSink,
+    };
+    use chrono::{TimeZone, Utc};
+    use futures::StreamExt;
+    use object_store::{memory::InMemory, path::Path, ObjectStore, ObjectStoreExt};
+    use std::sync::Arc;
+
+    fn record(value: f64) -> DataRecord {
+        DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: "Temperature".to_string(),
+            data_point: DataPoint {
+                ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+                value: TagValue::Float(value),
+                quality: "good".to_string(),
+            },
+        }
+    }
+
+    async fn object(store: &InMemory, key: &str) -> String {
+        let bytes = store
+            .get(&Path::from(key))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn s3_sink_chunks_ok() {
+        let store = Arc::new(InMemory::new());
+        let mut sink = S3Sink::new(store.clone(), ExportFormat::Csv)
+            .key_template("values/{date}/{start}-{seq}.{ext}")
+            .chunk_size(2);
+
+        sink.write(&[record(21.5)]).await.unwrap();
+        sink.flush().await.unwrap();
+        assert!(store.list(None).next().await.is_none());
+        sink.write(&[record(22.0)]).await.unwrap();
+        sink.flush().await.unwrap();
+        sink.write(&[record(22.5)]).await.unwrap();
+        sink.flush().await.unwrap();
+        assert_eq!(sink.close().await.unwrap(), 2);
+
+        let first = object(&store, "values/2023-11-14/20231114T221320Z-0.csv").await;
+        assert_eq!(first.lines().count(), 3);
+        assert!(first.starts_with("ewon_id,ewon_name,tag_id,tag_name,date,value,quality\n"));
+        let second = object(&store, "values/2023-11-14/20231114T221320Z-1.csv").await;
+        assert_eq!(second.lines().count(), 2);
+        assert!(second.contains("22.5"));
+    }
+
+    #[tokio::test]
+    async fn s3_sink_upload_on_flush_ok() {
+        let store = Arc::new(InMemory::new());
+        let mut sink = S3Sink::new(store.clone(), ExportFormat::Csv).upload_on_flush(true);
+
+        sink.write(&[record(21.5)]).await.unwrap();
+        sink.flush().await.unwrap();
+        sink.flush().await.unwrap();
+        assert_eq!(sink.close().await.unwrap(), 1);
+
+        let first = object(&store, "ewon/2023-11-14/20231114T221320Z-0.csv").await;
+        assert_eq!(first.lines().count(), 2);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{
+    dmweb::{
+        client::DmClient,
+        data::{Boundary, DataRecord, HistoricalData},
+        query::DataQuery,
+    },
+    m2web::error,
+};
+use chrono::{DateTime, Utc};
+use std::time::{Duration, SystemTime};
+use tokio::{sync::broadcast, time::Instant};
+
+/// Number of events kept for the subscribers which lag behind.
+const EVENTS_CAPACITY: usize = 256;
+
+/// Default maximum time to wait before requesting the API again, once throttled.
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+/// Delay standing for a request which is never due, about 30 years like the far future of tokio.
+const FAR_FUTURE: Duration = Duration::from_secs(86400 * 365 * 30);
+
+/// Budget of requests to the DMWeb API of an account.
+///
+/// The requests are evenly spread over the period: a budget of 60 requests per hour allows a request every
+/// minute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SyncBudget {
+    /// The number of requests allowed during the period.
+    requests: u32,
+    /// The period of the budget.
+    period: Duration,
+}
+
+impl SyncBudget {
+    /// Allow this number of requests during the period, at least one.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        SyncBudget {
+            requests: requests.max(1),
+            period,
+        }
+    }
+
+    /// Return the time between two requests.
+    pub fn interval(&self) -> Duration {
+        self.period / self.requests
+    }
+}
+
+/// Event of a `SyncScheduler`, sent to its subscribers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncEvent {
+    /// New values of the eWON have been written to the sink.
+    Synced {
+        /// The id of the eWON in the Data Mailbox.
+        ewon_id: u32,
+        /// The number of values written.
+        values: usize,
+    },
+    /// The API throttled the request for the data of the eWON, which is requested again after a delay.
+    Throttled {
+        /// The id of the eWON in the Data Mailbox.
+        ewon_id: u32,
+        /// The time before the next request.
+        backoff: Duration,
+    },
+    /// The data of the eWON could not be requested or written, and is requested again at its next turn.
+    Failed {
+        /// The id of the eWON in the Data Mailbox.
+        ewon_id: u32,
+        /// The description of the error.
+        error: String,
+    },
+}
+
+/// eWON synchronized by a `SyncScheduler`.
+struct SyncedDevice {
+    /// The id of the eWON in the Data Mailbox.
+    ewon_id: u32,
+    /// The time from which the data of the eWON is requested, until some data is received.
+    since: Option<DateTime<Utc>>,
+    /// The latest values of the eWON written to the sink.
+    boundary: Boundary,
+}
+
+/// Scheduler of the requests of the data of several eWONs, sharing the request budget of an account.
+///
+/// Talk2M throttles the requests to the Data Mailbox. The scheduler requests the new data of each eWON in
+/// turn, pacing the requests to stay within the budget of the account. While more data is available for an
+/// eWON, its data is requested again before moving to the next eWON. When the API throttles a request, the
+/// scheduler waits before requesting the same eWON again, doubling the delay at each throttled request up to
+/// a maximum.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, data::DataRecord, scheduler::{SyncBudget, SyncScheduler}}, m2web::error};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn schedule_dm_sync() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut scheduler = SyncScheduler::new(SyncBudget::new(100, Duration::from_secs(3600)))
+///     .device(7, None)
+///     .device(8, None);
+///
+/// // Do something useful, for example:
+/// scheduler
+///     .run(&client, |ewon_id: u32, records: Vec<DataRecord>| {
+///         println!("{} new values for eWON #{}", records.len(), ewon_id);
+///         Ok(())
+///     })
+///     .await;
+/// # }
+/// ```
+pub struct SyncScheduler {
+    /// The budget of requests of the account.
+    budget: SyncBudget,
+    /// The maximum time to wait before requesting the API again, once throttled.
+    max_backoff: Duration,
+    /// The synchronized eWONs, in the order of their turns.
+    devices: Vec<SyncedDevice>,
+    /// The sender of the events.
+    events: broadcast::Sender<SyncEvent>,
+}
+
+impl SyncScheduler {
+    /// Create a scheduler without any eWON, pacing the requests within this budget.
+    pub fn new(budget: SyncBudget) -> Self {
+        SyncScheduler {
+            budget,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            devices: vec![],
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+        }
+    }
+
+    /// Synchronize the data of the eWON with this id in the Data Mailbox, from this time if any.
+    pub fn device(mut self, ewon_id: u32, since: Option<SystemTime>) -> Self {
+        self.devices.push(SyncedDevice {
+            ewon_id,
+            since: since.map(DateTime::<Utc>::from),
+            boundary: Boundary::default(),
+        });
+        self
+    }
+
+    /// Wait at most this time before requesting the API again, once throttled.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Subscribe to the events of the scheduler.
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncEvent> {
+        self.events.subscribe()
+    }
+
+    /// Request the data of the eWONs in turn, writing the new values to the sink, forever.
+    ///
+    /// The values of an eWON are only considered synchronized once the sink accepted them: when the sink
+    /// fails, the same values are requested again at the next turn of the eWON.
+    pub async fn run<F>(&mut self, client: &DmClient<'_>, mut sink: F)
+    where
+        F: FnMut(u32, Vec<DataRecord>) -> Result<(), error::Error>,
+    {
+        // A scheduler without any eWON never wakes up.
+        if self.devices.is_empty() {
+            tokio::time::sleep(Duration::MAX).await;
+        }
+
+        let interval = self.budget.interval();
+        let (mut next_request, mut backoff, mut index) = (Instant::now(), Duration::ZERO, 0);
+        loop {
+            tokio::time::sleep_until(next_request).await;
+            let device = &mut self.devices[index];
+            let mut query = DataQuery::new().ewon(device.ewon_id);
+            if let Some(since) = device.boundary.time().or(device.since) {
+                query = query.from(SystemTime::from(since));
+            }
+            let result = client.get_data(&query).await;
+            next_request = deadline(interval);
+
+            let (event, more_data_available) = match result {
+                Err(error::Error {
+                    kind: error::ErrorKind::Throttled(_),
+                    ..
+                }) => {
+                    backoff = backoff
+                        .saturating_mul(2)
+                        .clamp(interval, self.max_backoff.max(interval));
+                    next_request = deadline(backoff);
+                    // An error only means there is no subscriber for now.
+                    let _ = self.events.send(SyncEvent::Throttled {
+                        ewon_id: device.ewon_id,
+                        backoff,
+                    });
+                    continue;
+                }
+                Err(err) => (
+                    SyncEvent::Failed {
+                        ewon_id: device.ewon_id,
+                        error: err.to_string(),
+                    },
+                    false,
+                ),
+                Ok(HistoricalData {
+                    more_data_available,
+                    mut ewons,
+                }) => {
+                    let mut boundary = device.boundary.clone();
+                    boundary.advance(&mut ewons);
+                    let records = HistoricalData {
+                        more_data_available,
+                        ewons,
+                    }
+                    .into_records();
+                    let values = records.len();
+                    let written = match values {
+                        0 => Ok(()),
+                        _ => sink(device.ewon_id, records),
+                    };
+                    match written {
+                        Ok(()) => {
+                            device.boundary = boundary;
+                            let event = SyncEvent::Synced {
+                                ewon_id: device.ewon_id,
+                                values,
+                            };
+                            (event, more_data_available && values > 0)
+                        }
+                        Err(err) => {
+                            let event = SyncEvent::Failed {
+                                ewon_id: device.ewon_id,
+                                error: err.to_string(),
+                            };
+                            (event, false)
+                        }
+                    }
+                }
+            };
+            backoff = Duration::ZERO;
+            let _ = self.events.send(event);
+            if !more_data_available {
+                index = (index + 1) % self.devices.len();
+            }
+        }
+    }
+}
+/// Return the instant the delay elapses, capped to the far future rather than overflowing.
+fn deadline(delay: Duration) -> Instant {
+    let now = Instant::now();
+
+    now.checked_add(delay)
+        .or_else(|| now.checked_add(FAR_FUTURE))
+        .unwrap_or(now)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::scheduler::deadline;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    #[test]
+    fn deadline_ok() {
+        let now = Instant::now();
+
+        assert!(deadline(Duration::from_secs(60)) >= now + Duration::from_secs(60));
+        // A huge backoff is capped rather than overflowing.
+        assert!(deadline(Duration::MAX) > now + Duration::from_secs(86400 * 365));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{dmweb::data::DataRecord, m2web::error};
+use chrono::SecondsFormat;
+use std::{future::Future, io::Write};
+
+/// Receiver of the values synchronized from the Data Mailbox.
+///
+/// The trait is implemented for any closure taking a slice of values.
+pub trait DataSink {
+    /// Called with the values of each response of the API.
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error>;
+
+    /// Called once the values of a response are written, before they are considered synchronized.
+    fn flush(&mut self) -> Result<(), error::Error> {
+        Ok(())
+    }
+}
+
+impl<F> DataSink for F
+where
+    F: FnMut(&[DataRecord]) -> Result<(), error::Error>,
+{
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        self(records)
+    }
+}
+
+/// Receiver of the values synchronized from the Data Mailbox, writing them asynchronously.
+///
+/// The sinks sending the values over the network implement this trait rather than `DataSink`. The trait is
+/// implemented for any `DataSink`.
+pub trait AsyncDataSink {
+    /// Called with the values of each response of the API.
+    fn write(&mut self, records: &[DataRecord]) -> impl Future<Output = Result<(), error::Error>>;
+
+    /// Called once the values of a response are written, before they are considered synchronized.
+    fn flush(&mut self) -> impl Future<Output = Result<(), error::Error>>;
+}
+
+impl<D: DataSink> AsyncDataSink for D {
+    async fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        DataSink::write(self, records)
+    }
+
+    async fn flush(&mut self) -> Result<(), error::Error> {
+        DataSink::flush(self)
+    }
+}
+
+/// Sink writing the values as CSV, one line per value.
+///
+/// The columns are `ewon_id`, `ewon_name`, `tag_id`, `tag_name`, `date`, `value` and `quality`, named by a
+/// header line unless disabled. The dates are ISO 8601 UTC dates. The fields holding a comma, a quote or a
+/// line break are quoted.
+///
+/// # Example
+/// ```rust
+/// # use libewon::dmweb::{data::{DataPoint, DataRecord, TagValue}, sink::{CsvSink, DataSink}};
+/// # use chrono::{TimeZone, Utc};
+/// let mut sink = CsvSink::new(vec![]);
+/// sink.write(&[DataRecord {
+///     ewon_id: 7,
+///     ewon_name: "bea-test".to_string(),
+///     tag_id: 12,
+///     tag_name: "Temperature".to_string(),
+///     data_point: DataPoint {
+///         ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+///         value: TagValue::Float(21.5),
+///         quality: "good".to_string(),
+///     },
+/// }])
+/// .unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(sink.into_inner()).unwrap(),
+///     "ewon_id,ewon_name,tag_id,tag_name,date,value,quality\n\
+///     7,bea-test,12,Temperature,2023-11-14T22:13:20Z,21.5,good\n"
+/// );
+/// ```
+pub struct CsvSink<W: Write> {
+    /// The writer of the CSV.
+    writer: W,
+    /// Indicates if the header line remains to be written.
+    header: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    /// Create a sink writing the values as CSV, starting with a header line.
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer,
+            header: true,
+        }
+    }
+
+    /// Write a header line before the first value or not, for example when appending to an existing file.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Return the writer of the CSV.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> DataSink for CsvSink<W> {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        if self.header && !records.is_empty() {
+            writeln!(
+                self.writer,
+                "ewon_id,ewon_name,tag_id,tag_name,date,value,quality"
+            )?;
+            self.header = false;
+        }
+        for record in records {
+            writeln!(
+                self.writer,
+                "{},{},{},{},{},{},{}",
+                record.ewon_id,
+                csv_field(&record.ewon_name),
+                record.tag_id,
+                csv_field(&record.tag_name),
+                record
+                    .data_point
+                    .ts
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+                csv_field(&record.data_point.value.to_string()),
+                csv_field(&record.data_point.quality),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), error::Error> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Quote the field if it holds a comma, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_owned(),
+    }
+}
+
+/// Sink writing the values as JSON Lines, one JSON object per line.
+///
+/// Each object holds the fields of a `DataRecord`, the value being flattened into the object.
+pub struct JsonLinesSink<W: Write> {
+    /// The writer of the JSON Lines.
+    writer: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    /// Create a sink writing the values as JSON Lines.
+    pub fn new(writer: W) -> Self {
+        JsonLinesSink { writer }
+    }
+
+    /// Return the writer of the JSON Lines.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> DataSink for JsonLinesSink<W> {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        for record in records {
+            serde_json::to_writer(&mut self.writer, record)?;
+            writeln!(self.writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), error::Error> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        sink::{CsvSink, DataSink, JsonLinesSink},
+    };
+    use chrono::{TimeZone, Utc};
+
+    /// Return a value of a tag of the eWON.
+    fn record(tag_name: &str, value: TagValue) -> DataRecord {
+        DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: tag_name.to_string(),
+            data_point: DataPoint {
+                ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn csv_sink_ok() {
+        let mut sink = CsvSink::new(vec![]).header(false);
+        sink.write(&[
+            record("Temperature", TagValue::Float(21.5)),
+            record("Recipe, \"PET\"", TagValue::String("500\nml".to_string())),
+        ])
+        .unwrap();
+        sink.write(&[]).unwrap();
+        sink.flush().unwrap();
+
+        assert_eq!(
+            String::from_utf8(sink.into_inner()).unwrap(),
+            "7,bea-test,12,Temperature,2023-11-14T22:13:20Z,21.5,good\n\
+            7,bea-test,12,\"Recipe, \"\"PET\"\"\",2023-11-14T22:13:20Z,\"500\nml\",good\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_sink_ok() {
+        let mut sink = JsonLinesSink::new(vec![]);
+        sink.write(&[
+            record("Temperature", TagValue::Float(21.5)),
+            record("Running", TagValue::Bool(true)),
+        ])
+        .unwrap();
+
+        let lines = String::from_utf8(sink.into_inner()).unwrap();
+        let records = lines
+            .lines()
+            .map(|line| serde_json::from_str::<DataRecord>(line).unwrap())
+            .collect::<Vec<DataRecord>>();
+        assert_eq!(
+            lines.lines().next().unwrap(),
+            "{\"ewon_id\":7,\"ewon_name\":\"bea-test\",\"tag_id\":12,\"tag_name\":\"Temperature\",\
+            \"date\":\"2023-11-14T22:13:20Z\",\"value\":21.5,\"quality\":\"good\"}"
+        );
+        assert_eq!(records[1], record("Running", TagValue::Bool(true)));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::{
+    dmweb::{client::DmClient, data::DataRecord, latest::LatestValues, sink::AsyncDataSink},
+    m2web::{clock, error},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::stream::BoxStream;
+#[cfg(target_arch = "wasm32")]
+use futures::stream::LocalBoxStream;
+use futures::{stream, Stream, StreamExt};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+#[cfg(feature = "fs")]
+use std::{fs, io, path::PathBuf};
+
+/// Stream of the values of a `SyncStream`, once first polled.
+#[cfg(not(target_arch = "wasm32"))]
+type RecordStream<'c> = BoxStream<'c, Result<DataRecord, error::Error>>;
+
+/// Stream of the values of a `SyncStream`, once first polled, which the HTTP client of the browser cannot
+/// send between threads.
+#[cfg(target_arch = "wasm32")]
+type RecordStream<'c> = LocalBoxStream<'c, Result<DataRecord, error::Error>>;
+
+/// Store of the id of the last transaction synchronized by a `SyncStream`.
+///
+/// Persisting the id allows a new stream to resume the synchronization where the previous one stopped, for
+/// example after a crash.
+pub trait CheckpointStore {
+    /// Return the id of the last transaction synchronized, if any.
+    fn load(&mut self) -> Result<Option<u64>, error::Error>;
+    /// Store the id of the last transaction synchronized.
+    fn save(&mut self, transaction_id: u64) -> Result<(), error::Error>;
+}
+
+/// Store keeping the id of the last transaction in memory, which is lost when the program stops.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoryCheckpoint {
+    /// The id of the last transaction synchronized.
+    transaction_id: Option<u64>,
+}
+
+impl MemoryCheckpoint {
+    /// Create a store starting from this transaction, or from the oldest data if none.
+    pub fn new(transaction_id: Option<u64>) -> Self {
+        MemoryCheckpoint { transaction_id }
+    }
+
+    /// Return the id of the last transaction synchronized, if any.
+    pub fn transaction_id(&self) -> Option<u64> {
+        self.transaction_id
+    }
+}
+
+impl CheckpointStore for MemoryCheckpoint {
+    fn load(&mut self) -> Result<Option<u64>, error::Error> {
+        Ok(self.transaction_id)
+    }
+
+    fn save(&mut self, transaction_id: u64) -> Result<(), error::Error> {
+        self.transaction_id = Some(transaction_id);
+        Ok(())
+    }
+}
+
+/// Store keeping the id of the last transaction in a file.
+///
+/// The file holds the id as text, and is replaced as a whole when the id is saved, so a crash never leaves
+/// it half written. A missing file means no transaction was synchronized yet.
+#[cfg(feature = "fs")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileCheckpoint {
+    /// The path of the file.
+    path: PathBuf,
+}
+
+#[cfg(feature = "fs")]
+impl FileCheckpoint {
+    /// Create a store keeping the id in the file at this path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileCheckpoint { path: path.into() }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl CheckpointStore for FileCheckpoint {
+    fn load(&mut self) -> Result<Option<u64>, error::Error> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => text.trim().parse().map(Some).map_err(|_| error::Error {
+                http_status: 500,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::InternalError(format!(
+                    "invalid checkpoint [{}] in {}",
+                    text.trim(),
+                    self.path.display()
+                )),
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&mut self, transaction_id: u64) -> Result<(), error::Error> {
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        fs::write(&temp_path, transaction_id.to_string())?;
+        fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Stream of the values synchronized from the Data Mailbox.
+///
+/// Call `DmClient::sync_data()` repeatedly, from the transaction found in the checkpoint store, and yield
+/// the values of each response. The id of a transaction is only saved once all its values have been
+/// consumed, when the next value is polled: if the program stops before, the values of this transaction
+/// are synchronized again by the next stream, but no value is ever missed.
+///
+/// The stream ends once all the data has been synchronized, unless a poll interval is set: the Data
+/// Mailbox is then synchronized again after this interval. The stream also ends after its first error, for
+/// example the error of a `Cancellation` wrapping the stream to stop it.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, sync::FileCheckpoint}, m2web::error};
+/// # use futures::StreamExt;
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn stream_dm_data() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut records = client
+///     .sync_stream(FileCheckpoint::new("/var/lib/collector/transaction"))
+///     .poll_interval(Duration::from_secs(600));
+///
+/// // Do something useful, for example:
+/// while let Some(record) = records.next().await {
+///     let record = record?;
+///     println!("{}/{}: {}", record.ewon_name, record.tag_name, record.data_point.value);
+/// }
+/// # }
+/// ```
+pub struct SyncStream<'c, 'a, S> {
+    /// The state of the synchronization, until the stream is first polled.
+    state: Option<SyncState<'c, 'a, S>>,
+    /// The stream of values, once the stream is first polled.
+    records: Option<RecordStream<'c>>,
+}
+
+/// State of the synchronization of a `SyncStream`.
+struct SyncState<'c, 'a, S> {
+    /// The client used to request the API.
+    client: &'c DmClient<'a>,
+    /// The store of the id of the last transaction synchronized.
+    store: S,
+    /// The interval between two synchronizations once all the data is synchronized, if any.
+    poll_interval: Option<Duration>,
+    /// The cache updated with the values of each response, if any.
+    latest_values: Option<LatestValues>,
+    /// The id of the last transaction synchronized, once loaded from the store.
+    last_transaction_id: Option<Option<u64>>,
+    /// The id of the transaction to save once its values are consumed.
+    checkpoint: Option<u64>,
+    /// Indicates if the last response announced more data.
+    more_data_available: bool,
+    /// The values of the last response not consumed yet.
+    records: VecDeque<DataRecord>,
+    /// Indicates if an error ended the stream.
+    failed: bool,
+}
+
+impl<'c, 'a, S: CheckpointStore + Send + 'c> SyncStream<'c, 'a, S> {
+    /// Create a stream synchronizing the data from the transaction found in the store.
+    pub(crate) fn new(client: &'c DmClient<'a>, store: S) -> Self {
+        SyncStream {
+            state: Some(SyncState {
+                client,
+                store,
+                poll_interval: None,
+                latest_values: None,
+                last_transaction_id: None,
+                checkpoint: None,
+                more_data_available: false,
+                records: VecDeque::new(),
+                failed: false,
+            }),
+            records: None,
+        }
+    }
+
+    /// Synchronize the data again after this interval, once all the data has been synchronized.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        if let Some(ref mut state) = self.state {
+            state.poll_interval = Some(poll_interval);
+        }
+        self
+    }
+
+    /// Update the cache with the values of each response, as soon as they are received.
+    pub fn latest_values(mut self, latest_values: LatestValues) -> Self {
+        if let Some(ref mut state) = self.state {
+            state.latest_values = Some(latest_values);
+        }
+        self
+    }
+
+    /// Write all the synchronized values to the sink, and return the number of values written.
+    ///
+    /// The values of each response are written then flushed at once, before the id of their transaction is
+    /// saved: the values written are never synchronized again, unless the program stops in between. Unless a
+    /// poll interval is set, the future completes once all the data has been synchronized.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::{dmweb::{client::DmClientBuilder, sink::CsvSink, sync::FileCheckpoint}, m2web::error};
+    /// # use std::{fs::File, io::BufWriter};
+    /// # #[tokio::test]
+    /// # async fn write_dm_data_to_csv() -> Result<u64, error::Error> {
+    /// let client = DmClientBuilder::default().build().unwrap();
+    /// let mut sink = CsvSink::new(BufWriter::new(File::create("values.csv")?));
+    ///
+    /// client
+    ///     .sync_stream(FileCheckpoint::new("values.transaction"))
+    ///     .write_to(&mut sink)
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn write_to<D: AsyncDataSink>(mut self, sink: &mut D) -> Result<u64, error::Error> {
+        let mut state = match self.state.take() {
+            Some(state) => state,
+            None => {
+                return Err(error::Error {
+                    http_status: 500,
+                    api_code: None,
+                    source: None,
+                    kind: error::ErrorKind::InternalError(
+                        "the sync stream has already been polled".to_string(),
+                    ),
+                })
+            }
+        };
+
+        let mut written = 0;
+        while state.next_page().await? {
+            let records = Vec::from(std::mem::take(&mut state.records));
+            sink.write(&records).await?;
+            sink.flush().await?;
+            written += records.len() as u64;
+            state.save_checkpoint()?;
+        }
+
+        Ok(written)
+    }
+}
+
+impl<'c, 'a, S> Unpin for SyncStream<'c, 'a, S> {}
+
+impl<'c, 'a, S: CheckpointStore + Send + 'c> Stream for SyncStream<'c, 'a, S> {
+    type Item = Result<DataRecord, error::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(state) = this.state.take() {
+            this.records = Some(Box::pin(stream::unfold(state, next_record)));
+        }
+
+        match this.records {
+            Some(ref mut records) => records.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<'c, 'a, S: CheckpointStore> SyncState<'c, 'a, S> {
+    /// Request the next values, once all the values of the previous response are consumed.
+    ///
+    /// Return `false` once all the data has been synchronized, unless a poll interval is set.
+    async fn next_page(&mut self) -> Result<bool, error::Error> {
+        let last_transaction_id = match self.last_transaction_id {
+            None => self.store.load()?,
+            Some(last_transaction_id) => {
+                if !self.more_data_available {
+                    match self.poll_interval {
+                        Some(poll_interval) => clock::sleep(poll_interval).await,
+                        None => return Ok(false),
+                    }
+                }
+                last_transaction_id
+            }
+        };
+
+        let data = self.client.sync_data(last_transaction_id, true).await?;
+        self.last_transaction_id = Some(data.transaction_id.or(last_transaction_id));
+        self.checkpoint = data.transaction_id;
+        self.more_data_available = data.more_data_available;
+        self.records = data.into_records().into();
+        if let Some(ref latest_values) = self.latest_values {
+            latest_values.update(self.records.make_contiguous());
+        }
+
+        Ok(true)
+    }
+
+    /// Save the transaction of the values consumed, if any.
+    fn save_checkpoint(&mut self) -> Result<(), error::Error> {
+        match self.checkpoint.take() {
+            Some(transaction_id) => self.store.save(transaction_id),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Return the next value of the synchronization, requesting the API when all the values are consumed.
+async fn next_record<'c, 'a, S: CheckpointStore>(
+    mut state: SyncState<'c, 'a, S>,
+) -> Option<(Result<DataRecord, error::Error>, SyncState<'c, 'a, S>)> {
+    loop {
+        if state.failed {
+            return None;
+        }
+        if let Some(record) = state.records.pop_front() {
+            return Some((Ok(record), state));
+        }
+
+        let next_page = match state.save_checkpoint() {
+            Ok(()) => state.next_page().await,
+            Err(err) => Err(err),
+        };
+        match next_page {
+            Ok(true) => (),
+            Ok(false) => return None,
+            Err(err) => {
+                state.failed = true;
+                return Some((Err(err), state));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "fs"))]
+mod test {
+    use crate::dmweb::sync::{CheckpointStore, FileCheckpoint, MemoryCheckpoint};
+    use std::fs;
+
+    #[test]
+    fn checkpoint_store_ok() {
+        let mut memory = MemoryCheckpoint::new(None);
+        assert_eq!(memory.load().unwrap(), None);
+        memory.save(42).unwrap();
+        assert_eq!(memory.load().unwrap(), Some(42));
+        assert_eq!(memory.transaction_id(), Some(42));
+
+        let path = std::env::temp_dir().join(format!("libewon-checkpoint-{}", std::process::id()));
+        let mut file = FileCheckpoint::new(&path);
+        assert_eq!(file.load().unwrap(), None);
+        file.save(42).unwrap();
+        file.save(43).unwrap();
+        assert_eq!(FileCheckpoint::new(&path).load().unwrap(), Some(43));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn checkpoint_store_ko() {
+        let path =
+            std::env::temp_dir().join(format!("libewon-bad-checkpoint-{}", std::process::id()));
+        fs::write(&path, "forty-two").unwrap();
+
+        let err = match FileCheckpoint::new(&path).load() {
+            Ok(_) => panic!("load should have returned an error::InternalError"),
+            Err(err) => err,
+        };
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            format!("{}", err),
+            format!(
+                "Internal error: invalid checkpoint [forty-two] in {}",
+                path.display()
+            )
+        );
+    }
+}
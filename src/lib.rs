@@ -27,5 +27,42 @@
 //!     .for_each(|ewon| println!(format!("#{}: {}", ewon.id, ewon.name)));
 //! # }
 //! ```
+//!
+//! The historical data pushed by the eWONs to the Talk2M Data Mailbox is collected with the client of the
+//! DMWeb API, in the `dmweb` module. Behind the `blocking` feature, the `blocking` module wraps the client
+//! for the programs which do not run an async runtime. Behind the `mqtt` feature, the `mqtt` module
+//! republishes the status changes and the tag values of the eWONs to an MQTT broker, and behind the
+//! `prometheus` feature, the `prometheus` module exports them as Prometheus gauges. Behind the `sqlite`
+//! feature, the `sqlite` module persists them in a local SQLite database. Behind the `polars` feature, the
+//! `dataframe` module converts the values to Polars data frames. The `series` module processes the series of
+//! values, whatever their source. Behind the `tracing` feature, the clients trace their calls and their
+//! requests to the APIs with `tracing` spans and events. Behind the `test-util` feature, the `m2web::mock`
+//! and `m2web::vcr` modules provide transports answering the requests of the clients with canned or
+//! recorded responses, to test the applications without a Talk2M account.
+//!
+//! The crate builds for `wasm32-unknown-unknown`, for the dashboards querying the APIs from the browser,
+//! with its default features disabled: the schedulers, behind the `scheduler` feature, and the checkpoints
+//! kept in files, behind the `fs` feature, are not available in the browser. The clients send their requests
+//! with the HTTP client of the browser, which has its own proxy, certificates and connection pool, so the
+//! builders only set the total timeout, the headers and the interceptors, and the timers of the browser
+//! replace those of tokio. The `blocking`, `mqtt`, `postgres`, `prometheus`, `sqlite` and `test-util`
+//! features are not available either.
+//!
+//! ```toml
+//! [dependencies]
+//! libewon = {version = "0.1", default-features = false}
+//! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "polars")]
+pub mod dataframe;
+pub mod dmweb;
 pub mod m2web;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod series;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
@@ -0,0 +1,33 @@
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+/// Talk2M account parameters.
+///
+/// Describe the corporate account used to connect to the M2Web API.
+#[derive(Builder, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AccountInfo {
+    /// The unique reference of the account.
+    pub account_reference: String,
+    /// The name of the account.
+    pub account_name: String,
+    /// The company which owns the account.
+    pub company: String,
+    /// The type of the account, for example "Free" or "Pro".
+    pub account_type: String,
+    /// The labels given by the account to the three custom attributes of the eWONs.
+    pub custom_attributes: [String; 3],
+    /// The pools defined for the account.
+    pub pools: Vec<Pool>,
+}
+
+/// Pool parameters.
+///
+/// A pool gathers several eWONs of the account.
+#[derive(Builder, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pool {
+    /// The UID of the pool to the M2Web API.
+    pub id: u32,
+    /// The name of the pool.
+    pub name: String,
+}
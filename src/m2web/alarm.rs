@@ -0,0 +1,119 @@
+use crate::m2web::{error, parser::ExportParser};
+use chrono_tz::Tz;
+use std::time::SystemTime;
+
+/// Status of an alarm.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AlarmStatus {
+    /// The tag is not in alarm.
+    None,
+    /// The threshold is exceeded, but not for long enough to raise the alarm.
+    Pretrigger,
+    /// The alarm is raised and not acknowledged.
+    Alarm,
+    /// The alarm is raised and acknowledged.
+    Acknowledged,
+    /// The value of the tag went back to normal, but the alarm is not acknowledged.
+    ReturnToNormal,
+    /// A status unknown to this crate, with its code.
+    Other(u32),
+}
+
+impl From<u32> for AlarmStatus {
+    fn from(code: u32) -> Self {
+        match code {
+            0 => AlarmStatus::None,
+            1 => AlarmStatus::Pretrigger,
+            2 => AlarmStatus::Alarm,
+            3 => AlarmStatus::Acknowledged,
+            4 => AlarmStatus::ReturnToNormal,
+            _ => AlarmStatus::Other(code),
+        }
+    }
+}
+
+/// Active alarm of an eWON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alarm {
+    /// The id of the tag in alarm.
+    pub tag_id: u32,
+    /// The name of the tag in alarm.
+    pub tag_name: String,
+    /// The status of the alarm.
+    pub status: AlarmStatus,
+    /// The type of the alarm, for example high or low threshold, as coded by the eWON.
+    pub alarm_type: u32,
+    /// The time at which the alarm entered its current status.
+    pub since: SystemTime,
+    /// The user who acknowledged the alarm, empty if the alarm is not acknowledged.
+    pub user: String,
+}
+
+impl Alarm {
+    /// Parse the active alarms exported by the eWON.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per alarm. The times are
+    /// exported as seconds since the Unix epoch, in the given timezone.
+    pub(in crate::m2web) fn parse_alarms(
+        export: &str,
+        timezone: Tz,
+    ) -> Result<Vec<Alarm>, error::Error> {
+        let table = ExportParser::new().parse(export)?;
+        let (tag_id_column, tag_name_column, status_column) = (
+            table.required_column("TagId")?,
+            table.required_column("TagName")?,
+            table.required_column("AlStatus")?,
+        );
+        let (type_column, time_column) = (
+            table.required_column("AlType")?,
+            table.required_column("TimeInt")?,
+        );
+        let user_column = table.column("UserAck");
+
+        table
+            .rows()
+            .iter()
+            .map(|row| {
+                Ok(Alarm {
+                    tag_id: row.number(tag_id_column)?,
+                    tag_name: row.text(tag_name_column)?.to_owned(),
+                    status: AlarmStatus::from(row.number::<u32>(status_column)?),
+                    alarm_type: row.number(type_column)?,
+                    since: row.time(time_column, timezone)?,
+                    user: match user_column {
+                        Some(column) => row.text(column)?.to_owned(),
+                        None => String::new(),
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::alarm::{Alarm, AlarmStatus};
+    use chrono_tz::Tz;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn parse_alarms_ok() {
+        let export =
+            "\"TagId\";\"TagName\";\"AlStatus\";\"AlType\";\"TimeInt\";\"TimeStr\";\"UserAck\"\r\n\
+            1;\"Temperature\";2;1;1700000000;\"14/11/2023 22:13:20\";\"\"\r\n\
+            4;\"Pressure\";3;2;1700000060;\"14/11/2023 22:14:20\";\"adm\"\r\n";
+
+        let alarms = Alarm::parse_alarms(export, Tz::UTC).unwrap();
+
+        assert_eq!(alarms.len(), 2);
+        assert_eq!(alarms[0].tag_name, "Temperature");
+        assert_eq!(alarms[0].status, AlarmStatus::Alarm);
+        assert_eq!(
+            alarms[0].since,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)
+        );
+        assert_eq!(alarms[0].user, "");
+        assert_eq!(alarms[1].status, AlarmStatus::Acknowledged);
+        assert_eq!(alarms[1].user, "adm");
+    }
+}
@@ -0,0 +1,202 @@
+use crate::m2web::{clock::Instant, error};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// State of the circuit of an endpoint or of an eWON.
+#[derive(Debug, Default)]
+struct Circuit {
+    /// The number of consecutive failures.
+    failures: u32,
+    /// The time the circuit was opened, or last let a trial request through.
+    opened: Option<Instant>,
+}
+
+/// Circuit breaker of the requests to the failing endpoints or eWONs.
+///
+/// The breaker keeps a circuit per endpoint of the API, and per eWON for the requests proxied to them. A
+/// circuit opens after the configured number of consecutive failures, transport errors or server errors,
+/// and the requests to its endpoint or eWON then fail at once with an `ErrorKind::CircuitOpen` error, without
+/// being sent, for the cool-down period. Once it elapsed, one request is let through: the circuit closes if
+/// it succeeds, and opens again for another period if it fails. It protects the batch jobs from wasting their
+/// rate budget on a dead relay. The clones of a breaker share their circuits, which allows to share one
+/// breaker across the clients of a Talk2M account.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{breaker::CircuitBreaker, client::ClientBuilder};
+/// # use std::time::Duration;
+/// let breaker = CircuitBreaker::new(5, Duration::from_secs(300));
+/// let client = ClientBuilder::default()
+///     .circuit_breaker(breaker.clone())
+///     .build()
+///     .unwrap();
+///
+/// if breaker.is_open("/t2mapi/get/bea-test") {
+///     println!("bea-test is unreachable");
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    /// The number of consecutive failures opening a circuit.
+    threshold: u32,
+    /// The time a circuit stays open.
+    cool_down: Duration,
+    /// The circuits, keyed by endpoint or eWON, shared by the clones of the breaker.
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker opening a circuit after this number of consecutive failures, for this time.
+    pub fn new(threshold: u32, cool_down: Duration) -> Self {
+        CircuitBreaker {
+            threshold: threshold.max(1),
+            cool_down,
+            circuits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Return the number of consecutive failures opening a circuit.
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Return the time a circuit stays open.
+    pub fn cool_down(&self) -> Duration {
+        self.cool_down
+    }
+
+    /// Check if the circuit of the endpoint, the path of its url such as `/t2mapi/getewons`, or of the eWON,
+    /// the path of its proxied requests such as `/t2mapi/get/bea-test`, is open.
+    pub fn is_open(&self, url_path: &str) -> bool {
+        self.circuits
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(circuit_key(url_path))
+            .and_then(|circuit| circuit.opened)
+            .is_some_and(|opened| opened.elapsed() < self.cool_down)
+    }
+
+    /// Close all the circuits.
+    pub fn reset(&self) {
+        self.circuits
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Check if a request to the url path may be sent, letting one request through once the circuit has been
+    /// open for the cool-down period.
+    pub(crate) fn check(&self, url_path: &str) -> Result<(), error::Error> {
+        let key = circuit_key(url_path);
+        let mut circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match circuits.get_mut(key) {
+            Some(Circuit {
+                failures,
+                opened: Some(opened),
+            }) => match opened.elapsed() < self.cool_down {
+                true => Err(error::Error {
+                    http_status: 503,
+                    api_code: None,
+                    source: None,
+                    kind: error::ErrorKind::CircuitOpen(format!(
+                        "[{}] failed {} times in a row, retry in {}s",
+                        key,
+                        failures,
+                        (self.cool_down - opened.elapsed()).as_secs()
+                    )),
+                }),
+                false => {
+                    // The other requests keep failing at once while the trial request is in flight.
+                    *opened = Instant::now();
+                    Ok(())
+                }
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Record the outcome of a request to the url path.
+    pub(crate) fn record(&self, url_path: &str, success: bool) {
+        let key = circuit_key(url_path);
+        let mut circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match success {
+            true => {
+                circuits.remove(key);
+            }
+            false => {
+                let circuit = circuits.entry(key.to_owned()).or_default();
+                circuit.failures += 1;
+                if circuit.failures >= self.threshold {
+                    circuit.opened = Some(Instant::now());
+                }
+            }
+        }
+    }
+}
+
+/// Return the key of the circuit of the url path: the path of the eWON for the proxied requests, the path
+/// of the endpoint otherwise.
+fn circuit_key(url_path: &str) -> &str {
+    let url_path = url_path.trim_end_matches('/');
+
+    match url_path.find("/get/") {
+        Some(start) => {
+            let device = start + "/get/".len();
+            match url_path[device..].find('/') {
+                Some(end) => &url_path[..device + end],
+                None => url_path,
+            }
+        }
+        None => url_path,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::breaker::{circuit_key, CircuitBreaker};
+    use std::time::Duration;
+
+    #[test]
+    fn circuit_key_ok() {
+        assert_eq!(circuit_key("/t2mapi/getewons"), "/t2mapi/getewons");
+        assert_eq!(
+            circuit_key("/t2mapi/get/bea-test/rcgi.bin/ParamForm"),
+            "/t2mapi/get/bea-test"
+        );
+        assert_eq!(circuit_key("/t2mapi/get/bea-test"), "/t2mapi/get/bea-test");
+    }
+
+    #[test]
+    fn circuit_breaker_ok() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let url_path = "/t2mapi/get/bea-test/rcgi.bin/ParamForm";
+
+        breaker.record(url_path, false);
+        assert!(breaker.check(url_path).is_ok());
+        breaker.record(url_path, true);
+        breaker.record(url_path, false);
+        assert!(!breaker.is_open("/t2mapi/get/bea-test"));
+
+        breaker.record(url_path, false);
+        assert!(breaker.is_open("/t2mapi/get/bea-test"));
+        assert!(breaker.check("/t2mapi/get/bea-test/usr/data.csv").is_err());
+        assert!(breaker.check("/t2mapi/get/eWON42/usr/data.csv").is_ok());
+
+        breaker.reset();
+        assert!(breaker.check(url_path).is_ok());
+    }
+
+    #[test]
+    fn circuit_breaker_cool_down_ok() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+
+        breaker.record("/t2mapi/getewons", false);
+        assert!(breaker.check("/t2mapi/getewons").is_ok());
+        assert!(!breaker.is_open("/t2mapi/getewons"));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::m2web::{
+    clock::Instant,
+    ewon::{Ewon, EwonRef},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// Entries of an `InventoryCache`.
+#[derive(Debug, Default)]
+struct Inventory {
+    /// The lists of eWONs, keyed by their pool, empty for all the eWONs, with the time they were fetched.
+    pools: HashMap<String, (Instant, Vec<Ewon>)>,
+    /// The eWONs fetched one by one, keyed by their query parameter, with the time they were fetched.
+    ewons: HashMap<(&'static str, String), (Instant, Ewon)>,
+}
+
+/// Cache of the inventory of the eWONs.
+///
+/// A client with a cache returns the lists of eWONs and the eWONs selected by their name or their id from
+/// the cache, as long as they were fetched from the API for less than the TTL of the cache, which spares
+/// the requests of the applications listing the eWONs again and again. The status of the eWONs returned
+/// may then be as old as the TTL: `Client::refresh_ewons()` and `Client::refresh_ewon()` bypass the cache and
+/// refresh it, and `invalidate()` empties it. The clones of a cache share their entries, which allows to share
+/// one cache across the clients of a Talk2M account.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{cache::InventoryCache, client::ClientBuilder};
+/// # use std::time::Duration;
+/// let cache = InventoryCache::new(Duration::from_secs(60));
+/// let client = ClientBuilder::default()
+///     .inventory_cache(cache.clone())
+///     .build()
+///     .unwrap();
+///
+/// // After the eWONs were added or removed:
+/// cache.invalidate();
+/// ```
+#[derive(Clone, Debug)]
+pub struct InventoryCache {
+    /// The time the entries are returned for, once fetched.
+    ttl: Duration,
+    /// The entries, shared by the clones of the cache.
+    inventory: Arc<Mutex<Inventory>>,
+}
+
+impl InventoryCache {
+    /// Create a cache returning the eWONs for this time once fetched.
+    pub fn new(ttl: Duration) -> Self {
+        InventoryCache {
+            ttl,
+            inventory: Arc::new(Mutex::new(Inventory::default())),
+        }
+    }
+
+    /// Return the time the eWONs are returned for, once fetched.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Empty the cache, so that the next requests fetch the eWONs from the API.
+    pub fn invalidate(&self) {
+        let mut inventory = self
+            .inventory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        inventory.pools.clear();
+        inventory.ewons.clear();
+    }
+
+    /// Return the eWONs of the pool, or all of them, unless they expired.
+    pub(crate) fn ewons(&self, pool: Option<&str>) -> Option<Vec<Ewon>> {
+        let inventory = self
+            .inventory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        inventory
+            .pools
+            .get(pool.unwrap_or_default())
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, ewons)| ewons.to_vec())
+    }
+
+    /// Store the eWONs of the pool, or all of them.
+    pub(crate) fn put_ewons(&self, pool: Option<&str>, ewons: &[Ewon]) {
+        let mut inventory = self
+            .inventory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        inventory.pools.insert(
+            pool.unwrap_or_default().to_owned(),
+            (Instant::now(), ewons.to_vec()),
+        );
+    }
+
+    /// Return the eWON, from the eWONs fetched one by one or from the lists of eWONs, unless it expired.
+    pub(crate) fn ewon(&self, ewon: &EwonRef<'_>) -> Option<Ewon> {
+        let inventory = self
+            .inventory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let is_ewon = |cached: &Ewon| match ewon {
+            EwonRef::Id(id) => cached.id == *id,
+            EwonRef::Name(name) => cached.name == *name,
+            EwonRef::Ewon(ewon) => cached.id == ewon.id,
+        };
+
+        inventory
+            .ewons
+            .get(&ewon.query_param())
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, cached)| cached.to_owned())
+            .or_else(|| {
+                inventory
+                    .pools
+                    .values()
+                    .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+                    .find_map(|(_, ewons)| ewons.iter().find(|cached| is_ewon(cached)).cloned())
+            })
+    }
+
+    /// Store the eWON fetched by its name or its id.
+    pub(crate) fn put_ewon(&self, ewon_ref: &EwonRef<'_>, ewon: &Ewon) {
+        let mut inventory = self
+            .inventory
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        inventory
+            .ewons
+            .insert(ewon_ref.query_param(), (Instant::now(), ewon.to_owned()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        cache::InventoryCache,
+        ewon::{Ewon, EwonRef},
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn inventory_cache_ok() {
+        let cache = InventoryCache::new(Duration::from_secs(60));
+        let ewons = vec![Ewon {
+            id: 1206698,
+            name: "bea-test".to_string(),
+            ..Ewon::default()
+        }];
+        let ewon = ewons[0].clone();
+
+        assert_eq!(cache.ewons(None), None);
+        cache.put_ewons(None, &ewons);
+        assert_eq!(cache.ewons(None), Some(ewons));
+        assert_eq!(cache.ewons(Some("emea")), None);
+        assert_eq!(cache.ewon(&EwonRef::Name("bea-test")), Some(ewon.clone()));
+        assert_eq!(cache.ewon(&EwonRef::Id(1206698)), Some(ewon));
+        assert_eq!(cache.ewon(&EwonRef::Name("eWON42")), None);
+
+        cache.invalidate();
+        assert_eq!(cache.ewons(None), None);
+        assert_eq!(cache.ewon(&EwonRef::Name("bea-test")), None);
+    }
+
+    #[test]
+    fn inventory_cache_expired_ok() {
+        let cache = InventoryCache::new(Duration::ZERO);
+        let ewons = vec![Ewon::default()];
+
+        cache.put_ewons(None, &ewons);
+        cache.put_ewon(&EwonRef::Id(0), &ewons[0]);
+        assert_eq!(cache.ewons(None), None);
+        assert_eq!(cache.ewon(&EwonRef::Id(0)), None);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::m2web::{
+    clock::{self, Instant},
+    error,
+};
+use futures::{
+    future::{self, Either},
+    stream, Stream, StreamExt,
+};
+use std::{future::Future, pin::pin, time::Duration};
+pub use tokio_util::sync::CancellationToken;
+
+/// Cancellation of long-running operations, by a token or at a deadline.
+///
+/// An operation run under a cancellation is dropped as soon as its token is cancelled or its deadline is
+/// reached, which drops the connection of its request in flight, and returns an `ErrorKind::Cancelled`
+/// or an `ErrorKind::Timeout` error. It allows to abort cleanly the EBD downloads, the bulk fetches or the
+/// synchronization streams, for example when the application shuts down. The clones of a cancellation share
+/// its token, so one token may cancel many operations at once.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{cancellation::{Cancellation, CancellationToken}, client::ClientBuilder, error};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn download_file_for_one_minute() -> Result<Vec<u8>, error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let shutdown = CancellationToken::new();
+/// let cancellation = Cancellation::new()
+///     .token(shutdown.clone())
+///     .timeout(Duration::from_secs(60));
+///
+/// let file = cancellation
+///     .run(client.download_file("bea-test", "/usr/mydata.csv"))
+///     .await?;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    /// The token cancelling the operations.
+    token: Option<CancellationToken>,
+    /// The time the operations are cancelled at.
+    deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    /// Create a cancellation which never cancels the operations, until a token or a deadline is set.
+    pub fn new() -> Self {
+        Cancellation::default()
+    }
+
+    /// Cancel the operations once the token is cancelled.
+    pub fn token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Cancel the operations once the deadline is reached.
+    ///
+    /// On wasm32, the deadline is a `wasmtimer::std::Instant`, read from the clock of the browser, since
+    /// `std::time::Instant::now()` panics there.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Cancel the operations once this time elapsed, from now.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Check if the operations are cancelled, their token being cancelled or their deadline reached.
+    pub fn is_cancelled(&self) -> bool {
+        self.token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+            || self
+                .deadline
+                .is_some_and(|deadline| deadline <= Instant::now())
+    }
+
+    /// Run the operation, unless it is cancelled before it completes.
+    pub async fn run<T>(
+        &self,
+        operation: impl Future<Output = Result<T, error::Error>>,
+    ) -> Result<T, error::Error> {
+        match future::select(pin!(operation), pin!(self.cancelled())).await {
+            Either::Left((result, _)) => result,
+            Either::Right((error, _)) => Err(error),
+        }
+    }
+
+    /// Return the items of the stream, until it is cancelled: the stream then returns the error of the
+    /// cancellation, and ends.
+    pub fn stream<'s, T: 's>(
+        &self,
+        items: impl Stream<Item = Result<T, error::Error>> + 's,
+    ) -> impl Stream<Item = Result<T, error::Error>> + 's {
+        let cancellation = self.clone();
+        let cancelled = Box::pin(async move { cancellation.cancelled().await });
+
+        stream::unfold(
+            (Box::pin(items), Some(cancelled)),
+            |(mut items, cancelled)| async move {
+                let mut cancelled = cancelled?;
+                let next = match future::select(items.next(), cancelled.as_mut()).await {
+                    Either::Left((item, _)) => Ok(item),
+                    Either::Right((error, _)) => Err(error),
+                };
+
+                match next {
+                    Ok(Some(item)) => Some((item, (items, Some(cancelled)))),
+                    Ok(None) => None,
+                    Err(error) => Some((Err(error), (items, None))),
+                }
+            },
+        )
+    }
+
+    /// Wait until the operations are cancelled, and return the error of the cancellation.
+    async fn cancelled(&self) -> error::Error {
+        let token = async {
+            match self.token {
+                Some(ref token) => token.cancelled().await,
+                None => future::pending().await,
+            }
+        };
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => clock::sleep_until(deadline).await,
+                None => future::pending().await,
+            }
+        };
+
+        match future::select(pin!(token), pin!(deadline)).await {
+            Either::Left(_) => error::Error {
+                http_status: 499,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::Cancelled("Operation cancelled".to_string()),
+            },
+            Either::Right(_) => error::Error {
+                http_status: 408,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::Timeout("Operation deadline exceeded".to_string()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::cancellation::{Cancellation, CancellationToken};
+    use futures::{stream, StreamExt};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn cancellation_token_ok() {
+        let token = CancellationToken::new();
+        let cancellation = Cancellation::new().token(token.clone());
+
+        assert_eq!(cancellation.run(async { Ok(42) }).await, Ok(42));
+        assert!(!cancellation.is_cancelled());
+
+        token.cancel();
+        let error = cancellation
+            .run(futures::future::pending::<Result<(), _>>())
+            .await
+            .unwrap_err();
+        assert!(cancellation.is_cancelled());
+        assert_eq!(format!("{}", error), "Cancelled: Operation cancelled");
+    }
+
+    #[tokio::test]
+    async fn cancellation_deadline_ok() {
+        let cancellation = Cancellation::new().timeout(Duration::from_millis(50));
+        let items = cancellation
+            .stream(stream::iter([Ok(1), Ok(2)]).chain(stream::pending()))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1], Ok(2));
+        assert_eq!(
+            format!("{}", items[2].as_ref().unwrap_err()),
+            "Timeout: Operation deadline exceeded"
+        );
+    }
+}
@@ -1,9 +1,72 @@
 use crate::m2web::{
+    account::{AccountInfo, Pool},
+    alarm::Alarm,
+    breaker::CircuitBreaker,
+    cache::InventoryCache,
+    clock,
+    config::{ConfigBackup, ConfigBackupDiff, ConfigFile},
+    credentials::DeviceCredentials,
+    device::{DeviceInfo, ScriptState},
+    ebd::{Ebd, EbdData, EbdTime},
     error,
-    ewon::{ApiResponse, Ewon},
+    event::EventEntry,
+    ewon::{ApiResponse, Ewon, EwonRef, RawEwonsResponse, WakeupOutcome, WakeupSummary},
+    failover::Failover,
+    filter::EwonFilter,
+    handle::EwonHandle,
+    history::LogRecord,
+    interceptor::Interceptor,
+    limiter::RateLimiter,
+    metrics::MetricsObserver,
+    parser::ExportParser,
+    proxy::{body_chunks, encode_ewon_name, gzip_error, is_gzip, LanProxy, ProxyResponse},
+    query::EwonQuery,
+    region::{Region, DEFAULT_T2M_URL},
+    retry::{Middleware, RetryPolicy},
+    service::HttpService,
+    tag::{Tag, TagData, TagGroup, TagValue},
+    timeouts::Timeouts,
+    timezone::DeviceTimezones,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use crate::m2web::{
+    dns::{FamilyResolver, IpFamily},
+    http_proxy::HttpProxy,
+    tls::{self, CertificatePin},
+};
+use chrono_tz::Tz;
 use derive_builder::Builder;
-use reqwest::Client as HttpClient;
+use flate2::write::GzDecoder;
+use futures::{stream, Stream, StreamExt};
+use percent_encoding::percent_decode_str;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Certificate;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+    multipart::{Form, Part},
+    Client as HttpClient, Method,
+};
+#[cfg(target_arch = "wasm32")]
+use std::marker::PhantomData;
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::{IpAddr, SocketAddr};
+use std::{borrow::Cow, collections::HashMap, io::Write, pin::pin, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    sync::{Semaphore, SemaphorePermit},
+};
+
+/// The User-Agent of the requests, unless set otherwise.
+const DEFAULT_USER_AGENT: &str = concat!("libewon/", env!("CARGO_PKG_VERSION"));
+
+/// Interval between two requests of the eWON status while waiting for it to be online.
+const WAKEUP_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The endpoints of the API which change the state of the account, whose requests are never retried.
+const NON_IDEMPOTENT_URL_PATHS: [&str; 3] = ["login", "logout", "sendofflineaction"];
+
+/// The forms of the eWONs which change their state, whose proxied requests are never retried.
+const NON_IDEMPOTENT_DEVICE_PATHS: [&str; 2] = ["rcgi.bin/ExeScriptForm", "rcgi.bin/UpdateTagForm"];
 
 /// M2Web API client.
 ///
@@ -11,8 +74,11 @@ use reqwest::Client as HttpClient;
 #[derive(Builder)]
 pub struct Client<'a> {
     /// The API base url.
-    #[builder(setter(strip_option), default = "\"https://m2web.talk2m.com/t2mapi\"")]
+    #[builder(setter(strip_option), default = "DEFAULT_T2M_URL")]
     t2m_url: &'a str,
+    /// The API base url of the device-scoped requests, which overrides the M2Web server of the eWONs.
+    #[builder(setter(strip_option), default = "None")]
+    t2m_device_url: Option<&'a str>,
     /// The Talk2M corporate account.
     #[builder(default = "\"account1\"")]
     t2m_account: &'a str,
@@ -25,18 +91,417 @@ pub struct Client<'a> {
     /// The Talk2M API key used to check the user is authorized to use the API.
     #[builder(default = "\"731e38ec-981f-4f31-9cb5-e87f0d571816\"")]
     t2m_developer_id: &'a str,
+    /// The credentials used to authenticate against the eWONs, for the requests proxied to them.
+    #[builder(default)]
+    device_credentials: DeviceCredentials<'a>,
+    /// The timezones of the eWONs, used to read the times they export.
+    #[builder(default)]
+    device_timezones: DeviceTimezones<'a>,
     /// Athenticate statefully or not.
     #[builder(default = "false")]
     stateful_auth: bool,
+    /// The policy of retry of the idempotent requests failing on a transient error, which never retries them
+    /// by default.
+    #[builder(default = "RetryPolicy::never()")]
+    retry_policy: RetryPolicy,
+    /// The rate limiter of the requests, which may be shared with other clients.
+    #[builder(setter(strip_option), default = "None")]
+    rate_limiter: Option<RateLimiter>,
+    /// The circuit breaker of the requests to the failing endpoints or eWONs, which may be shared with other
+    /// clients.
+    #[builder(setter(strip_option), default = "None")]
+    circuit_breaker: Option<CircuitBreaker>,
+    /// The tower service sending the requests, which sends them with the HTTP client by default.
+    #[builder(setter(strip_option), default = "None")]
+    http_service: Option<HttpService>,
+    /// The failover of the requests to the fallback urls of the API, in order, when the API base url is
+    /// unreachable.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Vec<&'a str>",
+            build = "Failover::new(self.t2m_url.unwrap_or(DEFAULT_T2M_URL), &self.failover)"
+        )
+    )]
+    failover: Option<Failover>,
+    /// The observer of the requests, feeding the metrics of the application, which may be shared with other
+    /// clients.
+    #[builder(setter(strip_option), default = "None")]
+    metrics_observer: Option<Arc<dyn MetricsObserver>>,
+    /// The cache of the eWONs, which may be shared with other clients.
+    #[builder(setter(strip_option), default = "None")]
+    inventory_cache: Option<InventoryCache>,
+    /// The hooks called around each request, in order.
+    #[builder(
+        setter(custom),
+        field(ty = "Vec<Arc<dyn Interceptor>>", build = "self.interceptors.clone()")
+    )]
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    /// The slots of the requests in flight, whose number is unlimited by default.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Option<usize>",
+            build = "self.in_flight.map(|max| Semaphore::new(max.clamp(1, Semaphore::MAX_PERMITS)))"
+        )
+    )]
+    in_flight: Option<Semaphore>,
+    /// The timeouts of the requests, unlimited by default.
+    #[builder(default)]
+    timeouts: Timeouts,
     /// Session id returned by the API in case of successful authentication.
     #[builder(default = "None", setter(skip))]
     t2m_session: Option<String>,
-    /// HTTP client to connect to the API.
-    #[builder(setter(strip_option, skip), default = "reqwest::Client::new()")]
+    /// The headers sent with each request, identifying the application, starting with its User-Agent.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "Vec<(&'a str, &'a str)>",
+            build = "default_headers(&self.headers)?"
+        )
+    )]
+    headers: HeaderMap,
+    /// HTTP client to connect to the API, built with the connect and read timeouts, the proxy and the TLS
+    /// settings unless provided.
+    #[builder(
+        setter(custom),
+        field(
+            ty = "HttpClientOptions<'a>",
+            build = "self.http_client.build(self.timeouts.unwrap_or_default())?"
+        )
+    )]
     http_client: HttpClient,
 }
 
+/// Return the headers sent with each request, the User-Agent of this crate overridden by the given headers.
+pub(crate) fn default_headers(headers: &[(&str, &str)]) -> Result<HeaderMap, String> {
+    let mut default_headers = HeaderMap::new();
+    default_headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+    for (name, value) in headers {
+        default_headers.insert(
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("invalid header name [{}]", name))?,
+            HeaderValue::from_str(value)
+                .map_err(|_| format!("invalid value of header [{}]", name))?,
+        );
+    }
+
+    Ok(default_headers)
+}
+
+/// Define the setters of the options of the HTTP client of a client builder, of its headers and of its
+/// interceptors, shared by the builders of the M2Web and DMWeb clients so they cannot drift apart.
+///
+/// The setters are generated in the `impl` block of a builder with the `timeouts`, `http_client`, `headers`
+/// and `interceptors` fields, given the lifetime of the builder and the payloads of its API which the
+/// compression of the responses shrinks. On wasm32, the browser connects to the API, through its own proxy,
+/// with its own certificates and its own compression, so only the total timeout, the HTTP client, the
+/// interceptors and the headers are set.
+macro_rules! http_client_setters {
+    ($a:lifetime, $payloads:literal) => {
+        /// Set the maximum time to connect to the API.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn connect_timeout(&mut self, connect_timeout: std::time::Duration) -> &mut Self {
+            self.timeouts = Some(self.timeouts.unwrap_or_default().connect(connect_timeout));
+            self
+        }
+
+        /// Set the maximum time between two reads of a response.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn read_timeout(&mut self, read_timeout: std::time::Duration) -> &mut Self {
+            self.timeouts = Some(self.timeouts.unwrap_or_default().read(read_timeout));
+            self
+        }
+
+        /// Set the maximum time of a request, from its connection to the end of its response.
+        pub fn timeout(&mut self, timeout: std::time::Duration) -> &mut Self {
+            self.timeouts = Some(self.timeouts.unwrap_or_default().total(timeout));
+            self
+        }
+
+        /// Send the requests with this HTTP client, configured and possibly shared by the application.
+        ///
+        /// The client keeps its own configuration, its proxies, TLS settings and connection pool; only the
+        /// total timeout is applied to its requests.
+        pub fn http_client(&mut self, http_client: reqwest::Client) -> &mut Self {
+            self.http_client.provided = Some(http_client);
+            self
+        }
+
+        /// Send the requests through the corporate proxy.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn http_proxy(
+            &mut self,
+            http_proxy: $crate::m2web::http_proxy::HttpProxy<$a>,
+        ) -> &mut Self {
+            self.http_client.http_proxy = Some(http_proxy);
+            self
+        }
+
+        /// Trust the root certificates of the PEM bundle, besides those of the platform, for example the
+        /// certificate of a TLS-intercepting middlebox.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn root_certificate(&mut self, pem_bundle: &$a [u8]) -> &mut Self {
+            self.http_client.root_certificates.push(pem_bundle);
+            self
+        }
+
+        /// Only connect to the servers whose certificates match one of the pins.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn pin(&mut self, pin: $crate::m2web::tls::CertificatePin) -> &mut Self {
+            self.http_client.pins.push(pin);
+            self
+        }
+
+        /// Negotiate the compression of the responses, or not.
+        ///
+        /// The responses are compressed with the algorithms enabled by the `gzip`, `brotli`, `deflate` and
+        /// `zstd` features, `gzip` being enabled by default, and decompressed on the fly, which shrinks
+        #[doc = concat!(" ", $payloads, " over metered links.")]
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn compression(&mut self, compression: bool) -> &mut Self {
+            self.http_client.no_compression = !compression;
+            self
+        }
+
+        /// Close the connections idle for this time, rather than 90 seconds.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn pool_idle_timeout(&mut self, pool_idle_timeout: std::time::Duration) -> &mut Self {
+            self.http_client.pool_idle_timeout = Some(pool_idle_timeout);
+            self
+        }
+
+        /// Keep at most this number of idle connections per host, rather than all of them.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn pool_max_idle_per_host(&mut self, pool_max_idle_per_host: usize) -> &mut Self {
+            self.http_client.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+            self
+        }
+
+        /// Send TCP keepalive probes at this interval on the connections, which keeps them open through the
+        /// firewalls and NATs dropping the idle connections.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn tcp_keepalive(&mut self, tcp_keepalive: std::time::Duration) -> &mut Self {
+            self.http_client.tcp_keepalive = Some(tcp_keepalive);
+            self
+        }
+
+        /// Resolve the host to this IP address, besides those added before, rather than with the DNS.
+        ///
+        /// The requests to the host are sent to the port of their url, whatever the address. It bypasses the
+        /// broken DNS of some plants, for the API as well as for the proxy.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn resolve(&mut self, host: &$a str, ip: std::net::IpAddr) -> &mut Self {
+            self.http_client.dns_overrides.push((host, ip));
+            self
+        }
+
+        /// Connect to the IP addresses of this family first, or only, among those resolved with the DNS.
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn ip_family(&mut self, ip_family: $crate::m2web::dns::IpFamily) -> &mut Self {
+            self.http_client.ip_family = Some(ip_family);
+            self
+        }
+
+        /// Call the hooks of the interceptor around each request, after those of the interceptors added
+        /// before.
+        pub fn interceptor(
+            &mut self,
+            interceptor: impl $crate::m2web::interceptor::Interceptor + 'static,
+        ) -> &mut Self {
+            self.interceptors.push(std::sync::Arc::new(interceptor));
+            self
+        }
+
+        /// Identify the application with this User-Agent, rather than the one of this crate.
+        pub fn user_agent(&mut self, user_agent: &$a str) -> &mut Self {
+            self.headers.push(("user-agent", user_agent));
+            self
+        }
+
+        /// Send this header with each request, for example to identify the application to a proxy.
+        pub fn header(&mut self, name: &$a str, value: &$a str) -> &mut Self {
+            self.headers.push((name, value));
+            self
+        }
+    };
+}
+pub(crate) use http_client_setters;
+
+/// Options of the HTTP client built by a client builder, unless an HTTP client is provided.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HttpClientOptions<'a> {
+    /// The HTTP client provided, used as is.
+    pub(crate) provided: Option<HttpClient>,
+    /// The corporate proxy the requests go through.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) http_proxy: Option<HttpProxy<'a>>,
+    /// The PEM bundles of the root certificates trusted besides those of the platform.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) root_certificates: Vec<&'a [u8]>,
+    /// The pins of the certificates of the servers.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) pins: Vec<CertificatePin>,
+    /// Do not negotiate the compression of the responses.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) no_compression: bool,
+    /// The time an idle connection is kept in the pool.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) pool_idle_timeout: Option<Duration>,
+    /// The maximum number of idle connections kept in the pool per host.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    /// The interval of the TCP keepalive probes on the connections.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) tcp_keepalive: Option<Duration>,
+    /// The IP addresses the hosts resolve to, rather than with the DNS.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) dns_overrides: Vec<(&'a str, IpAddr)>,
+    /// The family of the IP addresses connected to.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) ip_family: Option<IpFamily>,
+    /// The lifetime of the options, which only the native HTTP client takes.
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) options: PhantomData<&'a ()>,
+}
+
+impl<'a> HttpClientOptions<'a> {
+    /// Return the HTTP client provided, or else build one with the options.
+    ///
+    /// The total timeout is applied to each request, rather than by the HTTP client.
+    pub(crate) fn build(&self, timeouts: Timeouts) -> Result<HttpClient, String> {
+        if let Some(ref http_client) = self.provided {
+            return Ok(http_client.clone());
+        }
+
+        self.configure(HttpClient::builder(), timeouts)?
+            .build()
+            .map_err(|error| format!("unable to build the HTTP client: {}", error))
+    }
+
+    /// Configure the HTTP client with the connect and read timeouts, the proxy, the root certificates, the
+    /// pins, the tuning of the connection pool and the resolution of the hosts.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn configure(
+        &self,
+        mut http_client: reqwest::ClientBuilder,
+        timeouts: Timeouts,
+    ) -> Result<reqwest::ClientBuilder, String> {
+        if let Some(connect) = timeouts.connect {
+            http_client = http_client.connect_timeout(connect);
+        }
+        if let Some(read) = timeouts.read {
+            http_client = http_client.read_timeout(read);
+        }
+        if let Some(ref http_proxy) = self.http_proxy {
+            http_client = http_client.proxy(http_proxy.proxy()?);
+        }
+        if self.no_compression {
+            http_client = http_client.no_gzip().no_brotli().no_deflate().no_zstd();
+        }
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            http_client = http_client.pool_idle_timeout(pool_idle_timeout);
+        }
+        if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+            http_client = http_client.pool_max_idle_per_host(pool_max_idle_per_host);
+        }
+        if let Some(tcp_keepalive) = self.tcp_keepalive {
+            http_client = http_client.tcp_keepalive(tcp_keepalive);
+        }
+        let mut dns_overrides = HashMap::<&str, Vec<SocketAddr>>::new();
+        for (host, ip) in &self.dns_overrides {
+            // The port of the url of the request is used, rather than the one of the address.
+            dns_overrides
+                .entry(host)
+                .or_default()
+                .push(SocketAddr::new(*ip, 0));
+        }
+        for (host, addrs) in dns_overrides {
+            http_client = http_client.resolve_to_addrs(host, &addrs);
+        }
+        if let Some(ip_family) = self.ip_family {
+            http_client = http_client.dns_resolver(Arc::new(FamilyResolver(ip_family)));
+        }
+        let root_certificates =
+            tls::root_certificates(&self.root_certificates).map_err(|error| error.to_string())?;
+        // The pins are checked by a TLS configuration of its own, which verifies the root certificates too.
+        http_client = match self.pins.is_empty() {
+            true => root_certificates
+                .iter()
+                .try_fold(http_client, |http_client, root_certificate| {
+                    Ok::<_, reqwest::Error>(
+                        http_client.add_root_certificate(Certificate::from_der(root_certificate)?),
+                    )
+                })
+                .map_err(|error| format!("invalid root certificate: {}", error))?,
+            false => http_client.tls_backend_preconfigured(
+                tls::pinned_tls_config(root_certificates, &self.pins)
+                    .map_err(|error| error.to_string())?,
+            ),
+        };
+
+        Ok(http_client)
+    }
+
+    /// Leave the HTTP client as is, since the browser connects to the API with its own settings.
+    #[cfg(target_arch = "wasm32")]
+    fn configure(
+        &self,
+        http_client: reqwest::ClientBuilder,
+        _timeouts: Timeouts,
+    ) -> Result<reqwest::ClientBuilder, String> {
+        Ok(http_client)
+    }
+}
+
+impl<'a> ClientBuilder<'a> {
+    /// Set the API base url to the url of the Talk2M region.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, region::Region};
+    /// # #[tokio::test]
+    /// # async fn connect_to_region() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().region(Region::Eu2).build()?;
+    /// # }
+    /// ```
+    pub fn region(&mut self, region: Region) -> &mut Self {
+        self.t2m_url = Some(region.url());
+        self
+    }
+
+    http_client_setters!('a, "the lists of eWONs and the exports of the historical data");
+
+    /// Cap the number of requests in flight at once, the other requests waiting for one of them to complete.
+    ///
+    /// A request keeps its slot until its response is read, including the downloads from the eWONs, which
+    /// caps the number of connections relayed to the eWONs by the batches of requests.
+    pub fn max_concurrent_requests(&mut self, max_concurrent_requests: usize) -> &mut Self {
+        self.in_flight = Some(max_concurrent_requests);
+        self
+    }
+
+    /// Fail over to this API base url when the previous ones are unreachable, after those added before.
+    ///
+    /// A request failing to connect to the API is sent again to the next url, and the client keeps sending
+    /// its requests to the url which served it, until it fails in turn. The requests to the API base url of
+    /// the device-scoped requests or to the M2Web servers of the eWONs do not fail over.
+    pub fn t2m_fallback_url(&mut self, t2m_fallback_url: &'a str) -> &mut Self {
+        self.failover.push(t2m_fallback_url);
+        self
+    }
+}
+
 impl<'a> Client<'a> {
+    /// Return the API base url serving the requests: the primary one, unless the client failed over to one of
+    /// its fallback urls.
+    pub fn serving_t2m_url(&self) -> &str {
+        match self.failover {
+            Some(ref failover) => failover.active_url(),
+            None => self.t2m_url,
+        }
+    }
+
     /// Open a stateful session.
     ///
     /// To remain compatible with potential legacy code which could use the stateful authentication, authenticate
@@ -56,11 +521,14 @@ impl<'a> Client<'a> {
     /// # client.logout().await?;
     /// # }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub async fn login(&mut self) -> Result<&str, error::Error> {
         // Check if the user set the stateful auth.
         if !self.stateful_auth {
             return Err(error::Error {
-                code: 500,
+                http_status: 500,
+                api_code: None,
+                source: None,
                 kind: error::ErrorKind::StatelessAuthSet("stateful_auth was not set".to_string()),
             });
         }
@@ -68,7 +536,7 @@ impl<'a> Client<'a> {
         let api_response = self.request_api("login", None).await?;
         self.t2m_session = Some(api_response.t2msession.to_owned());
 
-        Ok(&self.t2m_session.as_ref().unwrap())
+        Ok(self.t2m_session.as_ref().unwrap())
     }
 
     /// Close a stateful session.
@@ -94,17 +562,19 @@ impl<'a> Client<'a> {
     /// client.logout().await?;
     /// # }
     /// ```
-    pub async fn logout(mut self) -> Result<(), error::Error> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn logout(self) -> Result<(), error::Error> {
         // Check if the user set the stateful auth.
         if !self.stateful_auth {
             return Err(error::Error {
-                code: 500,
+                http_status: 500,
+                api_code: None,
+                source: None,
                 kind: error::ErrorKind::StatelessAuthSet("stateful_auth was not set".to_string()),
             });
         }
 
         let _ = self.request_api("logout", None).await?;
-        self.t2m_session = None;
 
         Ok(())
     }
@@ -135,20 +605,283 @@ impl<'a> Client<'a> {
     /// let all_ewons = client.get_ewons(Some("emea")).await?;
     /// # }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub async fn get_ewons(&self, pool: Option<&str>) -> Result<Vec<Ewon>, error::Error> {
+        let cached_ewons = self
+            .inventory_cache
+            .as_ref()
+            .and_then(|inventory_cache| inventory_cache.ewons(pool));
+
+        match cached_ewons {
+            Some(ewons) => Ok(ewons),
+            None => self.refresh_ewons(pool).await,
+        }
+    }
+
+    /// Return the list of all eWONs registered for the corporate account, bypassing the cache.
+    ///
+    /// Like `get_ewons()`, but always request the API, and store the eWONs in the cache of the client, if
+    /// any.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{cache::InventoryCache, client::ClientBuilder, error, ewon::Ewon};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn refresh_all_ewons() -> Result<Vec<Ewon>, error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .inventory_cache(InventoryCache::new(Duration::from_secs(300)))
+    ///     .build()?;
+    /// let all_ewons = client.refresh_ewons(None).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn refresh_ewons(&self, pool: Option<&str>) -> Result<Vec<Ewon>, error::Error> {
         let query_params = vec![("pool", pool.unwrap_or_default())];
         let api_response = self.request_api("getewons", Some(query_params)).await?;
 
         if api_response.ewons.is_empty() {
             Err(error::Error {
-                code: 204,
+                http_status: 204,
+                api_code: None,
+                source: None,
                 kind: error::ErrorKind::NoContent("No eWON were returned by API".to_string()),
             })
         } else {
+            if let Some(ref inventory_cache) = self.inventory_cache {
+                inventory_cache.put_ewons(pool, &api_response.ewons);
+            }
+
             Ok(api_response.ewons)
         }
     }
 
+    /// Return all eWONs registered for the corporate account, keyed by their id.
+    ///
+    /// Like `get_ewons()`, all eWONs are returned unless an optional pool name is specified.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewons_keyed_by_id() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ewons = client.get_ewons_by_id_map(None).await?;
+    ///
+    /// // Do something useful, for example:
+    /// if let Some(ewon) = ewons.get(&42) {
+    ///     println!("eWON name: {}", ewon.name);
+    /// }
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewons_by_id_map(
+        &self,
+        pool: Option<&str>,
+    ) -> Result<HashMap<u32, Ewon>, error::Error> {
+        let ewons = self.get_ewons(pool).await?;
+
+        Ok(ewons.into_iter().map(|ewon| (ewon.id, ewon)).collect())
+    }
+
+    /// Return all eWONs registered for the corporate account, keyed by their name.
+    ///
+    /// Like `get_ewons()`, all eWONs are returned unless an optional pool name is specified.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewons_keyed_by_name() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ewons = client.get_ewons_by_name_map(None).await?;
+    ///
+    /// // Do something useful, for example:
+    /// if let Some(ewon) = ewons.get("ewon42") {
+    ///     println!("eWON id: {}", ewon.id);
+    /// }
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewons_by_name_map(
+        &self,
+        pool: Option<&str>,
+    ) -> Result<HashMap<String, Ewon>, error::Error> {
+        let ewons = self.get_ewons(pool).await?;
+
+        Ok(ewons
+            .into_iter()
+            .map(|ewon| (ewon.name.to_owned(), ewon))
+            .collect())
+    }
+
+    /// Return all eWONs registered for the corporate account, grouped by pool.
+    ///
+    /// The eWONs returned by the `getewons` endpoint do not carry the pools they belong to, so a list of eWONs
+    /// cannot be grouped by pool like with `Ewon::group_by_m2web_server()` or `Ewon::group_by_status()`.
+    /// Instead, the pools are first requested with `get_pools()`, then the eWONs of each pool with
+    /// `get_ewons()`, one request per pool. An eWON belonging to several pools appears in each of them, and
+    /// a pool without eWON is mapped to an empty list.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewons_grouped_by_pool() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let pools = client.get_ewons_by_pool().await?;
+    ///
+    /// // Do something useful, for example:
+    /// pools
+    ///     .iter()
+    ///     .for_each(|(pool, ewons)| println!("{}: {} eWONs", pool, ewons.len()));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewons_by_pool(&self) -> Result<HashMap<String, Vec<Ewon>>, error::Error> {
+        let mut groups = HashMap::new();
+
+        for pool in self.get_pools().await? {
+            let ewons = match self.get_ewons(Some(&pool.name)).await {
+                Ok(ewons) => ewons,
+                Err(err) if err.http_status == 204 => vec![],
+                Err(err) => return Err(err),
+            };
+            groups.insert(pool.name, ewons);
+        }
+
+        Ok(groups)
+    }
+
+    /// Return a stream of all eWONs registered for the corporate account.
+    ///
+    /// Like `get_ewons()`, all eWONs are returned unless an optional pool name is specified. Each eWON is
+    /// only decoded when it is polled from the stream, so the consumer could start to process the eWONs
+    /// without waiting for all of them to be decoded. A decoding error only affects the related eWON.
+    /// Unlike `get_ewons()`, no error is returned if the API returns no eWON: the stream is just empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # use futures::StreamExt;
+    /// # #[tokio::test]
+    /// # async fn stream_all_ewons() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let mut ewons = Box::pin(client.get_ewons_stream(None));
+    ///
+    /// // Do something useful, for example:
+    /// while let Some(ewon) = ewons.next().await {
+    ///     println!("eWON name: {}", ewon?.name);
+    /// }
+    /// # }
+    /// ```
+    pub fn get_ewons_stream<'s>(
+        &'s self,
+        pool: Option<&'s str>,
+    ) -> impl Stream<Item = Result<Ewon, error::Error>> + 's {
+        stream::once(async move {
+            let query_params = vec![("pool", pool.unwrap_or_default())];
+            let (http_status, http_body) = self
+                .request_api_body(self.t2m_url, "getewons", Some(query_params))
+                .await?;
+            let api_response = serde_json::from_str::<RawEwonsResponse>(&http_body)
+                .map_err(|err| error::response_parsing_error(err, "getewons", &http_body))?;
+
+            match api_response.success {
+                true => Ok(api_response.ewons),
+                false => Err(error::Error::from_api_response(
+                    http_status,
+                    api_response.code,
+                    &api_response.message,
+                )),
+            }
+        })
+        .flat_map(|raw_ewons| match raw_ewons {
+            Ok(raw_ewons) => stream::iter(raw_ewons.into_iter().map(|raw_ewon| {
+                serde_json::from_str::<Ewon>(raw_ewon.get())
+                    .map_err(|err| error::response_parsing_error(err, "getewons", raw_ewon.get()))
+            }))
+            .left_stream(),
+            Err(err) => stream::iter(vec![Err(err)]).right_stream(),
+        })
+    }
+
+    /// Return the list of the eWONs matching a filter.
+    ///
+    /// Get the list of all eWONs, or only those belonging to a pool like `get_ewons()`, then only keep
+    /// the eWONs matching the filter. The filtering is performed by the client once the eWONs have been
+    /// returned by the API; the returned list could be empty if no eWON matches the filter.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::{Ewon, EwonStatus}, filter::EwonFilter};
+    /// # #[tokio::test]
+    /// # async fn get_online_ewons() -> Result<Vec<Ewon>, error::Error> {
+    /// // Get all online eWONs belonging to the corporate account.
+    /// let client = ClientBuilder::default().build()?;
+    /// let online_ewons = client
+    ///     .get_ewons_filtered(None, &EwonFilter::default().status(EwonStatus::Online))
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewons_filtered(
+        &self,
+        pool: Option<&str>,
+        filter: &EwonFilter,
+    ) -> Result<Vec<Ewon>, error::Error> {
+        let ewons = self.get_ewons(pool).await?;
+
+        Ok(ewons
+            .into_iter()
+            .filter(|ewon| filter.matches(ewon))
+            .collect())
+    }
+
+    /// Return a query of the eWONs registered for the corporate account.
+    ///
+    /// The query selects all eWONs until criteria are added to it. The API is only requested when the
+    /// query is fetched.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon};
+    /// # #[tokio::test]
+    /// # async fn get_offline_ewons_from_pool() -> Result<Vec<Ewon>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let offline_ewons = client.ewons().in_pool("emea").offline().fetch().await?;
+    /// # }
+    /// ```
+    pub fn ewons(&self) -> EwonQuery<'_, 'a> {
+        EwonQuery::new(self)
+    }
+
+    /// Return a handle of the eWON selected by its id or its name.
+    ///
+    /// The eWON is requested once, then cached by the handle which performs the device-level operations.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_handle() -> Result<Ewon, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ewon = client.ewon("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{} is connected to {}", ewon.name(), ewon.m2web_server());
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn ewon<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<EwonHandle<'_, 'a>, error::Error> {
+        let ewon = self.get_ewon(ewon.into()).await?;
+
+        Ok(EwonHandle::new(self, ewon))
+    }
+
     /// Return the eWON selected by its name.
     ///
     /// Get the eWON selected by its name and only this one. The name have to be the exact
@@ -167,11 +900,51 @@ impl<'a> Client<'a> {
     /// println!("eWON name: {}", ewon.name);
     /// # }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub async fn get_ewon_by_name(&self, name: &str) -> Result<Ewon, error::Error> {
-        let query_params = vec![("name", name)];
-        let api_response = self.request_api("getewon", Some(query_params)).await?;
+        self.get_cached_ewon(EwonRef::Name(name)).await
+    }
 
-        Ok(api_response.ewon)
+    /// Return the eWON selected by its url-encoded name.
+    ///
+    /// Talk2M url-encodes the name of each eWON, for example "eWON  FLEXOCOLOR SM2845" is encoded as
+    /// "eWON++FLEXOCOLOR+SM2845". Unlike `get_ewon_by_name()`, which expects the exact name of the eWON,
+    /// this method expects the encoded name, like returned in the `encoded_name` field by `get_ewons()`,
+    /// and decodes it before requesting the API.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon};
+    /// # #[tokio::test]
+    /// # async fn get_one_ewon_by_encoded_name() -> Result<Ewon, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ewon = client
+    ///     .get_ewon_by_encoded_name("eWON++FLEXOCOLOR+SM2845")
+    ///     .await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("eWON name: {}", ewon.name);
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_ewon_by_encoded_name(&self, encoded_name: &str) -> Result<Ewon, error::Error> {
+        let name = encoded_name.replace('+', " ");
+        let name = match percent_decode_str(&name).decode_utf8() {
+            Ok(name) => name,
+            Err(_) => {
+                return Err(error::Error {
+                    http_status: 400,
+                    api_code: None,
+                    source: None,
+                    kind: error::ErrorKind::MissingOrWrongParameter(format!(
+                        "Encoded name [{}] is not a valid UTF-8 string",
+                        encoded_name
+                    )),
+                })
+            }
+        };
+
+        self.get_ewon_by_name(&name).await
     }
 
     /// Return the eWON selected by its id.
@@ -192,28 +965,1531 @@ impl<'a> Client<'a> {
     /// println!("eWON id: {}", ewon.id);
     /// # }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
     pub async fn get_ewon_by_id(&self, id: u32) -> Result<Ewon, error::Error> {
-        let id = id.to_string();
-        let query_params = vec![("id", id.as_ref())];
+        self.get_cached_ewon(EwonRef::Id(id)).await
+    }
+
+    /// Return the eWON selected by its id or its name, bypassing the cache.
+    ///
+    /// Like `get_ewon_by_id()` or `get_ewon_by_name()`, but always request the API, and store the eWON in the
+    /// cache of the client, if any.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{cache::InventoryCache, client::ClientBuilder, error, ewon::Ewon};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn refresh_one_ewon() -> Result<Ewon, error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .inventory_cache(InventoryCache::new(Duration::from_secs(300)))
+    ///     .build()?;
+    /// let ewon = client.refresh_ewon(42).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn refresh_ewon<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Ewon, error::Error> {
+        let ewon = ewon.into();
+        let fetched_ewon = self.get_ewon(ewon).await?;
+        if let Some(ref inventory_cache) = self.inventory_cache {
+            inventory_cache.put_ewon(&ewon, &fetched_ewon);
+        }
+
+        Ok(fetched_ewon)
+    }
+
+    /// Return the eWON selected by its id or its name, from the cache if it holds it.
+    async fn get_cached_ewon(&self, ewon: EwonRef<'_>) -> Result<Ewon, error::Error> {
+        match self.inventory_cache {
+            Some(ref inventory_cache) => match inventory_cache.ewon(&ewon) {
+                Some(cached_ewon) => Ok(cached_ewon),
+                None => {
+                    let fetched_ewon = self.get_ewon(ewon).await?;
+                    inventory_cache.put_ewon(&ewon, &fetched_ewon);
+
+                    Ok(fetched_ewon)
+                }
+            },
+            None => self.get_ewon(ewon).await,
+        }
+    }
+
+    /// Return the eWON selected by its id or its name.
+    async fn get_ewon(&self, ewon: EwonRef<'_>) -> Result<Ewon, error::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("device", ewon.label());
+        let (param_name, param_value) = ewon.query_param();
+        let query_params = vec![(param_name, param_value.as_ref())];
         let api_response = self.request_api("getewon", Some(query_params)).await?;
 
         Ok(api_response.ewon)
     }
 
-    /// Perform the request and check the HTTP error codes.
-    async fn request_api(
-        &self,
-        url_path: &str,
-        req_query_params: Option<Vec<(&str, &str)>>,
-    ) -> Result<ApiResponse, error::Error> {
-        // Check if the endpoint is provided.
-        if url_path.is_empty() {
-            return Err(error::Error {
-                code: 500,
-                kind: error::ErrorKind::InternalError("no API endpoint provided".to_string()),
-            });
-        }
-        // Check if the auth is stateful or not.
+    /// Return the information of the corporate account.
+    ///
+    /// Get the details of the corporate account used to connect to the API: its name, its reference,
+    /// its type, the labels of the eWONs custom attributes and the pools defined for the account.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{account::AccountInfo, client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_account_info() -> Result<AccountInfo, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let account_info = client.get_account_info().await?;
+    ///
+    /// // Do something useful, for example:
+    /// account_info
+    ///     .pools
+    ///     .iter()
+    ///     .for_each(|pool| println!("#{}: {}", pool.id, pool.name));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_account_info(&self) -> Result<AccountInfo, error::Error> {
+        let api_response = self.request_api("getaccountinfo", None).await?;
+
+        Ok(api_response.account_info)
+    }
+
+    /// Return the list of all pools defined for the corporate account.
+    ///
+    /// The pools are extracted from the account information. Each pool name could then be given
+    /// to `get_ewons()` to only get the eWONs belonging to this pool.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{account::Pool, client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewons_from_all_pools() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let pools = client.get_pools().await?;
+    ///
+    /// // Do something useful, for example:
+    /// for pool in pools {
+    ///     let ewons = client.get_ewons(Some(&pool.name)).await?;
+    ///     println!("{}: {} eWONs", pool.name, ewons.len());
+    /// }
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn get_pools(&self) -> Result<Vec<Pool>, error::Error> {
+        let account_info = self.get_account_info().await?;
+
+        if account_info.pools.is_empty() {
+            Err(error::Error {
+                http_status: 204,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::NoContent("No pool were returned by API".to_string()),
+            })
+        } else {
+            Ok(account_info.pools)
+        }
+    }
+
+    /// Wake up an eWON.
+    ///
+    /// Some eWONs, for example those connected through a GSM modem, only connect to Talk2M on demand.
+    /// Ask the M2Web API to wake up the eWON selected by its id or its name, so it could be reached
+    /// afterwards. The eWON may take some time to be online once the request is accepted.
+    ///
+    /// When an `Ewon` returned by the API is given, the request is sent to its own M2Web server.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::WakeupOutcome};
+    /// # #[tokio::test]
+    /// # async fn wakeup_ewon() -> Result<WakeupOutcome, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    ///
+    /// // Wake up an eWON by its name, or by its id.
+    /// let outcome = client.wakeup("ewon42").await?;
+    /// let outcome = client.wakeup(42).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn wakeup<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<WakeupOutcome, error::Error> {
+        let ewon = ewon.into();
+        let (param_name, param_value) = ewon.query_param();
+        let query_params = vec![(param_name, param_value.as_ref())];
+        let api_response = self
+            .request_device_api(&ewon, "wakeup", Some(query_params))
+            .await?;
+
+        Ok(WakeupOutcome {
+            message: Some(api_response.message).filter(|message| !message.is_empty()),
+        })
+    }
+
+    /// Wake up an eWON and wait for it to be online.
+    ///
+    /// Ask the M2Web API to wake up the eWON selected by its id or its name, then request the eWON every
+    /// few seconds until it is online, or until the timeout expires. The eWON is returned as soon as it is
+    /// online, with its refreshed parameters.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn wakeup_ewon_and_wait() -> Result<Ewon, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ewon = client
+    ///     .wakeup_and_wait("ewon42", Duration::from_secs(180))
+    ///     .await?;
+    ///
+    /// // Do something useful with the online eWON.
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn wakeup_and_wait<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        timeout: Duration,
+    ) -> Result<Ewon, error::Error> {
+        let ewon = ewon.into();
+        let deadline = clock::Instant::now() + timeout;
+        self.wakeup(ewon).await?;
+
+        loop {
+            let refreshed_ewon = self.get_ewon(ewon).await?;
+            if refreshed_ewon.is_online() {
+                return Ok(refreshed_ewon);
+            }
+
+            let now = clock::Instant::now();
+            if now >= deadline {
+                return Err(error::Error {
+                    http_status: 408,
+                    api_code: None,
+                    source: None,
+                    kind: error::ErrorKind::Timeout(format!(
+                        "eWON [{}] is still {} after {} seconds",
+                        refreshed_ewon.name,
+                        refreshed_ewon.status,
+                        timeout.as_secs()
+                    )),
+                });
+            }
+            clock::sleep(WAKEUP_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    /// Wake up all the eWONs belonging to a pool.
+    ///
+    /// Get the eWONs belonging to the pool, then wake up each eWON which is not online. The wake-up requests
+    /// are spaced by the given interval, to avoid flooding the API. A failed wake-up request does not stop
+    /// the wake-up of the other eWONs; it is reported in the returned summary.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, ewon::WakeupSummary};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn wakeup_pool() -> Result<WakeupSummary, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let summary = client
+    ///     .wakeup_pool("gsm-sites", Duration::from_secs(2))
+    ///     .await?;
+    ///
+    /// // Do something useful, for example:
+    /// summary
+    ///     .failed
+    ///     .iter()
+    ///     .for_each(|(ewon, err)| println!("{}: {}", ewon.name, err));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn wakeup_pool(
+        &self,
+        pool: &str,
+        interval: Duration,
+    ) -> Result<WakeupSummary, error::Error> {
+        let mut summary = WakeupSummary::default();
+        let mut first_request = true;
+
+        // The statuses of the cache could be outdated, an eWON gone offline would not be woken up.
+        for ewon in self.refresh_ewons(Some(pool)).await? {
+            if ewon.is_online() {
+                summary.already_online.push(ewon);
+                continue;
+            }
+
+            if !first_request {
+                clock::sleep(interval).await;
+            }
+            first_request = false;
+
+            match self.wakeup(&ewon).await {
+                Ok(_) => summary.woken.push(ewon),
+                Err(err) => summary.failed.push((ewon, err)),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Queue an action for an offline eWON.
+    ///
+    /// Ask the M2Web API to queue an action for the eWON selected by its id or its name. The action
+    /// will be executed by Talk2M as soon as the eWON connects again.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn send_offline_action_to_ewon() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    ///
+    /// // Queue an action for an eWON selected by its name, or by its id.
+    /// client.send_offline_action("ewon42", "reboot").await?;
+    /// client.send_offline_action(42, "reboot").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn send_offline_action<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        action: &str,
+    ) -> Result<(), error::Error> {
+        let (param_name, param_value) = ewon.into().query_param();
+        let query_params = vec![(param_name, param_value.as_ref()), ("action", action)];
+        let _ = self
+            .request_api("sendofflineaction", Some(query_params))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Send a GET request to the web server of an eWON, through the M2Web API.
+    ///
+    /// The M2Web API relays the request to the path of the eWON selected by its id or its name, and returns
+    /// the response of the eWON as is. The request is sent to the M2Web server of the eWON when it is known,
+    /// and an eWON selected by its id is requested first to get its name.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, proxy::ProxyResponse};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_param_form() -> Result<ProxyResponse, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let response = client
+    ///     .proxy_get("bea-test", "rcgi.bin/ParamForm", Some(vec![("AST_Param", "$dtSV$")]))
+    ///     .await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{}", response.text());
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn proxy_get<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        device_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ProxyResponse, error::Error> {
+        let (http_response, _in_flight) = self
+            .send_proxy_request(ewon.into(), device_path, req_query_params)
+            .await?;
+
+        Self::read_proxy_response(http_response).await
+    }
+
+    /// Return a proxy to the web server of a device of the LAN of an eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, proxy::ProxyResponse};
+    /// # #[tokio::test]
+    /// # async fn get_plc_status() -> Result<ProxyResponse, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let plc = client.lan_proxy("bea-test", "10.0.0.50", 80);
+    ///
+    /// let status = plc.get("/status").await?.error_for_status()?;
+    /// # }
+    /// ```
+    pub fn lan_proxy<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ip: &'e str,
+        port: u16,
+    ) -> LanProxy<'_, 'a, 'e> {
+        LanProxy::new(self, ewon.into(), ip, port)
+    }
+
+    /// Send a GET request to the web server of an eWON, through the M2Web API, and return the HTTP response
+    /// with its slot among the requests in flight, held until the response is read.
+    async fn send_proxy_request(
+        &self,
+        ewon: EwonRef<'_>,
+        device_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<(reqwest::Response, Option<SemaphorePermit<'_>>), error::Error> {
+        let request = self
+            .build_proxy_request(Method::GET, ewon, device_path, req_query_params)
+            .await?;
+        let in_flight = self.in_flight_permit().await;
+        let http_response = self
+            .send(request, !NON_IDEMPOTENT_DEVICE_PATHS.contains(&device_path))
+            .await?;
+
+        Ok((http_response, in_flight))
+    }
+
+    /// Build a request to the web server of an eWON, through the M2Web API.
+    async fn build_proxy_request(
+        &self,
+        method: Method,
+        ewon: EwonRef<'_>,
+        device_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<reqwest::RequestBuilder, error::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("device", ewon.label());
+        let (t2m_url, ewon_name, encoded_name) = match ewon {
+            EwonRef::Id(_) => {
+                let ewon = self.get_ewon(ewon).await?;
+                (
+                    self.device_t2m_url(&EwonRef::Ewon(&ewon)),
+                    Cow::Owned(ewon.name),
+                    ewon.encoded_name,
+                )
+            }
+            EwonRef::Name(name) => (
+                self.device_t2m_url(&ewon),
+                Cow::Borrowed(name),
+                encode_ewon_name(name),
+            ),
+            EwonRef::Ewon(device) => (
+                self.device_t2m_url(&ewon),
+                Cow::Borrowed(device.name.as_str()),
+                device.encoded_name.to_owned(),
+            ),
+        };
+        let url_path = format!(
+            "get/{}/{}",
+            encoded_name,
+            device_path.trim_start_matches('/')
+        );
+
+        let mut query_params = vec![];
+        if let Some((t2m_device_username, t2m_device_password)) =
+            self.device_credentials.get(&ewon_name)
+        {
+            query_params.push(("t2mdeviceusername", t2m_device_username));
+            query_params.push(("t2mdevicepassword", t2m_device_password));
+        }
+        query_params.extend(req_query_params.unwrap_or_default());
+
+        self.build_request(method, &t2m_url, &url_path, Some(query_params))
+    }
+
+    /// Read the whole response of an eWON, and check the errors of the M2Web API itself.
+    async fn read_proxy_response(
+        http_response: reqwest::Response,
+    ) -> Result<ProxyResponse, error::Error> {
+        let http_status = http_response.status();
+        let content_type = http_response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(|content_type| content_type.to_owned());
+        let http_body = http_response.bytes().await?;
+
+        // The errors of the M2Web API itself, unlike those of the eWON, are returned as an API response.
+        if !http_status.is_success() {
+            if let Ok(api_response) = serde_json::from_slice::<ApiResponse>(&http_body) {
+                if !api_response.success {
+                    return Err(error::Error::from_api_response(
+                        http_status,
+                        api_response.code,
+                        &api_response.message,
+                    ));
+                }
+            }
+        }
+
+        Ok(ProxyResponse {
+            status: http_status.as_u16(),
+            content_type,
+            body: http_body.to_vec(),
+        })
+    }
+
+    /// Export data from an eWON to a writer, as described by an Export Block Descriptor.
+    ///
+    /// The export is written as it is received, without being buffered as a whole, which suits the large
+    /// exports of the historical logging. An export compressed with gzip is decompressed on the fly. The
+    /// progress callback is called after each written chunk, with the number of bytes written so far. The
+    /// total number of bytes written is returned once the export is complete and the writer is flushed. A
+    /// download run under a `Cancellation` stops, and drops its connection, once cancelled.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, ebd::{Ebd, EbdData, EbdTime}, error};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn download_ewon_history() -> Result<u64, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ebd = Ebd::new(EbdData::HistoricalLog).start(EbdTime::Ago(Duration::from_secs(30 * 24 * 3600)));
+    /// let mut file = tokio::fs::File::create("history.csv").await?;
+    ///
+    /// let written = client
+    ///     .download_ebd_to("bea-test", &ebd, &mut file, |written| println!("{} bytes", written))
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn download_ebd_to<'e, W>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let ebd = ebd.to_string();
+        let (http_response, _in_flight) = self
+            .send_proxy_request(
+                ewon.into(),
+                "rcgi.bin/ParamForm",
+                Some(vec![("AST_Param", ebd.as_str())]),
+            )
+            .await?;
+
+        Self::write_proxy_response(http_response, writer, true, progress).await
+    }
+
+    /// Write the response of an eWON as it is received, and check the errors.
+    ///
+    /// When asked to, a response compressed with gzip is decompressed before being written.
+    async fn write_proxy_response<W>(
+        http_response: reqwest::Response,
+        writer: &mut W,
+        decompress: bool,
+        mut progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        // The response of an unsuccessful request is small, so it is read as a whole to return the error.
+        if !http_response.status().is_success() {
+            return Self::read_proxy_response(http_response)
+                .await?
+                .error_for_status()
+                .map(|_| 0);
+        }
+
+        let mut written = 0;
+        let mut decoder = None;
+        let mut chunks = pin!(body_chunks(http_response));
+        while let Some(chunk) = chunks.next().await.transpose()? {
+            let chunk = chunk.as_ref();
+            if written == 0 && decoder.is_none() && decompress && is_gzip(chunk) {
+                decoder = Some(GzDecoder::new(vec![]));
+            }
+            let data = match decoder {
+                Some(ref mut decoder) => {
+                    decoder.write_all(chunk).map_err(gzip_error)?;
+                    Cow::Owned(std::mem::take(decoder.get_mut()))
+                }
+                None => Cow::Borrowed(chunk),
+            };
+            writer.write_all(&data).await?;
+            written += data.len() as u64;
+            progress(written);
+        }
+        if let Some(decoder) = decoder {
+            let data = decoder.finish().map_err(gzip_error)?;
+            writer.write_all(&data).await?;
+            written += data.len() as u64;
+            progress(written);
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Download a file stored on an eWON.
+    ///
+    /// The file, for example stored in the `/usr` directory of the eWON, is downloaded from the web server
+    /// of the eWON through the M2Web API.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn download_ewon_file() -> Result<Vec<u8>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let file = client.download_file("bea-test", "/usr/mydata.csv").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn download_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+    ) -> Result<Vec<u8>, error::Error> {
+        let response = self.proxy_get(ewon, path, None).await?.error_for_status()?;
+
+        Ok(response.body)
+    }
+
+    /// Download a file stored on an eWON to a writer.
+    ///
+    /// The file is written as it is received, without being buffered as a whole. The progress callback is
+    /// called after each written chunk, with the number of bytes written so far.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn download_ewon_file_to() -> Result<u64, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let mut file = tokio::fs::File::create("mydata.csv").await?;
+    /// let written = client
+    ///     .download_file_to("bea-test", "/usr/mydata.csv", &mut file, |_| {})
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn download_file_to<'e, W>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let (http_response, _in_flight) = self.send_proxy_request(ewon.into(), path, None).await?;
+
+        Self::write_proxy_response(http_response, writer, false, progress).await
+    }
+
+    /// Upload a file to an eWON.
+    ///
+    /// The file is posted to the file upload form of the eWON, which is proxied by the M2Web API, and stored
+    /// at the given path, for example in the `/usr` directory. An existing file is overwritten.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, credentials::DeviceCredentials, error};
+    /// # #[tokio::test]
+    /// # async fn upload_ewon_file() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+    ///     .build()?;
+    /// client
+    ///     .upload_file("bea-test", "/usr/recipe.txt", "PET-500;12\n")
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn upload_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        path: &str,
+        body: impl Into<Vec<u8>>,
+    ) -> Result<(), error::Error> {
+        let file_name = path.rsplit('/').next().unwrap_or_default().to_owned();
+        let form = Form::new()
+            .text("FileName", path.to_owned())
+            .part("File", Part::bytes(body.into()).file_name(file_name));
+
+        let request = self
+            .build_proxy_request(Method::POST, ewon.into(), "rcgi.bin/UploadFileForm", None)
+            .await?
+            .multipart(form);
+        let _in_flight = self.in_flight_permit().await;
+        let http_response = self.send(request, false).await?;
+        let _ = Self::read_proxy_response(http_response)
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Back up the configuration of an eWON.
+    ///
+    /// Download the `config.txt` and `comcfg.txt` files of the eWON, which requires the credentials of a
+    /// user of the eWON allowed to read its configuration.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, credentials::DeviceCredentials, config::ConfigBackup, error};
+    /// # #[tokio::test]
+    /// # async fn backup_ewon_config() -> Result<ConfigBackup, error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+    ///     .build()?;
+    /// let backup = client.backup_config("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{:?}", backup.comcfg.value("ComCfg", "EthIP"));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn backup_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<ConfigBackup, error::Error> {
+        // Request an eWON selected by its id once, rather than once per file.
+        let resolved_ewon;
+        let ewon = match ewon.into() {
+            ewon @ EwonRef::Id(_) => {
+                resolved_ewon = self.get_ewon(ewon).await?;
+                EwonRef::Ewon(&resolved_ewon)
+            }
+            ewon => ewon,
+        };
+        let taken_at = clock::now();
+        let config = self.download_file(ewon, "config.txt").await?;
+        let comcfg = self.download_file(ewon, "comcfg.txt").await?;
+
+        Ok(ConfigBackup {
+            taken_at,
+            config: ConfigFile::parse(&String::from_utf8_lossy(&config)),
+            comcfg: ConfigFile::parse(&String::from_utf8_lossy(&comcfg)),
+        })
+    }
+
+    /// Restore the configuration of an eWON from a backup.
+    ///
+    /// Upload the `config.txt` and `comcfg.txt` files of the backup to the eWON, which requires the
+    /// credentials of a user of the eWON allowed to change its configuration. The eWON applies the
+    /// communication configuration once rebooted.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, credentials::DeviceCredentials, error};
+    /// # #[tokio::test]
+    /// # async fn restore_ewon_config() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+    ///     .build()?;
+    /// let backup = client.backup_config("bea-test").await?;
+    /// client.restore_config("bea-spare", &backup).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn restore_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+    ) -> Result<(), error::Error> {
+        // Request an eWON selected by its id once, rather than once per file.
+        let resolved_ewon;
+        let ewon = match ewon.into() {
+            ewon @ EwonRef::Id(_) => {
+                resolved_ewon = self.get_ewon(ewon).await?;
+                EwonRef::Ewon(&resolved_ewon)
+            }
+            ewon => ewon,
+        };
+        self.upload_file(ewon, "/config.txt", backup.config.to_string())
+            .await?;
+        self.upload_file(ewon, "/comcfg.txt", backup.comcfg.to_string())
+            .await
+    }
+
+    /// Compare the configuration of an eWON with a backup, without changing the eWON.
+    ///
+    /// Return what `restore_config_sections()` would change on the eWON, either for the selected sections
+    /// or, when no section is selected, for the whole configuration.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn diff_ewon_config() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let backup = client.backup_config("bea-test").await?;
+    /// let diff = client
+    ///     .diff_config("bea-spare", &backup, Some(&["TagList"]))
+    ///     .await?;
+    ///
+    /// // Do something useful, for example:
+    /// diff.config
+    ///     .changed
+    ///     .iter()
+    ///     .for_each(|change| println!("{}: {:?}", change.name, change.added_lines));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn diff_config<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+        sections: Option<&[&str]>,
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        let current = self.backup_config(ewon).await?;
+        let merged = current.merge_sections(backup, sections);
+
+        Ok(ConfigBackupDiff::between(&current, &merged))
+    }
+
+    /// Restore the selected sections of the configuration of an eWON from a backup.
+    ///
+    /// The current configuration of the eWON is downloaded, its selected sections are replaced by those of
+    /// the backup, and the files which changed are uploaded back. The other sections are kept as they are on
+    /// the eWON. Return the changes applied to the eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, config::ConfigBackupDiff, error};
+    /// # #[tokio::test]
+    /// # async fn restore_ewon_tags() -> Result<ConfigBackupDiff, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let backup = client.backup_config("bea-test").await?;
+    /// let diff = client
+    ///     .restore_config_sections("bea-spare", &backup, &["TagList", "Script"])
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn restore_config_sections<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        backup: &ConfigBackup,
+        sections: &[&str],
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        // Request an eWON selected by its id once, rather than once per file.
+        let resolved_ewon;
+        let ewon = match ewon.into() {
+            ewon @ EwonRef::Id(_) => {
+                resolved_ewon = self.get_ewon(ewon).await?;
+                EwonRef::Ewon(&resolved_ewon)
+            }
+            ewon => ewon,
+        };
+        let current = self.backup_config(ewon).await?;
+        let merged = current.merge_sections(backup, Some(sections));
+        let diff = ConfigBackupDiff::between(&current, &merged);
+
+        if !diff.config.is_empty() {
+            self.upload_file(ewon, "/config.txt", merged.config.to_string())
+                .await?;
+        }
+        if !diff.comcfg.is_empty() {
+            self.upload_file(ewon, "/comcfg.txt", merged.comcfg.to_string())
+                .await?;
+        }
+
+        Ok(diff)
+    }
+
+    /// Export data from an eWON, as described by an Export Block Descriptor.
+    ///
+    /// The export is returned as is, in the format requested by the descriptor.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, ebd::{Ebd, EbdData, EbdTime}, error};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn export_ewon_events() -> Result<String, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ebd = Ebd::new(EbdData::Events).start(EbdTime::Ago(Duration::from_secs(3600)));
+    /// let export = client.export("bea-test", &ebd).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn export<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<String, error::Error> {
+        let ebd = ebd.to_string();
+        let response = self
+            .proxy_get(
+                ewon,
+                "rcgi.bin/ParamForm",
+                Some(vec![("AST_Param", ebd.as_str())]),
+            )
+            .await?
+            .error_for_status()?
+            .gunzip()?;
+
+        Ok(response.text().into_owned())
+    }
+
+    /// Return the historical logging of an eWON.
+    ///
+    /// Export the values logged by the eWON, as described by an Export Block Descriptor of the historical
+    /// logging: the time window, the tag and the groups of the descriptor select the exported values. When
+    /// the export does not name the tags, the tag list of the eWON is requested to name them. The times are
+    /// read in the timezone of the eWON, unless the descriptor exports them in UTC.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, ebd::{Ebd, EbdData, EbdTime}, error, history::LogRecord};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn get_ewon_history() -> Result<Vec<LogRecord>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ebd = Ebd::new(EbdData::HistoricalLog)
+    ///     .window(EbdTime::Ago(Duration::from_secs(24 * 3600)), EbdTime::Ago(Duration::ZERO));
+    /// let records = client.get_historical_data("bea-test", &ebd).await?;
+    ///
+    /// // Do something useful, for example:
+    /// records
+    ///     .iter()
+    ///     .for_each(|record| println!("{:?} {}: {}", record.timestamp, record.tag_name, record.value));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_historical_data<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        self.get_log(ewon.into(), ebd, EbdData::HistoricalLog).await
+    }
+
+    /// Return the real-time logging of an eWON.
+    ///
+    /// Export the values buffered by the real-time logging of the eWON, as described by an Export Block
+    /// Descriptor of the real-time logging. The real-time logging keeps the values of the last minutes at
+    /// a short interval, without enabling the historical logging of the tags.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, ebd::{Ebd, EbdData, EbdTime}, error, history::LogRecord};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn get_ewon_realtime() -> Result<Vec<LogRecord>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let ebd = Ebd::new(EbdData::RealTimeLog)
+    ///     .start(EbdTime::Ago(Duration::from_secs(5 * 60)))
+    ///     .tag("Pressure");
+    /// let records = client.get_realtime_data("bea-test", &ebd).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_realtime_data<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        ebd: &Ebd,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        self.get_log(ewon.into(), ebd, EbdData::RealTimeLog).await
+    }
+
+    /// Return the event log of an eWON, between both times.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, ebd::EbdTime, error, event::EventEntry};
+    /// # use std::time::Duration;
+    /// # #[tokio::test]
+    /// # async fn get_ewon_events() -> Result<Vec<EventEntry>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let events = client
+    ///     .get_events(
+    ///         "bea-test",
+    ///         EbdTime::Ago(Duration::from_secs(24 * 3600)),
+    ///         EbdTime::Ago(Duration::ZERO),
+    ///     )
+    ///     .await?;
+    ///
+    /// // Do something useful, for example:
+    /// events
+    ///     .iter()
+    ///     .for_each(|event| println!("[{}] {}: {}", event.level, event.origin, event.message));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_events<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        start: EbdTime,
+        end: EbdTime,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        let ewon = ewon.into();
+        let export = self
+            .export(ewon, &Ebd::new(EbdData::Events).window(start, end))
+            .await?;
+
+        EventEntry::parse_events(&export, self.device_timezone(ewon).await?)
+    }
+
+    /// Return the events of the event file of an eWON.
+    ///
+    /// Download the `events.txt` file of the eWON, which holds its latest events whatever its logging
+    /// configuration, rather than exporting the event log.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, event::EventEntry};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_event_file() -> Result<Vec<EventEntry>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let events = client.get_event_file("bea-test").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_event_file<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        let ewon = ewon.into();
+        let file = self.download_file(ewon, "events.txt").await?;
+
+        EventEntry::parse_events(
+            &String::from_utf8_lossy(&file),
+            self.device_timezone(ewon).await?,
+        )
+    }
+
+    /// Return the values logged by an eWON, checking the descriptor exports the expected logging.
+    async fn get_log(
+        &self,
+        ewon: EwonRef<'_>,
+        ebd: &Ebd,
+        data: EbdData,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        if ebd.data() != data {
+            return Err(error::Error {
+                http_status: 400,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::MissingOrWrongParameter(format!(
+                    "Export Block Descriptor [{}] does not export the {}",
+                    ebd, data
+                )),
+            });
+        }
+
+        let table = ExportParser::new().parse(&self.export(ewon, ebd).await?)?;
+        let tag_names = match table.column("TagName") {
+            Some(_) => HashMap::new(),
+            None => self
+                .list_tags(ewon)
+                .await?
+                .into_iter()
+                .map(|tag| (tag.id, tag.name))
+                .collect(),
+        };
+
+        let timezone = match ebd.is_utc() {
+            true => Tz::UTC,
+            false => self.device_timezone(ewon).await?,
+        };
+
+        LogRecord::parse_log(&table, &tag_names, timezone)
+    }
+
+    /// Return the timezone in which an eWON exports its times.
+    ///
+    /// The name of an eWON referenced by its id is only requested when some eWONs have their own timezone.
+    async fn device_timezone(&self, ewon: EwonRef<'_>) -> Result<Tz, error::Error> {
+        match ewon {
+            EwonRef::Id(_) if self.device_timezones.has_devices() => {
+                Ok(self.device_timezones.get(&self.get_ewon(ewon).await?.name))
+            }
+            EwonRef::Id(_) => Ok(self.device_timezones.get("")),
+            EwonRef::Name(name) => Ok(self.device_timezones.get(name)),
+            EwonRef::Ewon(device) => Ok(self.device_timezones.get(&device.name)),
+        }
+    }
+
+    /// Return the system information of an eWON.
+    ///
+    /// Export the status of the eWON, which holds its serial number, its firmware version, its product code
+    /// and its uptime.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, device::DeviceInfo, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_info() -> Result<DeviceInfo, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let info = client.get_device_info("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{}: {}", info.serial_number, info.firmware_version);
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_device_info<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<DeviceInfo, error::Error> {
+        let export = self.export(ewon, &Ebd::new(EbdData::Status)).await?;
+
+        DeviceInfo::parse_status(&export)
+    }
+
+    /// Return the instant values of the tags of an eWON.
+    ///
+    /// Export the instant values of all the tags through the M2Web API. The values are timestamped with
+    /// the time at which the export is received.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, tag::TagValue};
+    /// # #[tokio::test]
+    /// # async fn read_ewon_tags() -> Result<Vec<TagValue>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let tags = client.read_tags("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// tags.iter()
+    ///     .for_each(|tag| println!("{}: {}", tag.name, tag.value));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn read_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<TagValue>, error::Error> {
+        self.read_group_tags(ewon, &[]).await
+    }
+
+    /// Return the instant values of the tags of an eWON which belong to the given groups.
+    ///
+    /// Only the tags of the groups are exported, which spares exporting all the tags of the large eWONs.
+    /// All the tags are exported if no group is given.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, tag::{TagGroup, TagValue}};
+    /// # #[tokio::test]
+    /// # async fn read_ewon_group_tags() -> Result<Vec<TagValue>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let tags = client.read_group_tags("bea-test", &[TagGroup::A]).await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn read_group_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        groups: &[TagGroup],
+    ) -> Result<Vec<TagValue>, error::Error> {
+        let ebd = Ebd::new(EbdData::InstantValues).groups(groups);
+        let export = self.export(ewon, &ebd).await?;
+
+        TagValue::parse_instant_values(&export, clock::now())
+    }
+
+    /// Return the tags configured on an eWON.
+    ///
+    /// Export the tag list through the M2Web API, with the type, unit, alarm parameters and groups of
+    /// each tag.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, tag::Tag};
+    /// # #[tokio::test]
+    /// # async fn list_ewon_tags() -> Result<Vec<Tag>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let tags = client.list_tags("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// tags.iter()
+    ///     .for_each(|tag| println!("{} ({}): {:?}", tag.name, tag.unit, tag.tag_type));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn list_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<Tag>, error::Error> {
+        self.list_group_tags(ewon, &[]).await
+    }
+
+    /// Return the tags configured on an eWON which belong to the given groups.
+    ///
+    /// All the tags are returned if no group is given.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn list_group_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        groups: &[TagGroup],
+    ) -> Result<Vec<Tag>, error::Error> {
+        let ebd = Ebd::new(EbdData::TagList).groups(groups);
+        let export = self.export(ewon, &ebd).await?;
+
+        Tag::parse_tag_list(&export)
+    }
+
+    /// Write the value of a tag of an eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, credentials::DeviceCredentials, error};
+    /// # #[tokio::test]
+    /// # async fn write_ewon_tag() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default()
+    ///     .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+    ///     .build()?;
+    /// client.write_tag("bea-test", "Setpoint", 21.5).await?;
+    /// client.write_tag("bea-test", "Recipe", "PET-500").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn write_tag<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        name: &str,
+        value: impl Into<TagData>,
+    ) -> Result<(), error::Error> {
+        self.write_tags(ewon, &[(name, value.into())]).await
+    }
+
+    /// Write the values of several tags of an eWON at once.
+    ///
+    /// The values are written through the tag update form of the eWON, which is proxied by the M2Web API.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error, tag::TagData};
+    /// # #[tokio::test]
+    /// # async fn write_ewon_tags() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// client
+    ///     .write_tags(
+    ///         "bea-test",
+    ///         &[("Setpoint", TagData::from(21.5)), ("Start", TagData::from(true))],
+    ///     )
+    ///     .await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn write_tags<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        tags: &[(&str, TagData)],
+    ) -> Result<(), error::Error> {
+        let params = tags
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (name, value))| {
+                [
+                    (format!("TagName{}", index + 1), name.to_string()),
+                    (format!("TagValue{}", index + 1), value.to_string()),
+                ]
+            })
+            .collect::<Vec<(String, String)>>();
+        let query_params = params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let _ = self
+            .proxy_get(ewon, "rcgi.bin/UpdateTagForm", Some(query_params))
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Return the active alarms of an eWON.
+    ///
+    /// Export the alarms which are raised, acknowledged or not yet acknowledged after returning to normal.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{alarm::Alarm, client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_alarms() -> Result<Vec<Alarm>, error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let alarms = client.get_alarms("bea-test").await?;
+    ///
+    /// // Do something useful, for example:
+    /// alarms
+    ///     .iter()
+    ///     .for_each(|alarm| println!("{}: {:?}", alarm.tag_name, alarm.status));
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_alarms<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<Vec<Alarm>, error::Error> {
+        let ewon = ewon.into();
+        let export = self.export(ewon, &Ebd::new(EbdData::Alarms)).await?;
+
+        Alarm::parse_alarms(&export, self.device_timezone(ewon).await?)
+    }
+
+    /// Acknowledge the alarm of a tag of an eWON.
+    ///
+    /// The alarm is acknowledged by the user the client authenticates with against the eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn ack_ewon_alarm() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// client.ack_alarm("bea-test", "Temperature").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn ack_alarm<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+        tag: &str,
+    ) -> Result<(), error::Error> {
+        let command = format!("ALACK \"{}\",0", tag.replace('"', "\"\""));
+
+        self.execute_script(ewon, &command).await
+    }
+
+    /// Start the BASIC script of an eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn restart_ewon_script() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// client.stop_script("bea-test").await?;
+    /// client.start_script("bea-test").await?;
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn start_script<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<(), error::Error> {
+        self.execute_script(ewon, "RUN").await
+    }
+
+    /// Stop the BASIC script of an eWON.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn stop_script<'e>(&self, ewon: impl Into<EwonRef<'e>>) -> Result<(), error::Error> {
+        self.execute_script(ewon, "HALT").await
+    }
+
+    /// Return the run state of the BASIC script of an eWON.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, device::ScriptState, error};
+    /// # #[tokio::test]
+    /// # async fn get_ewon_script_state() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// if client.get_script_state("bea-test").await? == ScriptState::Stopped {
+    ///     client.start_script("bea-test").await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(device = tracing::field::Empty)))]
+    pub async fn get_script_state<'e>(
+        &self,
+        ewon: impl Into<EwonRef<'e>>,
+    ) -> Result<ScriptState, error::Error> {
+        self.get_device_info(ewon).await?.script_state()
+    }
+
+    /// Execute a BASIC command on an eWON, through its script execution form.
+    async fn execute_script(
+        &self,
+        ewon: impl Into<EwonRef<'_>>,
+        command: &str,
+    ) -> Result<(), error::Error> {
+        let _ = self
+            .proxy_get(
+                ewon,
+                "rcgi.bin/ExeScriptForm",
+                Some(vec![("Command1", command)]),
+            )
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Request an endpoint of the API, and return the HTTP status and the JSON body of its response.
+    ///
+    /// The request holds the authentication parameters of the client, followed by the given parameters, and
+    /// goes through the retry policy, the rate limiter and the HTTP service of the client like the other
+    /// requests. Its response is returned as is, whatever its status or its `success` field, and an empty
+    /// body as `null`: it allows to call the endpoints, or to read the fields, not covered by this client
+    /// yet.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use libewon::m2web::{client::ClientBuilder, error};
+    /// # #[tokio::test]
+    /// # async fn get_raw_account_info() -> Result<(), error::Error> {
+    /// let client = ClientBuilder::default().build()?;
+    /// let (status, account_info) = client.request_raw("getaccountinfo", None).await?;
+    ///
+    /// // Do something useful, for example:
+    /// println!("{}: {}", status, account_info["accountType"]);
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn request_raw(
+        &self,
+        endpoint: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<(reqwest::StatusCode, serde_json::Value), error::Error> {
+        let (http_status, http_body) = self
+            .request_api_body(self.t2m_url, endpoint, req_query_params)
+            .await?;
+
+        match http_body.trim().is_empty() {
+            true => Ok((http_status, serde_json::Value::Null)),
+            false => Ok((
+                http_status,
+                serde_json::from_str(&http_body)
+                    .map_err(|err| error::response_parsing_error(err, endpoint, &http_body))?,
+            )),
+        }
+    }
+
+    /// Perform the request and check the HTTP error codes.
+    async fn request_api(
+        &self,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ApiResponse, error::Error> {
+        self.request_api_at(self.t2m_url, url_path, req_query_params)
+            .await
+    }
+
+    /// Perform a device-scoped request and check the HTTP error codes.
+    ///
+    /// The request is routed to the M2Web server of the eWON, unless it is unknown or overridden.
+    async fn request_device_api(
+        &self,
+        ewon: &EwonRef<'_>,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ApiResponse, error::Error> {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("device", ewon.label());
+        let t2m_url = self.device_t2m_url(ewon);
+        self.request_api_at(&t2m_url, url_path, req_query_params)
+            .await
+    }
+
+    /// Return the API base url of the device-scoped requests to the eWON.
+    fn device_t2m_url(&self, ewon: &EwonRef<'_>) -> Cow<'a, str> {
+        match (self.t2m_device_url, ewon) {
+            (Some(t2m_device_url), _) => Cow::Borrowed(t2m_device_url),
+            (None, EwonRef::Ewon(ewon)) if !ewon.m2web_server.is_empty() => {
+                Cow::Owned(format!("https://{}/t2mapi", ewon.m2web_server))
+            }
+            (None, _) => Cow::Borrowed(self.t2m_url),
+        }
+    }
+
+    /// Perform the request to the API base url and check the HTTP error codes.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(endpoint = url_path)))]
+    async fn request_api_at(
+        &self,
+        t2m_url: &str,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ApiResponse, error::Error> {
+        let (http_status, http_body) = self
+            .request_api_body(t2m_url, url_path, req_query_params)
+            .await?;
+        let api_response = serde_json::from_str::<ApiResponse>(&http_body)
+            .map_err(|err| error::response_parsing_error(err, url_path, &http_body))?;
+
+        match api_response.success {
+            true => Ok(api_response),
+            false => Err(error::Error::from_api_response(
+                http_status,
+                api_response.code,
+                &api_response.message,
+            )),
+        }
+    }
+
+    /// Perform the request and return the HTTP status code and the raw body of the response.
+    async fn request_api_body(
+        &self,
+        t2m_url: &str,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<(reqwest::StatusCode, String), error::Error> {
+        let _in_flight = self.in_flight_permit().await;
+        let http_response = self
+            .send_request(t2m_url, url_path, req_query_params)
+            .await?;
+
+        let http_status = http_response.status();
+        let http_body = http_response.text().await?;
+
+        Ok((http_status, http_body))
+    }
+
+    /// Send the request with the authentication parameters and return the HTTP response.
+    async fn send_request(
+        &self,
+        t2m_url: &str,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<reqwest::Response, error::Error> {
+        let request = self.build_request(Method::GET, t2m_url, url_path, req_query_params)?;
+
+        self.send(request, !NON_IDEMPOTENT_URL_PATHS.contains(&url_path))
+            .await
+    }
+
+    /// Wait for a slot among the requests in flight, if their number is capped.
+    async fn in_flight_permit(&self) -> Option<SemaphorePermit<'_>> {
+        match self.in_flight {
+            Some(ref in_flight) => in_flight.acquire().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Send the request under the rate limiter, and retry it on transient errors if it is idempotent.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        idempotent: bool,
+    ) -> Result<reqwest::Response, error::Error> {
+        self.retry_policy
+            .send(
+                self.timeouts.apply(request).headers(self.headers.clone()),
+                idempotent,
+                Middleware {
+                    rate_limiter: self.rate_limiter.as_ref(),
+                    circuit_breaker: self.circuit_breaker.as_ref(),
+                    http_service: self.http_service.as_ref(),
+                    failover: self.failover.as_ref(),
+                    metrics_observer: self.metrics_observer.as_deref(),
+                    interceptors: &self.interceptors,
+                },
+            )
+            .await
+    }
+
+    /// Build the request, with the authentication parameters.
+    fn build_request(
+        &self,
+        method: Method,
+        t2m_url: &str,
+        url_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<reqwest::RequestBuilder, error::Error> {
+        // Check if the endpoint is provided.
+        if url_path.is_empty() {
+            return Err(error::Error {
+                http_status: 500,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::InternalError("no API endpoint provided".to_string()),
+            });
+        }
+        // Check if the auth is stateful or not.
         let mut query_params = match self.stateful_auth {
             true => match url_path {
                 // In case of stateful request, check if the user is performing a login.
@@ -233,7 +2509,9 @@ impl<'a> Client<'a> {
                     } else {
                         // If the session id does not exist and the user is not performin a login, return an error.
                         return Err(error::Error {
-                            code: 403,
+                            http_status: 403,
+                            api_code: None,
+                            source: None,
                             kind: error::ErrorKind::InvalidCredentials(
                                 "No session opened, please login before requesting the API"
                                     .to_string(),
@@ -257,65 +2535,66 @@ impl<'a> Client<'a> {
                 .for_each(|param| query_params.push(param.to_owned()));
         }
 
-        let http_response = self
+        Ok(self
             .http_client
-            .get(format!("{}/{}", self.t2m_url, url_path))
-            .query(&query_params)
-            .send()
-            .await?;
-
-        let http_status = http_response.status();
-        let http_body = http_response.text().await?;
-        let api_response = serde_json::from_str::<ApiResponse>(&http_body)?;
-
-        match api_response.success {
-            true => Ok(api_response),
-            false => match http_status {
-                reqwest::StatusCode::BAD_REQUEST => Err(error::Error {
-                    code: http_status.as_u16(),
-                    kind: error::ErrorKind::MissingOrWrongParameter(format!(
-                        "{}",
-                        api_response.message
-                    )),
-                }),
-                reqwest::StatusCode::FORBIDDEN => match api_response.message.as_ref() {
-                    "Invalid credentials" => Err(error::Error {
-                        code: http_status.as_u16(),
-                        kind: error::ErrorKind::InvalidCredentials(format!(
-                            "{}",
-                            api_response.message
-                        )),
-                    }),
-                    _ => Err(error::Error {
-                        code: 403,
-                        kind: error::ErrorKind::MissingOrWrongParameter(format!(
-                            "{}",
-                            api_response.message
-                        )),
-                    }),
-                },
-                reqwest::StatusCode::GONE => Err(error::Error {
-                    code: http_status.as_u16(),
-                    kind: error::ErrorKind::EmptyResponse(format!("{}", api_response.message)),
-                }),
-                _ => Err(error::Error {
-                    code: 500,
-                    kind: error::ErrorKind::UnknownError("Unkown error occurred".to_string()),
-                }),
-            },
-        }
+            .request(method, format!("{}/{}", t2m_url, url_path))
+            .query(&query_params))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::m2web::{client, error};
+    use crate::m2web::{
+        client, error,
+        ewon::{Ewon, EwonRef},
+        region::Region,
+    };
     use serde_json::json;
     use wiremock::{
         matchers::{method, path, query_param},
         Mock, MockServer, ResponseTemplate,
     };
 
+    #[test]
+    fn client_builder_region_ok() {
+        let client = client::ClientBuilder::default().build().unwrap();
+        assert_eq!(client.t2m_url, "https://m2web.talk2m.com/t2mapi");
+
+        let client = client::ClientBuilder::default()
+            .region(Region::Eu2)
+            .build()
+            .unwrap();
+        assert_eq!(client.t2m_url, "https://eu2.m2web.talk2m.com/t2mapi");
+    }
+
+    #[test]
+    fn device_t2m_url_ok() {
+        let ewon = Ewon {
+            id: 1206698,
+            m2web_server: "eu2.m2web.talk2m.com".to_string(),
+            ..Default::default()
+        };
+
+        let client = client::ClientBuilder::default().build().unwrap();
+        assert_eq!(
+            client.device_t2m_url(&EwonRef::Ewon(&ewon)),
+            "https://eu2.m2web.talk2m.com/t2mapi"
+        );
+        assert_eq!(
+            client.device_t2m_url(&EwonRef::Id(1206698)),
+            "https://m2web.talk2m.com/t2mapi"
+        );
+
+        let client = client::ClientBuilder::default()
+            .t2m_device_url("https://proxy.smag-graphique.com/t2mapi")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.device_t2m_url(&EwonRef::Ewon(&ewon)),
+            "https://proxy.smag-graphique.com/t2mapi"
+        );
+    }
+
     #[tokio::test]
     async fn request_api_empty_endpoint_ko() {
         let client = client::ClientBuilder::default().build().unwrap();
@@ -327,7 +2606,9 @@ mod test {
         assert_eq!(
             api_response,
             error::Error {
-                code: 500,
+                http_status: 500,
+                api_code: None,
+                source: None,
                 kind: error::ErrorKind::InternalError("no API endpoint provided".to_string())
             }
         );
@@ -374,10 +2655,10 @@ mod test {
         assert_eq!(
             api_response,
             error::Error {
-                code: 403,
-                kind: error::ErrorKind::MissingOrWrongParameter(
-                    "Method [wrong] is invalid".to_string()
-                ),
+                http_status: 403,
+                api_code: Some(403),
+                source: None,
+                kind: error::ErrorKind::UnknownMethod("Method [wrong] is invalid".to_string()),
             }
         );
     }
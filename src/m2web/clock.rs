@@ -0,0 +1,35 @@
+//! Timers and clocks of the clients, which the browsers provide rather than tokio and the system clock.
+//!
+//! On wasm32, neither the timers of tokio, which need a tokio runtime, nor `std::time::Instant::now()` and
+//! `std::time::SystemTime::now()`, which panic, are available. The timers of the browser are used instead.
+
+use std::time::SystemTime;
+#[cfg(target_arch = "wasm32")]
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::{interval, sleep, MissedTickBehavior};
+#[cfg(target_arch = "wasm32")]
+pub use wasmtimer::std::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use wasmtimer::tokio::{interval, sleep, sleep_until, MissedTickBehavior};
+
+/// Wait until the deadline.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep_until(deadline: Instant) {
+    tokio::time::sleep_until(deadline.into()).await
+}
+
+/// Return the current time.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+/// Return the current time, read from the clock of the browser.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
+}
@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, time::SystemTime};
+
+/// Section of a configuration file of an eWON.
+///
+/// A section starts with a line made of a colon followed by its name, for example `:System`. Its lines are
+/// kept as is: most of them are `Key:Value` parameters, while the lists, such as the tags or the users, are
+/// semicolon-separated lines.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSection {
+    /// The name of the section, empty for the lines preceding the first section.
+    pub name: String,
+    /// The lines of the section, without the line naming the section.
+    pub lines: Vec<String>,
+}
+
+impl ConfigSection {
+    /// Return the value of the `Key:Value` parameter with this key, if any.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.lines
+            .iter()
+            .find_map(|line| match line.split_once(':') {
+                Some((line_key, value)) if line_key == key => Some(value),
+                _ => None,
+            })
+    }
+}
+
+/// Configuration file of an eWON, split into its sections.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::config::ConfigFile;
+/// let comcfg = ConfigFile::parse(":ComCfg\r\nEthIP:10.0.0.53\r\nEthMask:255.255.255.0\r\n");
+///
+/// assert_eq!(comcfg.value("ComCfg", "EthIP"), Some("10.0.0.53"));
+/// assert_eq!(comcfg.to_string(), ":ComCfg\r\nEthIP:10.0.0.53\r\nEthMask:255.255.255.0\r\n");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFile {
+    /// The sections of the file, in the order of the file.
+    pub sections: Vec<ConfigSection>,
+}
+
+impl ConfigFile {
+    /// Parse a configuration file, as downloaded from the eWON.
+    ///
+    /// The empty lines are skipped. The lines preceding the first section, if any, are gathered in a
+    /// section without name.
+    pub fn parse(text: &str) -> Self {
+        let mut sections: Vec<ConfigSection> = vec![];
+        for line in text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty())
+        {
+            match line.strip_prefix(':') {
+                Some(name) => sections.push(ConfigSection {
+                    name: name.trim().to_owned(),
+                    lines: vec![],
+                }),
+                None => match sections.last_mut() {
+                    Some(section) => section.lines.push(line.to_owned()),
+                    None => sections.push(ConfigSection {
+                        name: String::new(),
+                        lines: vec![line.to_owned()],
+                    }),
+                },
+            }
+        }
+
+        ConfigFile { sections }
+    }
+
+    /// Return the section with this name, if any.
+    pub fn section(&self, name: &str) -> Option<&ConfigSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Return the value of the `Key:Value` parameter with this key in the section with this name, if any.
+    pub fn value(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section)?.value(key)
+    }
+
+    /// Return this file, with the selected sections taken from the other file.
+    ///
+    /// The selected sections keep their place in this file, those only found in the other file are
+    /// appended, and those missing from the other file are removed. When no section is selected, the other
+    /// file is returned as a whole.
+    pub fn merge_sections(&self, other: &ConfigFile, sections: Option<&[&str]>) -> ConfigFile {
+        let sections = match sections {
+            Some(sections) => sections,
+            None => return other.to_owned(),
+        };
+        let selected = |section: &ConfigSection| sections.contains(&section.name.as_str());
+
+        let mut merged = self
+            .sections
+            .iter()
+            .filter_map(|section| match selected(section) {
+                true => other.section(&section.name).cloned(),
+                false => Some(section.to_owned()),
+            })
+            .collect::<Vec<ConfigSection>>();
+        merged.extend(
+            other
+                .sections
+                .iter()
+                .filter(|section| selected(section) && self.section(&section.name).is_none())
+                .cloned(),
+        );
+
+        ConfigFile { sections: merged }
+    }
+}
+
+/// Compose the configuration file, as expected by the eWON.
+impl fmt::Display for ConfigFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for section in &self.sections {
+            if !section.name.is_empty() {
+                write!(f, ":{}\r\n", section.name)?;
+            }
+            for line in &section.lines {
+                write!(f, "{}\r\n", line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Backup of the configuration of an eWON.
+///
+/// The configuration of an eWON is split into two files: `config.txt` holds the system configuration, the
+/// tags, the users and the scripts, while `comcfg.txt` holds the communication configuration, such as the
+/// network interfaces and the VPN.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigBackup {
+    /// The time at which the backup has been taken.
+    pub taken_at: SystemTime,
+    /// The system configuration, from `config.txt`.
+    pub config: ConfigFile,
+    /// The communication configuration, from `comcfg.txt`.
+    pub comcfg: ConfigFile,
+}
+
+impl ConfigBackup {
+    /// Return this backup, with the selected sections of both files taken from the other backup.
+    ///
+    /// See `ConfigFile::merge_sections()`. The time of the backup is the time of the other backup.
+    pub fn merge_sections(&self, other: &ConfigBackup, sections: Option<&[&str]>) -> ConfigBackup {
+        ConfigBackup {
+            taken_at: other.taken_at,
+            config: self.config.merge_sections(&other.config, sections),
+            comcfg: self.comcfg.merge_sections(&other.comcfg, sections),
+        }
+    }
+}
+
+/// Differences between two configuration files.
+///
+/// The sections are matched by their name, and the lines of a section are compared regardless of their
+/// order.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::config::{ConfigDiff, ConfigFile};
+/// let old = ConfigFile::parse(":ComCfg\r\nEthIP:10.0.0.53\r\n");
+/// let new = ConfigFile::parse(":ComCfg\r\nEthIP:10.0.0.54\r\n");
+///
+/// let diff = ConfigDiff::between(&old, &new);
+/// assert_eq!(diff.changed[0].added_lines, ["EthIP:10.0.0.54"]);
+/// assert_eq!(diff.changed[0].removed_lines, ["EthIP:10.0.0.53"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigDiff {
+    /// The sections which only belong to the new file.
+    pub added: Vec<ConfigSection>,
+    /// The sections which only belong to the old file.
+    pub removed: Vec<ConfigSection>,
+    /// The sections which belong to both files, but with different lines.
+    pub changed: Vec<ConfigSectionChange>,
+}
+
+/// Changes of a section between two configuration files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigSectionChange {
+    /// The name of the section.
+    pub name: String,
+    /// The lines which only belong to the new section.
+    pub added_lines: Vec<String>,
+    /// The lines which only belong to the old section.
+    pub removed_lines: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Compare the old configuration file with the new one.
+    ///
+    /// The added, removed and changed sections keep the order of the file they are taken from.
+    pub fn between(old: &ConfigFile, new: &ConfigFile) -> Self {
+        let mut diff = ConfigDiff::default();
+        for new_section in &new.sections {
+            match old.section(&new_section.name) {
+                None => diff.added.push(new_section.to_owned()),
+                Some(old_section) => {
+                    let added_lines = missing_lines(&new_section.lines, &old_section.lines);
+                    let removed_lines = missing_lines(&old_section.lines, &new_section.lines);
+                    if !added_lines.is_empty() || !removed_lines.is_empty() {
+                        diff.changed.push(ConfigSectionChange {
+                            name: new_section.name.to_owned(),
+                            added_lines,
+                            removed_lines,
+                        });
+                    }
+                }
+            }
+        }
+        diff.removed = old
+            .sections
+            .iter()
+            .filter(|old_section| new.section(&old_section.name).is_none())
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Return `true` if both files hold the same sections with the same lines.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Return the lines which are not found in the other lines, each line of the other lines matching once.
+fn missing_lines(lines: &[String], other_lines: &[String]) -> Vec<String> {
+    let mut other_lines = other_lines.iter().collect::<Vec<&String>>();
+    lines
+        .iter()
+        .filter(
+            |line| match other_lines.iter().position(|other| other == line) {
+                Some(index) => {
+                    other_lines.swap_remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+/// Differences between two configuration backups.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigBackupDiff {
+    /// The differences of the system configuration, from `config.txt`.
+    pub config: ConfigDiff,
+    /// The differences of the communication configuration, from `comcfg.txt`.
+    pub comcfg: ConfigDiff,
+}
+
+impl ConfigBackupDiff {
+    /// Compare the old configuration backup with the new one.
+    pub fn between(old: &ConfigBackup, new: &ConfigBackup) -> Self {
+        ConfigBackupDiff {
+            config: ConfigDiff::between(&old.config, &new.config),
+            comcfg: ConfigDiff::between(&old.comcfg, &new.comcfg),
+        }
+    }
+
+    /// Return `true` if both backups hold the same configuration.
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty() && self.comcfg.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::config::{ConfigDiff, ConfigFile};
+
+    #[test]
+    fn config_file_ok() {
+        let text = "\u{feff}Version:14.7\r\n\
+            :System\r\n\
+            Identification:bea-test\r\n\
+            Information:Line 2; \"filler\"\r\n\
+            \r\n\
+            :TagList\r\n\
+            \"Id\";\"Name\"\r\n\
+            1;\"Temperature\"\r\n";
+
+        let config = ConfigFile::parse(text);
+
+        assert_eq!(config.sections.len(), 3);
+        assert_eq!(config.sections[0].name, "");
+        assert_eq!(config.value("", "Version"), Some("14.7"));
+        assert_eq!(
+            config.value("System", "Information"),
+            Some("Line 2; \"filler\"")
+        );
+        assert_eq!(config.value("System", "Missing"), None);
+        assert_eq!(config.section("TagList").unwrap().lines.len(), 2);
+        assert_eq!(
+            config.to_string(),
+            text.trim_start_matches('\u{feff}')
+                .replace("\r\n\r\n", "\r\n")
+        );
+    }
+
+    #[test]
+    fn config_merge_and_diff_ok() {
+        let current = ConfigFile::parse(
+            ":System\r\nIdentification:bea-test\r\n:User\r\n\"adm\"\r\n:Script\r\nPRINT 1\r\n",
+        );
+        let backup = ConfigFile::parse(
+            ":System\r\nIdentification:bea-old\r\n:TagList\r\n1;\"Temperature\"\r\n:Script\r\nPRINT 2\r\n",
+        );
+
+        let merged = current.merge_sections(&backup, Some(&["TagList", "User", "Script"]));
+        assert_eq!(
+            merged.to_string(),
+            ":System\r\nIdentification:bea-test\r\n:Script\r\nPRINT 2\r\n:TagList\r\n1;\"Temperature\"\r\n"
+        );
+        assert_eq!(current.merge_sections(&backup, None), backup);
+
+        let diff = ConfigDiff::between(&current, &merged);
+        assert_eq!(diff.added[0].name, "TagList");
+        assert_eq!(diff.removed[0].name, "User");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].name, "Script");
+        assert_eq!(diff.changed[0].added_lines, ["PRINT 2"]);
+        assert_eq!(diff.changed[0].removed_lines, ["PRINT 1"]);
+        assert!(ConfigDiff::between(&merged, &merged).is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+/// Credentials used to authenticate against the eWONs, for the requests proxied to them.
+///
+/// The requests proxied to an eWON, such as the exports or the tag updates, are authenticated by the eWON
+/// itself with one of its users. The credentials of an eWON are looked up by its name: the credentials set
+/// for this eWON are used if any, otherwise the default credentials.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, credentials::DeviceCredentials};
+/// let credentials = DeviceCredentials::new()
+///     .default_credentials("adm", "adm")
+///     .device("bea-test", "operator", "secret");
+///
+/// let client = ClientBuilder::default()
+///     .device_credentials(credentials)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceCredentials<'a> {
+    /// The username and password used for the eWONs without their own credentials.
+    default: Option<(&'a str, &'a str)>,
+    /// The username and password of the eWONs, keyed by the name of the eWON.
+    devices: HashMap<&'a str, (&'a str, &'a str)>,
+}
+
+impl<'a> DeviceCredentials<'a> {
+    /// Create a provider without any credentials.
+    pub fn new() -> Self {
+        DeviceCredentials::default()
+    }
+
+    /// Authenticate against the eWONs without their own credentials with this user.
+    pub fn default_credentials(mut self, username: &'a str, password: &'a str) -> Self {
+        self.default = Some((username, password));
+        self
+    }
+
+    /// Authenticate against the eWON with this name with this user.
+    pub fn device(mut self, ewon_name: &'a str, username: &'a str, password: &'a str) -> Self {
+        self.devices.insert(ewon_name, (username, password));
+        self
+    }
+
+    /// Return the username and password used to authenticate against the eWON with this name, if any.
+    pub fn get(&self, ewon_name: &str) -> Option<(&'a str, &'a str)> {
+        self.devices.get(ewon_name).copied().or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::credentials::DeviceCredentials;
+
+    #[test]
+    fn device_credentials_ok() {
+        let credentials = DeviceCredentials::new().device("bea-test", "operator", "secret");
+        assert_eq!(credentials.get("bea-test"), Some(("operator", "secret")));
+        assert_eq!(credentials.get("bea-spare"), None);
+
+        let credentials = credentials.default_credentials("adm", "adm");
+        assert_eq!(credentials.get("bea-test"), Some(("operator", "secret")));
+        assert_eq!(credentials.get("bea-spare"), Some(("adm", "adm")));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::m2web::{error, parser::ExportParser};
+use std::{collections::HashMap, time::Duration};
+
+/// Run state of the BASIC script of an eWON.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ScriptState {
+    /// The script is running.
+    Running,
+    /// The script is stopped.
+    Stopped,
+}
+
+/// System information of an eWON.
+///
+/// The information is taken from the status of the eWON, which is not exposed by the M2Web API itself.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceInfo {
+    /// The serial number of the eWON.
+    pub serial_number: String,
+    /// The version of the firmware of the eWON.
+    pub firmware_version: String,
+    /// The product code of the eWON, which identifies its model.
+    pub product_code: String,
+    /// The time elapsed since the eWON booted.
+    pub uptime: Duration,
+    /// All the parameters of the status, including those above, keyed by their name.
+    pub parameters: HashMap<String, String>,
+}
+
+impl DeviceInfo {
+    /// Return the run state of the BASIC script, as exported in the status of the eWON.
+    pub fn script_state(&self) -> Result<ScriptState, error::Error> {
+        match self
+            .parameters
+            .get("BasicRunning")
+            .map(|running| running.as_str())
+        {
+            Some("1") => Ok(ScriptState::Running),
+            Some("0") => Ok(ScriptState::Stopped),
+            Some(running) => Err(error::Error {
+                http_status: 500,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::ExportParsing(format!(
+                    "[{}] is not a valid script state",
+                    running
+                )),
+            }),
+            None => Err(missing_parameter("BasicRunning")),
+        }
+    }
+
+    /// Parse the status exported by the eWON.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per parameter. The uptime is
+    /// exported as seconds.
+    pub(in crate::m2web) fn parse_status(export: &str) -> Result<DeviceInfo, error::Error> {
+        let table = ExportParser::new().parse(export)?;
+        let (name_column, value_column) = (
+            table.required_column("Name")?,
+            table.required_column("Value")?,
+        );
+
+        let parameters = table
+            .rows()
+            .iter()
+            .map(|row| {
+                Ok((
+                    row.text(name_column)?.to_owned(),
+                    row.text(value_column)?.trim().to_owned(),
+                ))
+            })
+            .collect::<Result<HashMap<String, String>, error::Error>>()?;
+        let parameter = |name: &str| {
+            parameters
+                .get(name)
+                .cloned()
+                .ok_or_else(|| missing_parameter(name))
+        };
+        let uptime = parameter("UpTime")?;
+
+        Ok(DeviceInfo {
+            serial_number: parameter("SerNum")?,
+            firmware_version: parameter("FwrVersion")?,
+            product_code: parameter("PCode")?,
+            uptime: Duration::from_secs(uptime.parse().map_err(|_| error::Error {
+                http_status: 500,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::ExportParsing(format!(
+                    "[{}] is not a valid uptime",
+                    uptime
+                )),
+            })?),
+            parameters,
+        })
+    }
+}
+
+/// Return an error about a parameter missing from the status of the eWON.
+fn missing_parameter(name: &str) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        source: None,
+        kind: error::ErrorKind::ExportParsing(format!("missing parameter [{}]", name)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::device::{DeviceInfo, ScriptState};
+    use std::time::Duration;
+
+    #[test]
+    fn parse_status_ok() {
+        let export = "\"Name\";\"Value\"\r\n\
+            \"SerNum\";\"1234-5678-90\"\r\n\
+            \"FwrVersion\";\"14.7s0\"\r\n\
+            \"PCode\";\"Flexy205\"\r\n\
+            \"UpTime\";\"86400\"\r\n\
+            \"EthIP\";\"10.0.0.53\"\r\n";
+
+        let info = DeviceInfo::parse_status(export).unwrap();
+
+        assert_eq!(info.serial_number, "1234-5678-90");
+        assert_eq!(info.firmware_version, "14.7s0");
+        assert_eq!(info.product_code, "Flexy205");
+        assert_eq!(info.uptime, Duration::from_secs(86400));
+        assert_eq!(info.parameters["EthIP"], "10.0.0.53");
+        assert_eq!(
+            format!("{}", info.script_state().unwrap_err()),
+            "Unable to parse export: missing parameter [BasicRunning]"
+        );
+    }
+
+    #[test]
+    fn parse_status_ko() {
+        let export = "\"Name\";\"Value\"\r\n\"SerNum\";\"1234-5678-90\"\r\n";
+
+        let err = match DeviceInfo::parse_status(export) {
+            Ok(_) => panic!("parse_status should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: missing parameter [UpTime]"
+        );
+    }
+
+    #[test]
+    fn script_state_ok() {
+        let export = "\"Name\";\"Value\"\r\n\
+            \"SerNum\";\"1234-5678-90\"\r\n\
+            \"FwrVersion\";\"14.7s0\"\r\n\
+            \"PCode\";\"Flexy205\"\r\n\
+            \"UpTime\";\"86400\"\r\n\
+            \"BasicRunning\";\"1\"\r\n";
+
+        let info = DeviceInfo::parse_status(export).unwrap();
+
+        assert_eq!(info.script_state().unwrap(), ScriptState::Running);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::m2web::ewon::Ewon;
+use std::collections::HashMap;
+
+/// Differences between two lists of eWONs.
+///
+/// Compare two snapshots of the eWONs, for example returned by two successive calls to `get_ewons()`.
+/// The eWONs are matched by their id.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, diff::EwonDiff, error};
+/// # #[tokio::test]
+/// # async fn get_ewons_changes() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let old_ewons = client.get_ewons(None).await?;
+/// let new_ewons = client.get_ewons(None).await?;
+///
+/// // Do something useful, for example:
+/// let diff = EwonDiff::between(&old_ewons, &new_ewons);
+/// diff.changed
+///     .iter()
+///     .for_each(|change| println!("{}: {:?}", change.new.name, change.fields));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EwonDiff {
+    /// The eWONs which only belong to the new list.
+    pub added: Vec<Ewon>,
+    /// The eWONs which only belong to the old list.
+    pub removed: Vec<Ewon>,
+    /// The eWONs which belong to both lists, but with different parameters.
+    pub changed: Vec<EwonChange>,
+}
+
+/// Changes of an eWON between two lists of eWONs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EwonChange {
+    /// The eWON from the old list.
+    pub old: Ewon,
+    /// The eWON from the new list.
+    pub new: Ewon,
+    /// The parameters of the eWON which changed.
+    pub fields: Vec<EwonField>,
+}
+
+/// Enumerate the parameters of an eWON which could change.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EwonField {
+    /// The name, and the url-encoded name, of the eWON.
+    Name,
+    /// The status of the eWON.
+    Status,
+    /// The user description of the eWON.
+    Description,
+    /// The custom attributes of the eWON.
+    CustomAttributes,
+    /// The M2Web VPN server on which the eWON is connected to.
+    M2webServer,
+    /// The LAN devices connected to the eWON.
+    LanDevices,
+    /// The active eWON services.
+    EwonServices,
+}
+
+impl EwonDiff {
+    /// Compare the old list of eWONs with the new one.
+    ///
+    /// The added, removed and changed eWONs keep the order of the list they are taken from.
+    pub fn between(old: &[Ewon], new: &[Ewon]) -> Self {
+        let old_ewons = old
+            .iter()
+            .map(|ewon| (ewon.id, ewon))
+            .collect::<HashMap<u32, &Ewon>>();
+        let new_ewons = new
+            .iter()
+            .map(|ewon| (ewon.id, ewon))
+            .collect::<HashMap<u32, &Ewon>>();
+
+        let mut diff = EwonDiff::default();
+        for new_ewon in new {
+            match old_ewons.get(&new_ewon.id) {
+                None => diff.added.push(new_ewon.to_owned()),
+                Some(old_ewon) => {
+                    let fields = EwonChange::changed_fields(old_ewon, new_ewon);
+                    if !fields.is_empty() {
+                        diff.changed.push(EwonChange {
+                            old: (*old_ewon).to_owned(),
+                            new: new_ewon.to_owned(),
+                            fields,
+                        });
+                    }
+                }
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|old_ewon| !new_ewons.contains_key(&old_ewon.id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Return `true` if both lists hold the same eWONs with the same parameters.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl EwonChange {
+    /// Return `true` if the status of the eWON changed.
+    pub fn status_changed(&self) -> bool {
+        self.fields.contains(&EwonField::Status)
+    }
+
+    /// Return the parameters which differ between both eWONs.
+    fn changed_fields(old: &Ewon, new: &Ewon) -> Vec<EwonField> {
+        let mut fields = vec![];
+        if old.name != new.name || old.encoded_name != new.encoded_name {
+            fields.push(EwonField::Name);
+        }
+        if old.status != new.status {
+            fields.push(EwonField::Status);
+        }
+        if old.description != new.description {
+            fields.push(EwonField::Description);
+        }
+        if old.custom_attributes != new.custom_attributes {
+            fields.push(EwonField::CustomAttributes);
+        }
+        if old.m2web_server != new.m2web_server {
+            fields.push(EwonField::M2webServer);
+        }
+        if old.lan_devices != new.lan_devices {
+            fields.push(EwonField::LanDevices);
+        }
+        if old.ewon_services != new.ewon_services {
+            fields.push(EwonField::EwonServices);
+        }
+
+        fields
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        diff::{EwonDiff, EwonField},
+        ewon::{Ewon, EwonStatus},
+    };
+
+    fn ewon(id: u32, status: EwonStatus, description: &str) -> Ewon {
+        Ewon {
+            id,
+            name: format!("ewon{}", id),
+            status,
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ewon_diff_empty_ok() {
+        let ewons = vec![
+            ewon(1, EwonStatus::Online, ""),
+            ewon(2, EwonStatus::Offline, ""),
+        ];
+
+        assert!(EwonDiff::between(&ewons, &ewons).is_empty());
+    }
+
+    #[test]
+    fn ewon_diff_filled_ok() {
+        let old_ewons = vec![
+            ewon(1, EwonStatus::Online, "press"),
+            ewon(2, EwonStatus::Offline, ""),
+            ewon(3, EwonStatus::Online, ""),
+        ];
+        let new_ewons = vec![
+            ewon(1, EwonStatus::Offline, "press #1"),
+            ewon(3, EwonStatus::Online, ""),
+            ewon(4, EwonStatus::Online, ""),
+        ];
+
+        let diff = EwonDiff::between(&old_ewons, &new_ewons);
+
+        assert_eq!(diff.added, vec![ewon(4, EwonStatus::Online, "")]);
+        assert_eq!(diff.removed, vec![ewon(2, EwonStatus::Offline, "")]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].old, old_ewons[0]);
+        assert_eq!(diff.changed[0].new, new_ewons[0]);
+        assert_eq!(
+            diff.changed[0].fields,
+            vec![EwonField::Status, EwonField::Description]
+        );
+        assert!(diff.changed[0].status_changed());
+    }
+}
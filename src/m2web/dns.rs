@@ -0,0 +1,95 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::SocketAddr;
+
+/// Family of the IP addresses the clients connect to, among those resolved for the API.
+///
+/// By default, the clients connect to the first address resolved, and to the addresses of the other family if
+/// it fails to connect. On the networks where the routes to Talk2M of one family are blackholed, this first
+/// attempt delays each new connection: the preferred family is then tried first, or only.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, dns::IpFamily};
+/// let client = ClientBuilder::default()
+///     .ip_family(IpFamily::PreferIpv4)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IpFamily {
+    /// Connect to the IPv4 addresses first, and to the IPv6 addresses if it fails.
+    PreferIpv4,
+    /// Connect to the IPv4 addresses only.
+    Ipv4Only,
+    /// Connect to the IPv6 addresses first, and to the IPv4 addresses if it fails.
+    PreferIpv6,
+    /// Connect to the IPv6 addresses only.
+    Ipv6Only,
+}
+
+impl IpFamily {
+    /// Sort the addresses of the preferred family first, or keep them only.
+    fn select(self, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        match self {
+            IpFamily::PreferIpv4 => addrs.sort_by_key(SocketAddr::is_ipv6),
+            IpFamily::Ipv4Only => addrs.retain(SocketAddr::is_ipv4),
+            IpFamily::PreferIpv6 => addrs.sort_by_key(SocketAddr::is_ipv4),
+            IpFamily::Ipv6Only => addrs.retain(SocketAddr::is_ipv6),
+        }
+
+        addrs
+    }
+}
+
+/// Resolver of the system, selecting the addresses of a family.
+#[derive(Debug)]
+pub(crate) struct FamilyResolver(pub(crate) IpFamily);
+
+impl Resolve for FamilyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let ip_family = self.0;
+
+        Box::pin(async move {
+            let addrs =
+                ip_family.select(tokio::net::lookup_host((name.as_str(), 0)).await?.collect());
+            match addrs.is_empty() {
+                true => Err(format!(
+                    "no {:?} address resolved for [{}]",
+                    ip_family,
+                    name.as_str()
+                )
+                .into()),
+                false => Ok(Box::new(addrs.into_iter()) as Addrs),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::dns::IpFamily;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn ip_family_ok() {
+        let addrs: Vec<SocketAddr> = vec![
+            "[2001:db8::1]:443".parse().unwrap(),
+            "192.0.2.1:443".parse().unwrap(),
+            "[2001:db8::2]:443".parse().unwrap(),
+        ];
+
+        assert_eq!(
+            IpFamily::PreferIpv4.select(addrs.clone()),
+            vec![addrs[1], addrs[0], addrs[2]]
+        );
+        assert_eq!(IpFamily::Ipv4Only.select(addrs.clone()), vec![addrs[1]]);
+        assert_eq!(
+            IpFamily::PreferIpv6.select(addrs.clone()),
+            vec![addrs[0], addrs[2], addrs[1]]
+        );
+        assert_eq!(
+            IpFamily::Ipv6Only.select(addrs.clone()),
+            vec![addrs[0], addrs[2]]
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use crate::m2web::tag::TagGroup;
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+/// Data exported by an Export Block Descriptor.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EbdData {
+    /// The instant values of the tags ($dtIV).
+    InstantValues,
+    /// The tag list, with the configuration of each tag ($dtTL).
+    TagList,
+    /// The historical logging of the tags ($dtHL).
+    HistoricalLog,
+    /// The real-time logging of the tags ($dtRL).
+    RealTimeLog,
+    /// The active alarms ($dtAR).
+    Alarms,
+    /// The alarm history ($dtAH).
+    AlarmHistory,
+    /// The event log ($dtEV).
+    Events,
+    /// The status of the eWON, with its system information ($dtES).
+    Status,
+}
+
+impl EbdData {
+    /// Return the code of the data type in an Export Block Descriptor.
+    fn code(&self) -> &'static str {
+        match self {
+            EbdData::InstantValues => "IV",
+            EbdData::TagList => "TL",
+            EbdData::HistoricalLog => "HL",
+            EbdData::RealTimeLog => "RL",
+            EbdData::Alarms => "AR",
+            EbdData::AlarmHistory => "AH",
+            EbdData::Events => "EV",
+            EbdData::Status => "ES",
+        }
+    }
+}
+
+/// Display the exported data.
+impl fmt::Display for EbdData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EbdData::InstantValues => write!(f, "instant values"),
+            EbdData::TagList => write!(f, "tag list"),
+            EbdData::HistoricalLog => write!(f, "historical logging"),
+            EbdData::RealTimeLog => write!(f, "real-time logging"),
+            EbdData::Alarms => write!(f, "active alarms"),
+            EbdData::AlarmHistory => write!(f, "alarm history"),
+            EbdData::Events => write!(f, "event log"),
+            EbdData::Status => write!(f, "status"),
+        }
+    }
+}
+
+/// Format of the exported data.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum EbdFormat {
+    /// Semicolon-separated text, with a header line ($ftT).
+    #[default]
+    Text,
+    /// HTML table ($ftH).
+    Html,
+}
+
+impl EbdFormat {
+    /// Return the code of the format in an Export Block Descriptor.
+    fn code(&self) -> &'static str {
+        match self {
+            EbdFormat::Text => "T",
+            EbdFormat::Html => "H",
+        }
+    }
+}
+
+/// Bound of the time window of an export.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EbdTime {
+    /// An absolute time.
+    At(SystemTime),
+    /// A time relative to the time of the export, for example 2 hours ago.
+    Ago(Duration),
+}
+
+/// Format the bound as expected by the eWON.
+///
+/// The absolute times are formatted in UTC, which is why an `Ebd` with an absolute bound always exports its
+/// times in UTC, and the relative times are rounded up to the minute unless
+/// they are a whole number of hours or days.
+impl fmt::Display for EbdTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EbdTime::At(time) => {
+                let secs = time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let (year, month, day) = civil_from_days(secs / 86400);
+                write!(
+                    f,
+                    "{:02}/{:02}/{:04} {:02}:{:02}:{:02}",
+                    day,
+                    month,
+                    year,
+                    secs % 86400 / 3600,
+                    secs % 3600 / 60,
+                    secs % 60
+                )
+            }
+            EbdTime::Ago(duration) => {
+                let secs = duration.as_secs();
+                match secs {
+                    0 => write!(f, "_m0"),
+                    _ if secs % 86400 == 0 => write!(f, "_d{}", secs / 86400),
+                    _ if secs % 3600 == 0 => write!(f, "_h{}", secs / 3600),
+                    _ => write!(f, "_m{}", secs.div_ceil(60)),
+                }
+            }
+        }
+    }
+}
+
+/// Return the year, month and day of the given number of days since the Unix epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Shift the epoch to 0000-03-01, so the leap day is the last day of the year.
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = match shifted_month {
+        0..=9 => shifted_month + 3,
+        _ => shifted_month - 9,
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Export Block Descriptor.
+///
+/// Describe the data exported by an eWON: the data type, the format, the time window, and the tag or the
+/// groups of tags. The descriptor is composed by its `Display` implementation, for example
+/// `$dtHL$ftT$st_h2$et_m0`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::ebd::{Ebd, EbdData, EbdTime};
+/// # use std::time::Duration;
+/// let ebd = Ebd::new(EbdData::HistoricalLog)
+///     .start(EbdTime::Ago(Duration::from_secs(2 * 3600)))
+///     .end(EbdTime::Ago(Duration::ZERO))
+///     .tag("Temperature");
+///
+/// assert_eq!(ebd.to_string(), "$dtHL$ftT$st_h2$et_m0$tnTemperature");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Ebd {
+    /// The exported data.
+    data: EbdData,
+    /// The format of the exported data.
+    format: EbdFormat,
+    /// The start of the time window.
+    start: Option<EbdTime>,
+    /// The end of the time window.
+    end: Option<EbdTime>,
+    /// The name of the only exported tag.
+    tag: Option<String>,
+    /// The groups of the exported tags, all the tags being exported if empty.
+    groups: Vec<TagGroup>,
+    /// Indicates if the times are exported in UTC rather than in the local time of the eWON.
+    utc: bool,
+}
+
+impl Ebd {
+    /// Create a descriptor exporting the data as text, without any time window, tag or group filter.
+    pub fn new(data: EbdData) -> Self {
+        Ebd {
+            data,
+            format: EbdFormat::default(),
+            start: None,
+            end: None,
+            tag: None,
+            groups: vec![],
+            utc: false,
+        }
+    }
+
+    /// Return the exported data.
+    pub fn data(&self) -> EbdData {
+        self.data
+    }
+
+    /// Return `true` if the times are exported in UTC rather than in the local time of the eWON.
+    ///
+    /// The times are exported in UTC as soon as a bound of the time window is an absolute time, since the
+    /// absolute times are written in UTC and the eWON reads them in the timezone of the export.
+    pub fn is_utc(&self) -> bool {
+        self.utc
+            || [self.start, self.end]
+                .iter()
+                .any(|bound| matches!(bound, Some(EbdTime::At(_))))
+    }
+
+    /// Export the data in this format.
+    pub fn format(mut self, format: EbdFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Only export the data logged from this time.
+    pub fn start(mut self, start: EbdTime) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Only export the data logged until this time.
+    pub fn end(mut self, end: EbdTime) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Only export the data logged between both times.
+    pub fn window(self, start: EbdTime, end: EbdTime) -> Self {
+        self.start(start).end(end)
+    }
+
+    /// Only export the data of this tag.
+    pub fn tag(mut self, name: &str) -> Self {
+        self.tag = Some(name.to_owned());
+        self
+    }
+
+    /// Only export the data of the tags of this group, and of the other selected groups.
+    pub fn group(mut self, group: TagGroup) -> Self {
+        if !self.groups.contains(&group) {
+            self.groups.push(group);
+        }
+        self
+    }
+
+    /// Only export the data of the tags of these groups.
+    pub fn groups(self, groups: &[TagGroup]) -> Self {
+        groups.iter().fold(self, |ebd, group| ebd.group(*group))
+    }
+
+    /// Export the times in UTC rather than in the local time of the eWON ($ut).
+    ///
+    /// This is implied by an absolute bound of the time window.
+    pub fn utc(mut self) -> Self {
+        self.utc = true;
+        self
+    }
+}
+
+/// Compose the Export Block Descriptor.
+impl fmt::Display for Ebd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "$dt{}$ft{}", self.data.code(), self.format.code())?;
+        if let Some(start) = self.start {
+            write!(f, "$st{}", start)?;
+        }
+        if let Some(end) = self.end {
+            write!(f, "$et{}", end)?;
+        }
+        if let Some(ref tag) = self.tag {
+            write!(f, "$tn{}", tag)?;
+        }
+        if !self.groups.is_empty() {
+            write!(f, "$fl")?;
+            for group in &self.groups {
+                write!(f, "{}", group)?;
+            }
+        }
+        if self.is_utc() {
+            write!(f, "$ut")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        ebd::{Ebd, EbdData, EbdFormat, EbdTime},
+        tag::TagGroup,
+    };
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn ebd_display_ok() {
+        assert_eq!(Ebd::new(EbdData::InstantValues).to_string(), "$dtIV$ftT");
+        assert_eq!(
+            Ebd::new(EbdData::Events)
+                .format(EbdFormat::Html)
+                .window(
+                    EbdTime::At(SystemTime::UNIX_EPOCH + Duration::from_secs(1709210096)),
+                    EbdTime::Ago(Duration::from_secs(90)),
+                )
+                .to_string(),
+            "$dtEV$ftH$st29/02/2024 12:34:56$et_m2$ut"
+        );
+        assert_eq!(
+            Ebd::new(EbdData::HistoricalLog)
+                .end(EbdTime::At(
+                    SystemTime::UNIX_EPOCH + Duration::from_secs(1709210096)
+                ))
+                .utc()
+                .to_string(),
+            "$dtHL$ftT$et29/02/2024 12:34:56$ut"
+        );
+        assert_eq!(
+            Ebd::new(EbdData::RealTimeLog)
+                .start(EbdTime::Ago(Duration::from_secs(3 * 86400)))
+                .to_string(),
+            "$dtRL$ftT$st_d3"
+        );
+        assert_eq!(
+            Ebd::new(EbdData::InstantValues)
+                .group(TagGroup::C)
+                .groups(&[TagGroup::A, TagGroup::C])
+                .to_string(),
+            "$dtIV$ftT$flCA"
+        );
+        assert_eq!(
+            Ebd::new(EbdData::HistoricalLog)
+                .tag("Temperature")
+                .utc()
+                .to_string(),
+            "$dtHL$ftT$tnTemperature$ut"
+        );
+    }
+}
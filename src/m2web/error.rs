@@ -2,59 +2,239 @@ use std::convert;
 use std::error;
 use std::fmt;
 
-#[derive(Debug, PartialEq)]
+/// Length of the copy of the body kept by the errors parsing a response, in bytes.
+const PARSED_BODY_MAX_LENGTH: usize = 512;
+
+#[derive(Debug)]
 pub struct Error {
-    /// HTTP error code returned by the M2Web API.
-    pub(in crate::m2web) code: u16,
-    /// Store the error kind associated to the HTTP status code and the message returned by the M2Web API.
-    pub(in crate::m2web) kind: ErrorKind,
+    /// HTTP status code of the response of the M2Web API.
+    pub(crate) http_status: u16,
+    /// Error code given in the body of the response, which does not always match the HTTP status code.
+    pub(crate) api_code: Option<u16>,
+    /// The underlying error which caused this one, if any.
+    pub(crate) source: Option<Box<dyn error::Error + Send + Sync>>,
+    /// Store the error kind associated to the error code and the message returned by the M2Web API.
+    pub(crate) kind: ErrorKind,
 }
 
 /// Enumerate all kinds of error that could occur.
+///
+/// More kinds could be added as the API documents new error conditions.
 #[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum ErrorKind {
+    /// This error occurs when an operation is cancelled by its cancellation token.
+    Cancelled(String),
+    /// This error occurs when the circuit breaker fails a request to an endpoint or an eWON failing repeatedly.
+    CircuitOpen(String),
+    /// This error occurs when the eWON returns an unsuccessful response to a proxied request.
+    DeviceError(String),
+    /// This error occurs when the device requested does not exist on the account.
+    DeviceNotFound(String),
     /// This error occurs when the API returns and empty response.
     EmptyResponse(String),
+    /// This error occurs when the API client is unable to parse a data export of an eWON.
+    ExportParsing(String),
     /// This error occurs when an internal call goes wrong and return an error.
     InternalError(String),
     /// This error occurs when one of the authentication parameters provided to the M2Web API is wrong.
     InvalidCredentials(String),
+    /// This error occurs when the developer id provided to the M2Web API is wrong.
+    InvalidDeveloperId(String),
     /// This error occurs when a mandatory parameter or endpoint is missing or wrong
     MissingOrWrongParameter(String),
     /// This error occurs when the API returns an empty response.
     NoContent(String),
     /// This error occurs when the API client is unable to parse and deserialize the JSON response from the API.
     ResponseParsing(String),
+    /// This error occurs when the API fails to handle the request on its side.
+    ServerError(String),
+    /// This error occurs when the session of a stateful authentication is expired or unknown to the API.
+    SessionExpired(String),
     /// This error occurs when the client tries to auth statefully without the stateful_auth field set.
     StatelessAuthSet(String),
+    /// This error occurs when the API throttles the requests of the account.
+    Throttled(String),
+    /// This error occurs when an operation does not complete before its deadline.
+    Timeout(String),
     /// This is a generic error when an unknown error occurred.
     UnknownError(String),
+    /// This error occurs when the method requested does not exist in the M2Web API.
+    UnknownMethod(String),
+}
+
+impl Error {
+    /// Return the HTTP status code of the response, or the one matching the error if it did not come from a
+    /// response.
+    pub fn http_status(&self) -> u16 {
+        self.http_status
+    }
+
+    /// Return the kind of the error, to match on.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Return the error code given in the body of the response, if any.
+    pub fn api_code(&self) -> Option<u16> {
+        self.api_code
+    }
+
+    /// Return `true` if the failure is transient and the operation may succeed if tried again later.
+    ///
+    /// The timeouts, the throttling, the open circuits and the server errors are transient, while the wrong
+    /// credentials or parameters, the missing devices or the parsing errors are permanent.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            ErrorKind::CircuitOpen(_)
+            | ErrorKind::ServerError(_)
+            | ErrorKind::Throttled(_)
+            | ErrorKind::Timeout(_) => true,
+            ErrorKind::DeviceError(_) | ErrorKind::UnknownError(_) => {
+                self.api_code.unwrap_or(self.http_status) >= 500
+            }
+            _ => false,
+        }
+    }
+
+    /// Map the error code and the message of an unsuccessful API response to an error.
+    ///
+    /// The error code of the body classifies the error, or else the HTTP status code if the body has none. The
+    /// API gives the same code to several conditions, which are then told apart by their message.
+    pub(crate) fn from_api_response(
+        http_status: reqwest::StatusCode,
+        api_code: Option<u16>,
+        message: &str,
+    ) -> Self {
+        let code = api_code
+            .and_then(|api_code| reqwest::StatusCode::from_u16(api_code).ok())
+            .unwrap_or(http_status);
+        let kind = match code {
+            reqwest::StatusCode::BAD_REQUEST => {
+                ErrorKind::MissingOrWrongParameter(message.to_owned())
+            }
+            reqwest::StatusCode::UNAUTHORIZED => ErrorKind::InvalidCredentials(message.to_owned()),
+            reqwest::StatusCode::FORBIDDEN => {
+                let lowercase_message = message.to_lowercase();
+                if lowercase_message.contains("credentials") {
+                    ErrorKind::InvalidCredentials(message.to_owned())
+                } else if lowercase_message.contains("developer") {
+                    ErrorKind::InvalidDeveloperId(message.to_owned())
+                } else if lowercase_message.contains("session") {
+                    ErrorKind::SessionExpired(message.to_owned())
+                } else if lowercase_message.starts_with("method") {
+                    ErrorKind::UnknownMethod(message.to_owned())
+                } else {
+                    ErrorKind::MissingOrWrongParameter(message.to_owned())
+                }
+            }
+            reqwest::StatusCode::TOO_MANY_REQUESTS => ErrorKind::Throttled(message.to_owned()),
+            reqwest::StatusCode::GONE => match message.starts_with("Device") {
+                true => ErrorKind::DeviceNotFound(message.to_owned()),
+                false => ErrorKind::EmptyResponse(message.to_owned()),
+            },
+            code if code.is_server_error() => ErrorKind::ServerError(message.to_owned()),
+            _ => ErrorKind::UnknownError("Unkown error occurred".to_string()),
+        };
+
+        Error {
+            http_status: http_status.as_u16(),
+            api_code,
+            source: None,
+            kind,
+        }
+    }
 }
 
-impl error::Error for Error {}
+/// Map an error deserializing the body of a response from an endpoint to an error.
+///
+/// The endpoint and a copy of the body, truncated if too long, are added to the message to debug the response.
+pub(crate) fn response_parsing_error(
+    error: serde_json::Error,
+    endpoint: &str,
+    body: &str,
+) -> Error {
+    let mut parsing_error = Error::from(error);
+    if let ErrorKind::ResponseParsing(ref mut error_message) = parsing_error.kind {
+        let body = match body.len() > PARSED_BODY_MAX_LENGTH {
+            true => {
+                let end = (0..=PARSED_BODY_MAX_LENGTH)
+                    .rev()
+                    .find(|end| body.is_char_boundary(*end))
+                    .unwrap_or_default();
+                format!("{}...", &body[..end])
+            }
+            false => body.to_owned(),
+        };
+        *error_message = format!("{} from [{}] in [{}]", error_message, endpoint, body);
+    }
+
+    parsing_error
+}
+
+/// Compare the errors on their status, code and kind, whatever their source.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.http_status == other.http_status
+            && self.api_code == other.api_code
+            && self.kind == other.kind
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn error::Error + 'static))
+    }
+}
 
 /// Display the HTTP status code and the error message returned by the M2Web API.
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.kind {
+            ErrorKind::Cancelled(ref error_message) => {
+                write!(f, "Cancelled: {}", error_message)
+            }
+            ErrorKind::CircuitOpen(ref error_message) => {
+                write!(f, "Circuit open: {}", error_message)
+            }
+            ErrorKind::DeviceError(ref error_message) => {
+                write!(f, "eWON HTTP {}: {}", self.http_status, error_message)
+            }
+            ErrorKind::DeviceNotFound(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
             ErrorKind::EmptyResponse(ref error_message) => {
-                write!(f, "HTTP {}: {}", self.code, error_message)
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
+            ErrorKind::ExportParsing(ref error_message) => {
+                write!(f, "Unable to parse export: {}", error_message)
             }
             ErrorKind::InternalError(ref error_message) => {
                 write!(f, "Internal error: {}", error_message)
             }
             ErrorKind::InvalidCredentials(ref error_message) => {
-                write!(f, "HTTP {}: {}", self.code, error_message)
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
+            ErrorKind::InvalidDeveloperId(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
             }
             ErrorKind::MissingOrWrongParameter(ref error_message) => {
-                write!(f, "HTTP {}: {}", self.code, error_message)
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
             }
             ErrorKind::NoContent(ref error_message) => {
-                write!(f, "HTTP {}: {}", self.code, error_message)
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
             }
             ErrorKind::ResponseParsing(ref error_message) => {
                 write!(f, "Unable to parse JSON response: {}", error_message)
             }
+            ErrorKind::ServerError(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
+            ErrorKind::SessionExpired(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
             ErrorKind::StatelessAuthSet(ref error_message) => {
                 write!(
                     f,
@@ -62,64 +242,205 @@ impl fmt::Display for Error {
                     error_message
                 )
             }
+            ErrorKind::Throttled(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
+            ErrorKind::Timeout(ref error_message) => {
+                write!(f, "Timeout: {}", error_message)
+            }
             ErrorKind::UnknownError(ref error_message) => {
                 write!(f, "Unknown error: {}", error_message)
             }
+            ErrorKind::UnknownMethod(ref error_message) => {
+                write!(f, "HTTP {}: {}", self.http_status, error_message)
+            }
         }
     }
 }
 
-/// Allow to transform reqwest::Error to m2web::Error.
+/// Allow to transform reqwest::Error to m2web::Error, keeping it as the source.
 impl convert::From<reqwest::Error> for Error {
     fn from(error: reqwest::Error) -> Self {
-        match error.status() {
-            Some(reqwest::StatusCode::FORBIDDEN) => Error {
-                code: 403,
-                kind: ErrorKind::InvalidCredentials(format!("{}", error)),
-            },
-            Some(_) | None => Error {
-                code: 500,
-                kind: ErrorKind::UnknownError(format!(
-                    "Unknown error while requesting API: {}",
-                    error
-                )),
-            },
+        let (http_status, kind) = if error.is_timeout() {
+            (
+                408,
+                ErrorKind::Timeout(format!("Request to API timed out: {}", error)),
+            )
+        } else {
+            match error.status() {
+                Some(reqwest::StatusCode::FORBIDDEN) => {
+                    (403, ErrorKind::InvalidCredentials(format!("{}", error)))
+                }
+                Some(_) | None => (
+                    500,
+                    ErrorKind::UnknownError(format!(
+                        "Unknown error while requesting API: {}",
+                        error
+                    )),
+                ),
+            }
+        };
+
+        Error {
+            http_status,
+            api_code: None,
+            source: Some(Box::new(error)),
+            kind,
+        }
+    }
+}
+
+/// Allow to transform std::io::Error to m2web::Error, keeping it as the source.
+impl convert::From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error {
+            http_status: 500,
+            api_code: None,
+            kind: ErrorKind::InternalError(format!("I/O error: {}", error)),
+            source: Some(Box::new(error)),
         }
     }
 }
 
-/// Allow to transform serde_json::Error to m2web::Error.
+/// Allow to transform serde_json::Error to m2web::Error, keeping it as the source.
 impl convert::From<serde_json::Error> for Error {
     fn from(error: serde_json::Error) -> Self {
-        if error.is_syntax() {
-            Error {
-                code: 500,
-                kind: ErrorKind::ResponseParsing(format!("JSON response syntax error: {}", error)),
-            }
+        let message = if error.is_syntax() {
+            format!("JSON response syntax error: {}", error)
         } else if error.is_data() {
-            Error {
-                code: 500,
-                kind: ErrorKind::ResponseParsing(format!(
-                    "JSON response data format does not match the expected one: {}",
-                    error
-                )),
-            }
+            format!(
+                "JSON response data format does not match the expected one: {}",
+                error
+            )
         } else if error.is_eof() {
-            Error {
-                code: 500,
-                kind: ErrorKind::ResponseParsing(format!(
-                    "An empty or incomplete response were received: {}",
-                    error
-                )),
-            }
+            format!("An empty or incomplete response were received: {}", error)
         } else {
-            Error {
-                code: 500,
-                kind: ErrorKind::ResponseParsing(format!(
-                    "Unknown error while parsing JSON response: {}",
-                    error
-                )),
+            format!("Unknown error while parsing JSON response: {}", error)
+        };
+
+        Error {
+            http_status: 500,
+            api_code: None,
+            kind: ErrorKind::ResponseParsing(message),
+            source: Some(Box::new(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::error::{response_parsing_error, Error, ErrorKind};
+
+    #[test]
+    fn from_api_response_ok() {
+        let kind = |http_status: u16, api_code, message| {
+            Error::from_api_response(
+                reqwest::StatusCode::from_u16(http_status).unwrap(),
+                api_code,
+                message,
+            )
+            .kind
+        };
+
+        assert_eq!(
+            kind(403, Some(403), "Invalid credentials"),
+            ErrorKind::InvalidCredentials("Invalid credentials".to_string())
+        );
+        assert_eq!(
+            kind(403, Some(403), "Invalid developer ID"),
+            ErrorKind::InvalidDeveloperId("Invalid developer ID".to_string())
+        );
+        assert_eq!(
+            kind(403, Some(403), "Session expired"),
+            ErrorKind::SessionExpired("Session expired".to_string())
+        );
+        assert_eq!(
+            kind(403, Some(403), "Method [wrong] is invalid"),
+            ErrorKind::UnknownMethod("Method [wrong] is invalid".to_string())
+        );
+        assert_eq!(
+            kind(200, Some(410), "Device [missing42] does not exist"),
+            ErrorKind::DeviceNotFound("Device [missing42] does not exist".to_string())
+        );
+        assert_eq!(
+            kind(429, None, "Too many requests"),
+            ErrorKind::Throttled("Too many requests".to_string())
+        );
+        assert_eq!(
+            kind(502, None, "Bad Gateway"),
+            ErrorKind::ServerError("Bad Gateway".to_string())
+        );
+        assert_eq!(
+            kind(400, Some(400), "Parameter [action] is missing"),
+            ErrorKind::MissingOrWrongParameter("Parameter [action] is missing".to_string())
+        );
+    }
+
+    #[test]
+    fn response_parsing_error_ok() {
+        let json_error = || serde_json::from_str::<u32>("\"forty-two\"").unwrap_err();
+
+        assert_eq!(
+            response_parsing_error(json_error(), "getewon", "\"forty-two\"").kind,
+            ErrorKind::ResponseParsing(
+                "JSON response data format does not match the expected one: invalid type: string \"forty-two\", expected u32 at line 1 column 11 from [getewon] in [\"forty-two\"]"
+                    .to_string()
+            )
+        );
+
+        // The long bodies are truncated, on a character boundary.
+        let body = format!("{}é{}", "a".repeat(511), "b".repeat(100));
+        match response_parsing_error(json_error(), "getewons", &body).kind {
+            ErrorKind::ResponseParsing(error_message) => {
+                assert!(error_message.ends_with(&format!(" in [{}...]", "a".repeat(511))))
             }
+            kind => panic!("unexpected error kind {:?}", kind),
         }
     }
+
+    #[test]
+    fn is_retryable_ok() {
+        let error = |http_status, api_code, kind| Error {
+            http_status,
+            api_code,
+            source: None,
+            kind,
+        };
+
+        assert!(error(408, None, ErrorKind::Timeout("timed out".to_string())).is_retryable());
+        assert!(error(
+            429,
+            Some(429),
+            ErrorKind::Throttled("slow down".to_string())
+        )
+        .is_retryable());
+        assert!(error(503, None, ErrorKind::CircuitOpen("open".to_string())).is_retryable());
+        assert!(error(
+            502,
+            None,
+            ErrorKind::UnknownError("bad gateway".to_string())
+        )
+        .is_retryable());
+        assert!(error(503, None, ErrorKind::DeviceError("busy".to_string())).is_retryable());
+        assert!(!error(
+            200,
+            Some(400),
+            ErrorKind::UnknownError("unknown".to_string())
+        )
+        .is_retryable());
+        assert!(!error(
+            200,
+            Some(403),
+            ErrorKind::InvalidCredentials("denied".to_string())
+        )
+        .is_retryable());
+        assert!(!error(
+            410,
+            Some(410),
+            ErrorKind::EmptyResponse("missing".to_string())
+        )
+        .is_retryable());
+        assert!(!error(404, None, ErrorKind::DeviceError("not found".to_string())).is_retryable());
+        assert!(!error(500, None, ErrorKind::ResponseParsing("syntax".to_string())).is_retryable());
+    }
 }
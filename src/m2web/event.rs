@@ -0,0 +1,132 @@
+use crate::m2web::{error, parser::ExportParser};
+use chrono_tz::Tz;
+use std::{fmt, time::SystemTime};
+
+/// Level of an event.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum EventLevel {
+    /// A trace of the normal operation of the eWON.
+    Trace,
+    /// A warning, which does not prevent the eWON from operating.
+    Warning,
+    /// An error.
+    Error,
+    /// A level unknown to this crate, as exported by the eWON.
+    Other(String),
+}
+
+impl From<&str> for EventLevel {
+    fn from(level: &str) -> Self {
+        match level.to_lowercase().as_str() {
+            "trace" | "info" => EventLevel::Trace,
+            "warning" | "warn" => EventLevel::Warning,
+            "error" | "err" => EventLevel::Error,
+            _ => EventLevel::Other(level.to_owned()),
+        }
+    }
+}
+
+/// Display the level of an event.
+impl fmt::Display for EventLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventLevel::Trace => write!(f, "trace"),
+            EventLevel::Warning => write!(f, "warning"),
+            EventLevel::Error => write!(f, "error"),
+            EventLevel::Other(level) => write!(f, "{}", level),
+        }
+    }
+}
+
+/// Event logged by an eWON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventEntry {
+    /// The time at which the event has been logged.
+    pub timestamp: SystemTime,
+    /// The level of the event.
+    pub level: EventLevel,
+    /// The message describing the event.
+    pub message: String,
+    /// The component of the eWON which logged the event, empty if unknown.
+    pub origin: String,
+}
+
+impl EventEntry {
+    /// Parse the event log exported by the eWON.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per event. The times are
+    /// exported as seconds since the Unix epoch, in the given timezone. The event file of the eWON names the
+    /// message and the origin of the events `Event` and `Class`, rather than `Message` and `Source`.
+    pub(in crate::m2web) fn parse_events(
+        export: &str,
+        timezone: Tz,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        let table = ExportParser::new().parse(export)?;
+        let (time_column, level_column) = (
+            table.required_column("TimeInt")?,
+            table.required_column("Level")?,
+        );
+        let message_column = match table.column("Event") {
+            Some(column) => column,
+            None => table.required_column("Message")?,
+        };
+        let origin_column = table.column("Source").or_else(|| table.column("Class"));
+
+        table
+            .rows()
+            .iter()
+            .map(|row| {
+                Ok(EventEntry {
+                    timestamp: row.time(time_column, timezone)?,
+                    level: EventLevel::from(row.text(level_column)?),
+                    message: row.text(message_column)?.to_owned(),
+                    origin: match origin_column {
+                        Some(column) => row.text(column)?.to_owned(),
+                        None => String::new(),
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::event::{EventEntry, EventLevel};
+    use chrono_tz::Tz;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn parse_events_ok() {
+        let export = "\"TimeInt\";\"TimeStr\";\"Level\";\"Source\";\"Message\"\r\n\
+            1700000000;\"14/11/2023 22:13:20\";\"Trace\";\"bas-prg\";\"Program started\"\r\n\
+            1700000060;\"14/11/2023 22:14:20\";\"Error\";\"vpn\";\"Connection lost; retrying\"\r\n\
+            1700000120;\"14/11/2023 22:15:20\";\"Debug\";\"io\";\"IO server reset\"\r\n";
+
+        let events = EventEntry::parse_events(export, Tz::UTC).unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)
+        );
+        assert_eq!(events[0].level, EventLevel::Trace);
+        assert_eq!(events[0].origin, "bas-prg");
+        assert_eq!(events[1].level, EventLevel::Error);
+        assert_eq!(events[1].message, "Connection lost; retrying");
+        assert_eq!(events[2].level, EventLevel::Other("Debug".to_string()));
+    }
+
+    #[test]
+    fn parse_event_file_ok() {
+        let export = "\"TimeInt\";\"TimeStr\";\"Level\";\"Class\";\"Event\"\r\n\
+            1700000000;\"14/11/2023 22:13:20\";\"Warning\";\"sys\";\"Low battery\"\r\n";
+
+        let events = EventEntry::parse_events(export, Tz::UTC).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].level, EventLevel::Warning);
+        assert_eq!(events[0].origin, "sys");
+        assert_eq!(events[0].message, "Low battery");
+    }
+}
@@ -1,5 +1,8 @@
+use crate::m2web::{account::AccountInfo, error};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::{collections::HashMap, fmt};
 
 /// Response from the M2Web API.
 ///
@@ -17,6 +20,34 @@ pub(in crate::m2web) struct ApiResponse {
     /// Session id returned by the API in case of stateful auth.
     #[serde(default)]
     pub(in crate::m2web) t2msession: String,
+    /// Error code of the API, which does not always match the HTTP status code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::m2web) code: Option<u16>,
+    /// Message to explain which error just happened.
+    #[serde(default)]
+    pub(in crate::m2web) message: String,
+    /// The account information have been requested from the API.
+    #[serde(flatten)]
+    pub(in crate::m2web) account_info: AccountInfo,
+    /// The fields of the response unknown to this crate.
+    #[serde(flatten)]
+    pub(in crate::m2web) extra: HashMap<String, serde_json::Value>,
+}
+
+/// Response from the M2Web API with undecoded eWONs.
+///
+/// Handle the encapsulated response from the API, but leave each eWON undecoded so they could be decoded
+/// one by one.
+#[derive(Debug, Deserialize)]
+pub(in crate::m2web) struct RawEwonsResponse {
+    /// Indicates if the request suceeded or not.
+    pub(in crate::m2web) success: bool,
+    /// All eWON or eWON from a pool have been requested from the API.
+    #[serde(default)]
+    pub(in crate::m2web) ewons: Vec<Box<RawValue>>,
+    /// Error code of the API, which does not always match the HTTP status code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(in crate::m2web) code: Option<u16>,
     /// Message to explain which error just happened.
     #[serde(default)]
     pub(in crate::m2web) message: String,
@@ -35,7 +66,7 @@ pub struct Ewon {
     /// The url-encoded name of the eWON.
     pub encoded_name: String,
     /// The status of the eWON, either connected or disconnected.
-    pub status: String,
+    pub status: EwonStatus,
     /// The user description of the eWON.
     pub description: String,
     /// The three user-customized attributes of the eWON.
@@ -43,7 +74,391 @@ pub struct Ewon {
     /// The M2Web VPN server on which the eWON is connected to.
     pub m2web_server: String,
     /// The LAN devices connected to the eWON.
-    pub lan_devices: Vec<String>,
+    pub lan_devices: Vec<LanDevice>,
     /// The active eWON services.
-    pub ewon_services: Vec<String>,
+    pub ewon_services: Vec<EwonService>,
+    /// The parameters returned by the API which are unknown to this crate, for example added by a newer
+    /// version of the API.
+    #[builder(default)]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// LAN device parameters.
+///
+/// Each device connected to the LAN of an eWON and declared to Talk2M is described by these parameters.
+#[derive(Builder, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanDevice {
+    /// The name of the LAN device.
+    pub name: String,
+    /// The user description of the LAN device.
+    pub description: String,
+    /// The IP address of the LAN device, on the LAN of the eWON.
+    pub ip: String,
+    /// The port on which the LAN device is reachable.
+    pub port: u16,
+    /// The protocol used to reach the LAN device, for example "http".
+    pub protocol: String,
+}
+
+/// eWON service parameters.
+///
+/// Each service enabled on an eWON and reachable through Talk2M is described by these parameters.
+#[derive(Builder, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EwonService {
+    /// The name of the service, for example "HTTP" or "FTP".
+    pub name: String,
+    /// The port on which the service is reachable.
+    pub port: u16,
+    /// The protocol used to reach the service.
+    pub protocol: String,
+}
+
+impl Ewon {
+    /// Return `true` if the eWON is connected to Talk2M.
+    pub fn is_online(&self) -> bool {
+        self.status == EwonStatus::Online
+    }
+
+    /// Return the custom attributes of the eWON keyed by their label.
+    ///
+    /// The labels of the three custom attributes are defined by the corporate account, and returned
+    /// by `Client::get_account_info()`. The custom attributes without label are left out.
+    pub fn custom_attributes_map(&self, account_info: &AccountInfo) -> HashMap<String, String> {
+        account_info
+            .custom_attributes
+            .iter()
+            .zip(self.custom_attributes.iter())
+            .filter(|(label, _)| !label.is_empty())
+            .map(|(label, value)| (label.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    /// Sort the eWONs by name.
+    pub fn sort_by_name(ewons: &mut [Ewon]) {
+        ewons.sort_by(|ewon_a, ewon_b| ewon_a.name.cmp(&ewon_b.name));
+    }
+
+    /// Group the eWONs by the M2Web server on which they are connected to.
+    pub fn group_by_m2web_server(ewons: &[Ewon]) -> HashMap<&str, Vec<&Ewon>> {
+        let mut groups: HashMap<&str, Vec<&Ewon>> = HashMap::new();
+        ewons.iter().for_each(|ewon| {
+            groups
+                .entry(ewon.m2web_server.as_ref())
+                .or_default()
+                .push(ewon)
+        });
+
+        groups
+    }
+
+    /// Group the eWONs by status.
+    pub fn group_by_status(ewons: &[Ewon]) -> HashMap<&EwonStatus, Vec<&Ewon>> {
+        let mut groups: HashMap<&EwonStatus, Vec<&Ewon>> = HashMap::new();
+        ewons
+            .iter()
+            .for_each(|ewon| groups.entry(&ewon.status).or_default().push(ewon));
+
+        groups
+    }
+}
+
+/// Connection status of an eWON.
+///
+/// The M2Web API returns the status as a string, which is mapped to a variant. Any status unknown to
+/// the client is kept as returned by the API.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum EwonStatus {
+    /// The eWON is connected to Talk2M.
+    Online,
+    /// The eWON is disconnected from Talk2M.
+    Offline,
+    /// The status returned by the API is unknown to the client.
+    Other(String),
+}
+
+impl Default for EwonStatus {
+    fn default() -> Self {
+        EwonStatus::Other(String::new())
+    }
+}
+
+/// Display the status like returned by the M2Web API.
+impl fmt::Display for EwonStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EwonStatus::Online => write!(f, "online"),
+            EwonStatus::Offline => write!(f, "offline"),
+            EwonStatus::Other(ref status) => write!(f, "{}", status),
+        }
+    }
+}
+
+impl From<String> for EwonStatus {
+    fn from(status: String) -> Self {
+        match status.as_ref() {
+            "online" => EwonStatus::Online,
+            "offline" => EwonStatus::Offline,
+            _ => EwonStatus::Other(status),
+        }
+    }
+}
+
+impl From<EwonStatus> for String {
+    fn from(status: EwonStatus) -> Self {
+        status.to_string()
+    }
+}
+
+/// Reference to an eWON.
+///
+/// The M2Web API allows to select an eWON either by its id or by its name. An eWON returned by the API
+/// could also be used directly, in which case the device-scoped requests are routed to its M2Web server.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EwonRef<'a> {
+    /// The UID of the eWON to the M2Web API.
+    Id(u32),
+    /// The unique name of the eWON.
+    Name(&'a str),
+    /// The eWON, as returned by the API.
+    Ewon(&'a Ewon),
+}
+
+impl<'a> EwonRef<'a> {
+    /// Return the query parameter selecting the eWON.
+    pub(in crate::m2web) fn query_param(&self) -> (&'static str, String) {
+        match self {
+            EwonRef::Id(id) => ("id", id.to_string()),
+            EwonRef::Name(name) => ("name", name.to_string()),
+            EwonRef::Ewon(ewon) => ("id", ewon.id.to_string()),
+        }
+    }
+
+    /// Return the name of the eWON, or else its id, to identify it in the traces.
+    #[cfg(feature = "tracing")]
+    pub(in crate::m2web) fn label(&self) -> String {
+        match self {
+            EwonRef::Id(id) => id.to_string(),
+            EwonRef::Name(name) => name.to_string(),
+            EwonRef::Ewon(ewon) => ewon.name.clone(),
+        }
+    }
+}
+
+impl<'a> From<u32> for EwonRef<'a> {
+    fn from(id: u32) -> Self {
+        EwonRef::Id(id)
+    }
+}
+
+impl<'a> From<&'a Ewon> for EwonRef<'a> {
+    fn from(ewon: &'a Ewon) -> Self {
+        EwonRef::Ewon(ewon)
+    }
+}
+
+impl<'a> From<&'a str> for EwonRef<'a> {
+    fn from(name: &'a str) -> Self {
+        EwonRef::Name(name)
+    }
+}
+
+/// Outcome of a wake-up request.
+///
+/// The API only acknowledges that the wake-up request was accepted, the eWON then connects to Talk2M within a
+/// few minutes, or never if it did not receive the SMS: `Client::wakeup_and_wait()` waits for it to be online.
+/// The rejected requests, such as those to the eWONs which cannot be woken up, are returned as errors.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WakeupOutcome {
+    /// The message of the API about the accepted request, if any.
+    pub message: Option<String>,
+}
+
+/// Summary of the wake-up of several eWONs.
+#[derive(Debug, Default, PartialEq)]
+pub struct WakeupSummary {
+    /// The eWONs for which the wake-up request has been accepted by the API.
+    pub woken: Vec<Ewon>,
+    /// The eWONs which were already online, and have not been woken up.
+    pub already_online: Vec<Ewon>,
+    /// The eWONs for which the wake-up request failed, with the related error.
+    pub failed: Vec<(Ewon, error::Error)>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        account::AccountInfo,
+        ewon::{Ewon, EwonStatus},
+    };
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn ewon_status_deserialize_ok() {
+        let statuses =
+            serde_json::from_value::<Vec<EwonStatus>>(json!(["online", "offline", "maintenance"]))
+                .unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                EwonStatus::Online,
+                EwonStatus::Offline,
+                EwonStatus::Other("maintenance".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ewon_status_serialize_ok() {
+        let statuses = serde_json::to_value(vec![
+            EwonStatus::Online,
+            EwonStatus::Offline,
+            EwonStatus::Other("maintenance".to_string()),
+        ])
+        .unwrap();
+
+        assert_eq!(statuses, json!(["online", "offline", "maintenance"]));
+    }
+
+    #[test]
+    fn ewon_is_online_ok() {
+        let mut ewon = Ewon {
+            status: EwonStatus::Online,
+            ..Default::default()
+        };
+        assert!(ewon.is_online());
+
+        ewon.status = EwonStatus::Offline;
+        assert!(!ewon.is_online());
+    }
+
+    #[test]
+    fn ewon_custom_attributes_map_ok() {
+        let account_info = AccountInfo {
+            custom_attributes: ["customer".to_string(), "site".to_string(), "".to_string()],
+            ..Default::default()
+        };
+        let ewon = Ewon {
+            custom_attributes: [
+                "SMAG".to_string(),
+                "Limoges".to_string(),
+                "unlabeled".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ewon.custom_attributes_map(&account_info),
+            HashMap::from([
+                ("customer".to_string(), "SMAG".to_string()),
+                ("site".to_string(), "Limoges".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn ewon_sort_by_name_ok() {
+        let mut ewons = ["ewon42", "bea-test", "eWON  FLEXOCOLOR SM2845"]
+            .iter()
+            .map(|name| Ewon {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<Ewon>>();
+
+        Ewon::sort_by_name(&mut ewons);
+
+        assert_eq!(
+            ewons
+                .iter()
+                .map(|ewon| ewon.name.as_ref())
+                .collect::<Vec<&str>>(),
+            vec!["bea-test", "eWON  FLEXOCOLOR SM2845", "ewon42"]
+        );
+    }
+
+    #[test]
+    fn ewon_group_by_m2web_server_ok() {
+        let ewons = [
+            (1, "eu1.m2web.talk2m.com"),
+            (2, "eu2.m2web.talk2m.com"),
+            (3, "eu1.m2web.talk2m.com"),
+        ]
+        .iter()
+        .map(|(id, m2web_server)| Ewon {
+            id: *id,
+            m2web_server: m2web_server.to_string(),
+            ..Default::default()
+        })
+        .collect::<Vec<Ewon>>();
+
+        let groups = Ewon::group_by_m2web_server(&ewons);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups["eu1.m2web.talk2m.com"]
+                .iter()
+                .map(|ewon| ewon.id)
+                .collect::<Vec<u32>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            groups["eu2.m2web.talk2m.com"]
+                .iter()
+                .map(|ewon| ewon.id)
+                .collect::<Vec<u32>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn ewon_group_by_status_ok() {
+        let ewons = [
+            (1, EwonStatus::Online),
+            (2, EwonStatus::Offline),
+            (3, EwonStatus::Offline),
+        ]
+        .iter()
+        .map(|(id, status)| Ewon {
+            id: *id,
+            status: status.to_owned(),
+            ..Default::default()
+        })
+        .collect::<Vec<Ewon>>();
+
+        let groups = Ewon::group_by_status(&ewons);
+
+        assert_eq!(groups[&EwonStatus::Online].len(), 1);
+        assert_eq!(groups[&EwonStatus::Offline].len(), 2);
+    }
+
+    #[test]
+    fn ewon_extra_fields_ok() {
+        let ewon_json = json!({
+          "id": 1206698,
+          "name": "bea-test",
+          "encodedName": "bea-test",
+          "status": "online",
+          "description": "",
+          "customAttributes": ["bea", "", ""],
+          "m2webServer": "eu2.m2web.talk2m.com",
+          "lanDevices": [],
+          "ewonServices": [],
+          "firmwareVersion": "14.7s0"
+        });
+
+        let ewon = serde_json::from_value::<Ewon>(ewon_json.to_owned()).unwrap();
+
+        assert_eq!(ewon.status, EwonStatus::Online);
+        assert_eq!(
+            ewon.extra,
+            HashMap::from([("firmwareVersion".to_string(), json!("14.7s0"))])
+        );
+        assert_eq!(serde_json::to_value(&ewon).unwrap(), ewon_json);
+    }
 }
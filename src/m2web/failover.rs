@@ -0,0 +1,132 @@
+use reqwest::{Request, Response, Url};
+use std::{
+    future::Future,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use tower::BoxError;
+
+/// Failover of the requests across the base urls of an API.
+///
+/// The requests are sent to the active url, the first one until the client fails over. A request failing to
+/// connect is sent again to the next urls, in order, and the url which serves it becomes the active one,
+/// until it fails in turn. Only the requests to one of the urls fail over, not those to the M2Web server of
+/// an eWON for example.
+#[derive(Debug)]
+pub(crate) struct Failover {
+    /// The base urls, the primary one first.
+    urls: Vec<String>,
+    /// The index of the active url.
+    active: AtomicUsize,
+}
+
+impl Failover {
+    /// Create the failover from the primary url to the fallback urls, unless there is none.
+    pub(crate) fn new(t2m_url: &str, fallback_urls: &[&str]) -> Option<Self> {
+        if fallback_urls.is_empty() {
+            return None;
+        }
+
+        Some(Failover {
+            urls: std::iter::once(t2m_url)
+                .chain(fallback_urls.iter().copied())
+                .map(|url| url.trim_end_matches('/').to_owned())
+                .collect(),
+            active: AtomicUsize::new(0),
+        })
+    }
+
+    /// Return the base url serving the requests.
+    pub(crate) fn active_url(&self) -> &str {
+        &self.urls[self.active.load(Ordering::Relaxed)]
+    }
+
+    /// Send the request to the active url, and to the next ones while it fails to connect.
+    ///
+    /// A request which cannot be cloned, streaming its body, is sent to the active url only.
+    pub(crate) async fn send<F, T>(
+        &self,
+        mut request: Request,
+        transmit: F,
+    ) -> Result<Response, BoxError>
+    where
+        F: Fn(Request) -> T,
+        T: Future<Output = Result<Response, BoxError>>,
+    {
+        let url = request.url().as_str().to_owned();
+        let path = match self.urls.iter().find_map(|base| {
+            url.strip_prefix(base.as_str())
+                .filter(|path| path.is_empty() || path.starts_with(['/', '?']))
+        }) {
+            Some(path) => path.to_owned(),
+            None => return transmit(request).await,
+        };
+        let active = self.active.load(Ordering::Relaxed);
+        let mut tried = 0;
+
+        loop {
+            let index = (active + tried) % self.urls.len();
+            let next = match tried + 1 < self.urls.len() {
+                true => request.try_clone(),
+                false => None,
+            };
+            *request.url_mut() = Url::parse(&format!("{}{}", self.urls[index], path))?;
+
+            match (transmit(request).await, next) {
+                (Err(error), Some(next)) if is_connection_error(&error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        url = self.urls[index],
+                        error = %error,
+                        "failing over to the next url of the API"
+                    );
+                    request = next;
+                    tried += 1;
+                }
+                (result, _) => {
+                    if result.is_ok() {
+                        self.active.store(index, Ordering::Relaxed);
+                    }
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+/// Check if the request failed to connect to the server.
+fn is_connection_error(error: &BoxError) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(is_connect)
+}
+
+/// Check if the request failed to connect to the server.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn is_connect(error: &reqwest::Error) -> bool {
+    error.is_connect()
+}
+
+/// Check if the request failed to connect to the server. The browser does not tell the connection errors
+/// apart from the other errors sending the request, which are all taken as such.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn is_connect(error: &reqwest::Error) -> bool {
+    error.is_request()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::failover::Failover;
+
+    #[test]
+    fn failover_ok() {
+        assert!(Failover::new("https://m2web.talk2m.com/t2mapi", &[]).is_none());
+
+        let failover = Failover::new(
+            "https://m2web.talk2m.com/t2mapi",
+            &["https://eu2.m2web.talk2m.com/t2mapi/"],
+        )
+        .unwrap();
+        assert_eq!(failover.active_url(), "https://m2web.talk2m.com/t2mapi");
+        assert_eq!(failover.urls[1], "https://eu2.m2web.talk2m.com/t2mapi");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::m2web::ewon::{Ewon, EwonStatus};
+
+/// Client-side filter of eWONs.
+///
+/// Select the eWONs matching all the criteria set on the filter. A filter without any criterion matches
+/// all the eWONs.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{ewon::EwonStatus, filter::EwonFilter};
+/// // Select the online eWONs of the customer "SMAG".
+/// let filter = EwonFilter::default()
+///     .status(EwonStatus::Online)
+///     .custom_attribute("SMAG");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EwonFilter {
+    /// The status the eWONs must have.
+    status: Option<EwonStatus>,
+    /// The substring the description of the eWONs must contain.
+    description: Option<String>,
+    /// The value one of the custom attributes of the eWONs must have.
+    custom_attribute: Option<String>,
+    /// The name of the service the eWONs must have enabled.
+    service: Option<String>,
+}
+
+impl EwonFilter {
+    /// Only select the eWONs with this status.
+    pub fn status(mut self, status: EwonStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only select the eWONs whose description contains this substring.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Only select the eWONs which have one custom attribute set to this value.
+    ///
+    /// The custom attributes which are not set are empty, so an empty value does not select any eWON but
+    /// removes the criterion instead.
+    pub fn custom_attribute(mut self, value: &str) -> Self {
+        self.custom_attribute = Some(value.to_owned()).filter(|value| !value.is_empty());
+        self
+    }
+
+    /// Only select the eWONs which have this service enabled, regardless of the case of its name.
+    pub fn service(mut self, name: &str) -> Self {
+        self.service = Some(name.to_owned());
+        self
+    }
+
+    /// Return `true` if the eWON matches all the criteria of the filter.
+    pub fn matches(&self, ewon: &Ewon) -> bool {
+        if let Some(ref status) = self.status {
+            if &ewon.status != status {
+                return false;
+            }
+        }
+        if let Some(ref description) = self.description {
+            if !ewon.description.contains(description.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref value) = self.custom_attribute {
+            if !ewon.custom_attributes.contains(value) {
+                return false;
+            }
+        }
+        if let Some(ref name) = self.service {
+            if !ewon
+                .ewon_services
+                .iter()
+                .any(|service| service.name.eq_ignore_ascii_case(name))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        ewon::{Ewon, EwonService, EwonStatus},
+        filter::EwonFilter,
+    };
+
+    fn ewon(status: EwonStatus, description: &str, custom_attribute: &str) -> Ewon {
+        Ewon {
+            status,
+            description: description.to_string(),
+            custom_attributes: [custom_attribute.to_string(), "".to_string(), "".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ewon_filter_default_ok() {
+        let filter = EwonFilter::default();
+
+        assert!(filter.matches(&ewon(EwonStatus::Online, "", "")));
+        assert!(filter.matches(&ewon(EwonStatus::Offline, "press", "SMAG")));
+    }
+
+    #[test]
+    fn ewon_filter_all_criteria_ok() {
+        let filter = EwonFilter::default()
+            .status(EwonStatus::Online)
+            .description("DEBOBINEUR")
+            .custom_attribute("FRANCE");
+
+        assert!(filter.matches(&ewon(
+            EwonStatus::Online,
+            "SM2845 SIRIUS DEBOBINEUR1000",
+            "FRANCE"
+        )));
+        assert!(!filter.matches(&ewon(
+            EwonStatus::Offline,
+            "SM2845 SIRIUS DEBOBINEUR1000",
+            "FRANCE"
+        )));
+        assert!(!filter.matches(&ewon(EwonStatus::Online, "SM2845 SIRIUS", "FRANCE")));
+        assert!(!filter.matches(&ewon(
+            EwonStatus::Online,
+            "SM2845 SIRIUS DEBOBINEUR1000",
+            "ITALY"
+        )));
+    }
+
+    #[test]
+    fn ewon_filter_empty_custom_attribute_ok() {
+        let filter = EwonFilter::default()
+            .custom_attribute("FRANCE")
+            .custom_attribute("");
+
+        assert_eq!(filter, EwonFilter::default());
+        assert!(filter.matches(&ewon(EwonStatus::Online, "", "FRANCE")));
+        assert!(filter.matches(&ewon(EwonStatus::Online, "", "")));
+    }
+
+    #[test]
+    fn ewon_filter_service_ok() {
+        let filter = EwonFilter::default().service("http");
+        let mut ewon = ewon(EwonStatus::Online, "", "");
+
+        assert!(!filter.matches(&ewon));
+
+        ewon.ewon_services.push(EwonService {
+            name: "HTTP".to_string(),
+            port: 80,
+            protocol: "tcp".to_string(),
+        });
+        assert!(filter.matches(&ewon));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::m2web::{
+    alarm::Alarm,
+    client::Client,
+    config::{ConfigBackup, ConfigBackupDiff},
+    device::{DeviceInfo, ScriptState},
+    ebd::{Ebd, EbdTime},
+    error,
+    event::EventEntry,
+    ewon::{Ewon, EwonRef, WakeupOutcome},
+    history::LogRecord,
+    proxy::{LanProxy, ProxyResponse},
+    tag::{Tag, TagData, TagGroup, TagValue},
+};
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+
+/// Handle of an eWON.
+///
+/// Bind an eWON to the client used to request it, so the device-level operations could be performed
+/// without passing the client and the eWON around. The handle caches the eWON as returned by the API:
+/// its id, its encoded name and its M2Web server are known without requesting the API again. A handle
+/// is created by `Client::ewon()`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, ewon::WakeupOutcome};
+/// # #[tokio::test]
+/// # async fn wakeup_ewon_handle() -> Result<WakeupOutcome, error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let mut ewon = client.ewon("bea-test").await?;
+///
+/// if !ewon.refresh().await?.is_online() {
+///     ewon.wakeup().await?;
+/// }
+/// # }
+/// ```
+pub struct EwonHandle<'c, 'a> {
+    /// The client used to request the API.
+    client: &'c Client<'a>,
+    /// The eWON, as returned by the last request.
+    ewon: Ewon,
+}
+
+impl<'c, 'a> EwonHandle<'c, 'a> {
+    /// Create a handle of the eWON returned by the API.
+    pub(in crate::m2web) fn new(client: &'c Client<'a>, ewon: Ewon) -> Self {
+        EwonHandle { client, ewon }
+    }
+
+    /// Return the eWON, as returned by the last request.
+    pub fn ewon(&self) -> &Ewon {
+        &self.ewon
+    }
+
+    /// Return the UID of the eWON to the M2Web API.
+    pub fn id(&self) -> u32 {
+        self.ewon.id
+    }
+
+    /// Return the unique name of the eWON.
+    pub fn name(&self) -> &str {
+        &self.ewon.name
+    }
+
+    /// Return the url-encoded name of the eWON.
+    pub fn encoded_name(&self) -> &str {
+        &self.ewon.encoded_name
+    }
+
+    /// Return the M2Web VPN server on which the eWON is connected to.
+    pub fn m2web_server(&self) -> &str {
+        &self.ewon.m2web_server
+    }
+
+    /// Request the eWON again and return its refreshed parameters.
+    pub async fn refresh(&mut self) -> Result<&Ewon, error::Error> {
+        self.ewon = self.client.refresh_ewon(self.ewon.id).await?;
+
+        Ok(&self.ewon)
+    }
+
+    /// Wake up the eWON.
+    ///
+    /// The request is sent to the M2Web server of the eWON.
+    pub async fn wakeup(&self) -> Result<WakeupOutcome, error::Error> {
+        self.client.wakeup(&self.ewon).await
+    }
+
+    /// Wake up the eWON and wait until it is online.
+    ///
+    /// The cached eWON is refreshed once it is online.
+    pub async fn wakeup_and_wait(&mut self, timeout: Duration) -> Result<&Ewon, error::Error> {
+        self.ewon = self
+            .client
+            .wakeup_and_wait(EwonRef::Ewon(&self.ewon), timeout)
+            .await?;
+
+        Ok(&self.ewon)
+    }
+
+    /// Send a GET request to the web server of the eWON, through the M2Web API.
+    pub async fn proxy_get(
+        &self,
+        device_path: &str,
+        req_query_params: Option<Vec<(&str, &str)>>,
+    ) -> Result<ProxyResponse, error::Error> {
+        self.client
+            .proxy_get(&self.ewon, device_path, req_query_params)
+            .await
+    }
+
+    /// Return a proxy to the web server of a device of the LAN of the eWON.
+    pub fn lan_proxy<'p>(&'p self, ip: &'p str, port: u16) -> LanProxy<'c, 'a, 'p> {
+        self.client.lan_proxy(&self.ewon, ip, port)
+    }
+
+    /// Export data from the eWON, as described by an Export Block Descriptor.
+    pub async fn export(&self, ebd: &Ebd) -> Result<String, error::Error> {
+        self.client.export(&self.ewon, ebd).await
+    }
+
+    /// Return the historical logging of the eWON.
+    pub async fn get_historical_data(&self, ebd: &Ebd) -> Result<Vec<LogRecord>, error::Error> {
+        self.client.get_historical_data(&self.ewon, ebd).await
+    }
+
+    /// Return the real-time logging of the eWON.
+    pub async fn get_realtime_data(&self, ebd: &Ebd) -> Result<Vec<LogRecord>, error::Error> {
+        self.client.get_realtime_data(&self.ewon, ebd).await
+    }
+
+    /// Return the event log of the eWON, between both times.
+    pub async fn get_events(
+        &self,
+        start: EbdTime,
+        end: EbdTime,
+    ) -> Result<Vec<EventEntry>, error::Error> {
+        self.client.get_events(&self.ewon, start, end).await
+    }
+
+    /// Return the events of the event file of the eWON.
+    pub async fn get_event_file(&self) -> Result<Vec<EventEntry>, error::Error> {
+        self.client.get_event_file(&self.ewon).await
+    }
+
+    /// Export data from the eWON to a writer, as described by an Export Block Descriptor.
+    pub async fn download_ebd_to<W>(
+        &self,
+        ebd: &Ebd,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        self.client
+            .download_ebd_to(&self.ewon, ebd, writer, progress)
+            .await
+    }
+
+    /// Download a file stored on the eWON.
+    pub async fn download_file(&self, path: &str) -> Result<Vec<u8>, error::Error> {
+        self.client.download_file(&self.ewon, path).await
+    }
+
+    /// Download a file stored on the eWON to a writer.
+    pub async fn download_file_to<W>(
+        &self,
+        path: &str,
+        writer: &mut W,
+        progress: impl FnMut(u64),
+    ) -> Result<u64, error::Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        self.client
+            .download_file_to(&self.ewon, path, writer, progress)
+            .await
+    }
+
+    /// Upload a file to the eWON.
+    pub async fn upload_file(
+        &self,
+        path: &str,
+        body: impl Into<Vec<u8>>,
+    ) -> Result<(), error::Error> {
+        self.client.upload_file(&self.ewon, path, body).await
+    }
+
+    /// Back up the configuration of the eWON.
+    pub async fn backup_config(&self) -> Result<ConfigBackup, error::Error> {
+        self.client.backup_config(&self.ewon).await
+    }
+
+    /// Restore the configuration of the eWON from a backup.
+    pub async fn restore_config(&self, backup: &ConfigBackup) -> Result<(), error::Error> {
+        self.client.restore_config(&self.ewon, backup).await
+    }
+
+    /// Compare the configuration of the eWON with a backup, without changing the eWON.
+    pub async fn diff_config(
+        &self,
+        backup: &ConfigBackup,
+        sections: Option<&[&str]>,
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        self.client.diff_config(&self.ewon, backup, sections).await
+    }
+
+    /// Restore the selected sections of the configuration of the eWON from a backup.
+    pub async fn restore_config_sections(
+        &self,
+        backup: &ConfigBackup,
+        sections: &[&str],
+    ) -> Result<ConfigBackupDiff, error::Error> {
+        self.client
+            .restore_config_sections(&self.ewon, backup, sections)
+            .await
+    }
+
+    /// Return the system information of the eWON.
+    pub async fn get_device_info(&self) -> Result<DeviceInfo, error::Error> {
+        self.client.get_device_info(&self.ewon).await
+    }
+
+    /// Return the instant values of the tags of the eWON.
+    pub async fn read_tags(&self) -> Result<Vec<TagValue>, error::Error> {
+        self.client.read_tags(&self.ewon).await
+    }
+
+    /// Return the instant values of the tags of the eWON which belong to the given groups.
+    pub async fn read_group_tags(
+        &self,
+        groups: &[TagGroup],
+    ) -> Result<Vec<TagValue>, error::Error> {
+        self.client.read_group_tags(&self.ewon, groups).await
+    }
+
+    /// Return the tags configured on the eWON.
+    pub async fn list_tags(&self) -> Result<Vec<Tag>, error::Error> {
+        self.client.list_tags(&self.ewon).await
+    }
+
+    /// Return the tags configured on the eWON which belong to the given groups.
+    pub async fn list_group_tags(&self, groups: &[TagGroup]) -> Result<Vec<Tag>, error::Error> {
+        self.client.list_group_tags(&self.ewon, groups).await
+    }
+
+    /// Write the value of a tag of the eWON.
+    pub async fn write_tag(
+        &self,
+        name: &str,
+        value: impl Into<TagData>,
+    ) -> Result<(), error::Error> {
+        self.client.write_tag(&self.ewon, name, value).await
+    }
+
+    /// Write the values of several tags of the eWON at once.
+    pub async fn write_tags(&self, tags: &[(&str, TagData)]) -> Result<(), error::Error> {
+        self.client.write_tags(&self.ewon, tags).await
+    }
+
+    /// Return the active alarms of the eWON.
+    pub async fn get_alarms(&self) -> Result<Vec<Alarm>, error::Error> {
+        self.client.get_alarms(&self.ewon).await
+    }
+
+    /// Acknowledge the alarm of a tag of the eWON.
+    pub async fn ack_alarm(&self, tag: &str) -> Result<(), error::Error> {
+        self.client.ack_alarm(&self.ewon, tag).await
+    }
+
+    /// Start the BASIC script of the eWON.
+    pub async fn start_script(&self) -> Result<(), error::Error> {
+        self.client.start_script(&self.ewon).await
+    }
+
+    /// Stop the BASIC script of the eWON.
+    pub async fn stop_script(&self) -> Result<(), error::Error> {
+        self.client.stop_script(&self.ewon).await
+    }
+
+    /// Return the run state of the BASIC script of the eWON.
+    pub async fn get_script_state(&self) -> Result<ScriptState, error::Error> {
+        self.client.get_script_state(&self.ewon).await
+    }
+}
@@ -0,0 +1,134 @@
+use crate::m2web::{error, parser::ExportTable, tag::TagData};
+use chrono_tz::Tz;
+use std::{collections::HashMap, time::SystemTime};
+
+/// Logged value of a tag.
+///
+/// Each value logged by an eWON, in its historical or real-time logging, is exported with these parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogRecord {
+    /// The time at which the value has been logged.
+    pub timestamp: SystemTime,
+    /// The id of the tag on the eWON.
+    pub tag_id: u32,
+    /// The name of the tag.
+    pub tag_name: String,
+    /// The logged value.
+    pub value: TagData,
+    /// The OPC quality of the value.
+    pub quality: u16,
+    /// Indicates if the value is the initial value of the tag, logged when the logging started.
+    pub initial: bool,
+}
+
+impl LogRecord {
+    /// Return `true` if the OPC quality of the value is good.
+    pub fn is_good_quality(&self) -> bool {
+        self.quality & 0xC0 == 0xC0
+    }
+
+    /// Parse the logged values exported by the eWON.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per value. The times are
+    /// exported as seconds since the Unix epoch, in the given timezone. When the export only holds the tag
+    /// ids, the tag names are taken from the given map.
+    pub(in crate::m2web) fn parse_log(
+        table: &ExportTable,
+        tag_names: &HashMap<u32, String>,
+        timezone: Tz,
+    ) -> Result<Vec<LogRecord>, error::Error> {
+        let (tag_id_column, time_column, value_column) = (
+            table.required_column("TagId")?,
+            table.required_column("TimeInt")?,
+            table.required_column("Value")?,
+        );
+        let (tag_name_column, quality_column, initial_column) = (
+            table.column("TagName"),
+            table.column("IQuality"),
+            table.column("IsInitValue"),
+        );
+
+        table
+            .rows()
+            .iter()
+            .map(|row| {
+                let tag_id = row.number(tag_id_column)?;
+
+                Ok(LogRecord {
+                    timestamp: row.time(time_column, timezone)?,
+                    tag_id,
+                    tag_name: match tag_name_column {
+                        Some(column) => row.text(column)?.to_owned(),
+                        None => tag_names.get(&tag_id).cloned().unwrap_or_default(),
+                    },
+                    value: row.data(value_column)?,
+                    quality: match quality_column {
+                        Some(column) => row.number(column)?,
+                        None => 0xC0,
+                    },
+                    initial: match initial_column {
+                        Some(column) => row.flag(column)?,
+                        None => false,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{history::LogRecord, parser::ExportParser, tag::TagData};
+    use chrono_tz::{Europe, Tz};
+    use std::{
+        collections::HashMap,
+        time::{Duration, SystemTime},
+    };
+
+    #[test]
+    fn parse_log_ok() {
+        let export = "\"TagId\";\"TimeInt\";\"TimeStr\";\"IsInitValue\";\"Value\";\"IQuality\"\r\n\
+            1;1700000000;\"14/11/2023 22:13:20\";1;21.5;3\r\n\
+            2;1700000060;\"14/11/2023 22:14:20\";0;\"PET-500\";192\r\n";
+        let tag_names = HashMap::from([(1, "Temperature".to_string())]);
+
+        let table = ExportParser::new().parse(export).unwrap();
+        let records = LogRecord::parse_log(&table, &tag_names, Tz::UTC).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)
+        );
+        assert_eq!(records[0].tag_name, "Temperature");
+        assert_eq!(records[0].value, TagData::Number(21.5));
+        assert!(records[0].initial);
+        assert!(!records[0].is_good_quality());
+        assert_eq!(records[1].tag_name, "");
+        assert_eq!(records[1].value, TagData::Text("PET-500".to_string()));
+        assert!(records[1].is_good_quality());
+
+        let records = LogRecord::parse_log(&table, &tag_names, Europe::Paris).unwrap();
+        assert_eq!(
+            records[0].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1699996400)
+        );
+    }
+
+    #[test]
+    fn parse_log_ko() {
+        let export = "\"TagId\";\"TimeStr\";\"Value\"\r\n\
+            1;\"14/11/2023 22:13:20\";21.5\r\n";
+
+        let table = ExportParser::new().parse(export).unwrap();
+        let err = match LogRecord::parse_log(&table, &HashMap::new(), Tz::UTC) {
+            Ok(_) => panic!("parse_log should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: missing column [TimeInt]"
+        );
+    }
+}
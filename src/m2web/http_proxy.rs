@@ -0,0 +1,62 @@
+use reqwest::{NoProxy, Proxy};
+
+/// Corporate HTTP or HTTPS proxy the requests to the API go through.
+///
+/// The proxy may require a basic authentication, and the hosts of the no-proxy list, such as the local
+/// servers of the plant, are reached directly. The list accepts host names, domains, for which a leading dot
+/// also matches the subdomains, and IP addresses or networks, such as `10.0.0.0/8`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, http_proxy::HttpProxy};
+/// let http_proxy = HttpProxy::new("http://proxy.plant.local:3128")
+///     .credentials("svc-talk2m", "secret")
+///     .no_proxy(&["localhost", ".plant.local", "10.0.0.0/8"]);
+///
+/// let client = ClientBuilder::default()
+///     .http_proxy(http_proxy)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpProxy<'a> {
+    /// The url of the proxy, such as `http://proxy.plant.local:3128`.
+    url: &'a str,
+    /// The username and password authenticating against the proxy.
+    credentials: Option<(&'a str, &'a str)>,
+    /// The hosts reached without the proxy.
+    no_proxy: Vec<&'a str>,
+}
+
+impl<'a> HttpProxy<'a> {
+    /// Send all the requests through the proxy at this url.
+    pub fn new(url: &'a str) -> Self {
+        HttpProxy {
+            url,
+            ..HttpProxy::default()
+        }
+    }
+
+    /// Authenticate against the proxy with this user.
+    pub fn credentials(mut self, username: &'a str, password: &'a str) -> Self {
+        self.credentials = Some((username, password));
+        self
+    }
+
+    /// Reach these hosts, domains or networks without the proxy.
+    pub fn no_proxy(mut self, hosts: &[&'a str]) -> Self {
+        self.no_proxy.extend_from_slice(hosts);
+        self
+    }
+
+    /// Return the proxy of the HTTP client.
+    pub(crate) fn proxy(&self) -> Result<Proxy, String> {
+        let mut proxy = Proxy::all(self.url)
+            .map_err(|error| format!("invalid proxy url [{}]: {}", self.url, error))?;
+        if let Some((username, password)) = self.credentials {
+            proxy = proxy.basic_auth(username, password);
+        }
+
+        Ok(proxy.no_proxy(NoProxy::from_string(&self.no_proxy.join(","))))
+    }
+}
@@ -0,0 +1,68 @@
+use crate::m2web::error;
+use reqwest::{Request, Response};
+
+/// Hooks called around each request to the API.
+///
+/// The interceptors of a client are called, in the order they were added, on each attempt of a request,
+/// retries included: before it is sent, once it holds its authentication parameters and headers, and once
+/// its response is received, before its body is read. They allow to add or change the headers of the
+/// requests, for example to implement a custom authentication scheme in front of the API, or to log the
+/// requests and the responses. An error returned by an interceptor, for example converted from an
+/// `std::io::Error`, aborts the request.
+///
+/// A closure taking the request is an interceptor which only modifies the requests.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, interceptor::Interceptor};
+/// use reqwest::{header::HeaderValue, Request, Response};
+///
+/// struct Gateway;
+///
+/// impl Interceptor for Gateway {
+///     fn on_request(&self, request: &mut Request) -> Result<(), error::Error> {
+///         request
+///             .headers_mut()
+///             .insert("x-gateway-key", HeaderValue::from_static("secret"));
+///         Ok(())
+///     }
+///
+///     fn on_response(&self, response: &Response) -> Result<(), error::Error> {
+///         println!("{} {}", response.status(), response.url().path());
+///         Ok(())
+///     }
+/// }
+///
+/// let client = ClientBuilder::default()
+///     .interceptor(Gateway)
+///     .interceptor(|request: &mut Request| {
+///         request
+///             .headers_mut()
+///             .insert("x-request-source", HeaderValue::from_static("collector"));
+///         Ok(())
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Inspect or modify the request before it is sent.
+    fn on_request(&self, request: &mut Request) -> Result<(), error::Error> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Inspect the response once received, before its body is read.
+    fn on_response(&self, response: &Response) -> Result<(), error::Error> {
+        let _ = response;
+        Ok(())
+    }
+}
+
+impl<F> Interceptor for F
+where
+    F: Fn(&mut Request) -> Result<(), error::Error> + Send + Sync,
+{
+    fn on_request(&self, request: &mut Request) -> Result<(), error::Error> {
+        self(request)
+    }
+}
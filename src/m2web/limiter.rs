@@ -0,0 +1,133 @@
+use crate::m2web::clock::{self, Instant};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// Token bucket of a `RateLimiter`.
+#[derive(Debug)]
+struct Bucket {
+    /// The maximum number of tokens, which is the number of requests sent at once after an idle period.
+    capacity: f64,
+    /// The number of tokens available, negative when requests wait for tokens.
+    tokens: f64,
+    /// The number of tokens added per second.
+    rate: f64,
+    /// The time the tokens were last added.
+    updated: Instant,
+}
+
+/// Client-side rate limiter of the requests to the API.
+///
+/// The limiter is a token bucket: each request takes a token, and the tokens are added at the configured
+/// rate, up to the burst. A request waits for its token when none is available, in the order of the
+/// requests. The clones of a limiter share their tokens, which allows to share one limiter across all the
+/// clients of a Talk2M account.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::client::DmClientBuilder, m2web::{client::ClientBuilder, limiter::RateLimiter}};
+/// let limiter = RateLimiter::per_minute(60).burst(10);
+/// let client = ClientBuilder::default()
+///     .rate_limiter(limiter.clone())
+///     .build()
+///     .unwrap();
+/// let dm_client = DmClientBuilder::default()
+///     .rate_limiter(limiter)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    /// The token bucket, shared by the clones of the limiter.
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing this number of requests per period, all of them at once after an idle period.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let requests = requests.max(1) as f64;
+
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity: requests,
+                tokens: requests,
+                rate: requests / period.as_secs_f64().max(f64::EPSILON),
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Create a limiter allowing this number of requests per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimiter::new(requests, Duration::from_secs(60))
+    }
+
+    /// Set the number of requests sent at once after an idle period.
+    pub fn burst(self, burst: u32) -> Self {
+        {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            bucket.capacity = burst.max(1) as f64;
+            bucket.tokens = bucket.tokens.min(bucket.capacity);
+        }
+
+        self
+    }
+
+    /// Return the number of requests which could be sent without waiting.
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        bucket.refill();
+
+        bucket.tokens.max(0.0) as u32
+    }
+
+    /// Wait for a token, before sending a request.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+            bucket.refill();
+            bucket.tokens -= 1.0;
+            match bucket.tokens < 0.0 {
+                true => Duration::from_secs_f64(-bucket.tokens / bucket.rate),
+                false => Duration::ZERO,
+            }
+        };
+
+        if !wait.is_zero() {
+            clock::sleep(wait).await;
+        }
+    }
+}
+
+impl Bucket {
+    /// Add the tokens accumulated since the last update, up to the capacity.
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::limiter::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn rate_limiter_ok() {
+        let limiter = RateLimiter::new(20, Duration::from_secs(1)).burst(2);
+        let shared = limiter.clone();
+        let start = Instant::now();
+
+        limiter.acquire().await;
+        shared.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(limiter.available(), 0);
+        limiter.acquire().await;
+        shared.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+/// Observer of the requests to the API, to feed the metrics of the application.
+///
+/// The observer is called around each attempt of a request, retries included, with the path of the url of
+/// the request as its endpoint, without its parameters which hold the credentials. It allows to count the
+/// requests and to measure their latency in any metrics system, without this crate depending on one. Both
+/// methods do nothing by default, and are called from the tasks sending the requests, so they should not
+/// block.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, metrics::MetricsObserver};
+/// use std::{
+///     sync::{
+///         atomic::{AtomicU64, Ordering},
+///         Arc,
+///     },
+///     time::Duration,
+/// };
+///
+/// #[derive(Default)]
+/// struct ErrorCounter(AtomicU64);
+///
+/// impl MetricsObserver for ErrorCounter {
+///     fn on_request_end(&self, endpoint: &str, status: Option<u16>, latency: Duration) {
+///         if !status.is_some_and(|status| status < 400) {
+///             self.0.fetch_add(1, Ordering::Relaxed);
+///         }
+///     }
+/// }
+///
+/// let errors = Arc::new(ErrorCounter::default());
+/// let client = ClientBuilder::default()
+///     .metrics_observer(errors.clone())
+///     .build()
+///     .unwrap();
+/// ```
+pub trait MetricsObserver: Send + Sync {
+    /// Observe the start of an attempt of a request to the endpoint.
+    fn on_request_start(&self, endpoint: &str) {
+        let _ = endpoint;
+    }
+
+    /// Observe the end of an attempt of a request to the endpoint, with the HTTP status of its response, or
+    /// none if it failed before, and its latency until the headers of its response.
+    fn on_request_end(&self, endpoint: &str, status: Option<u16>, latency: Duration) {
+        let _ = (endpoint, status, latency);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::m2web::{ewon::Ewon, service::HttpService};
+use reqwest::{Request, Response, ResponseBuilderExt};
+use serde_json::json;
+use std::{
+    convert::Infallible,
+    future::{self, Ready},
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+use tower::Service;
+
+/// Canned response of a `MockTransport`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockResponse {
+    /// The HTTP status of the response.
+    status: u16,
+    /// The content type of the body.
+    content_type: &'static str,
+    /// The body of the response.
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// Respond with this HTTP status and JSON body.
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        MockResponse {
+            status,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    /// Respond with a successful response of the API, holding these fields.
+    pub fn success(fields: serde_json::Value) -> Self {
+        let mut body = json!({ "success": true });
+        if let (Some(body), serde_json::Value::Object(fields)) = (body.as_object_mut(), fields) {
+            body.extend(fields);
+        }
+
+        MockResponse::json(200, body)
+    }
+
+    /// Respond with the list of eWONs, as returned by `getewons`.
+    pub fn ewons(ewons: &[Ewon]) -> Self {
+        MockResponse::success(json!({ "ewons": ewons }))
+    }
+
+    /// Respond with the eWON, as returned by `getewon`.
+    pub fn ewon(ewon: &Ewon) -> Self {
+        MockResponse::success(json!({ "ewon": ewon }))
+    }
+
+    /// Respond with an unsuccessful response of the API, with this HTTP status and message.
+    pub fn error(status: u16, message: &str) -> Self {
+        MockResponse::json(
+            status,
+            json!({ "code": status, "message": message, "success": false }),
+        )
+    }
+
+    /// Respond with this HTTP status and raw body, as returned by the web server of an eWON.
+    pub fn body(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        MockResponse {
+            status,
+            content_type: "text/plain",
+            body: body.into(),
+        }
+    }
+}
+
+/// Transport answering the requests of a client with canned responses.
+///
+/// The transport, available behind the `test-util` feature, allows the applications using this crate to
+/// test their logic without a server. Set as the HTTP service of a client, it answers each request with the
+/// response registered for its endpoint, the end of the path of its url, such as `getewons` or
+/// `get/bea-test/rcgi.bin/ParamForm`, and with an unsuccessful response of the API for the other endpoints.
+/// The clones of a transport share their responses and the endpoints requested.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon, mock::{MockResponse, MockTransport}};
+/// # #[tokio::test]
+/// # async fn count_ewons() -> Result<(), error::Error> {
+/// let transport = MockTransport::new().on(
+///     "getewons",
+///     MockResponse::ewons(&[Ewon {
+///         id: 1,
+///         name: "bea-test".to_string(),
+///         ..Ewon::default()
+///     }]),
+/// );
+/// let client = ClientBuilder::default()
+///     .http_service(transport.clone().into())
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(client.get_ewons(None).await?.len(), 1);
+/// assert_eq!(transport.requested(), vec!["/t2mapi/getewons"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockTransport {
+    /// The responses, with their endpoints, in the order they were registered.
+    responses: Arc<Mutex<Vec<(String, MockResponse)>>>,
+    /// The paths of the urls of the requests, in the order they were received.
+    requested: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockTransport {
+    /// Create a transport without any response.
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Answer the requests to the endpoint with the response, instead of the one registered before, if any.
+    pub fn on(self, endpoint: &str, response: MockResponse) -> Self {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((endpoint.trim_start_matches('/').to_owned(), response));
+        self
+    }
+
+    /// Return the paths of the urls of the requests received so far, without their parameters.
+    pub fn requested(&self) -> Vec<String> {
+        self.requested
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .to_vec()
+    }
+
+    /// Return the response registered for the endpoint of the request.
+    fn respond(&self, request: &Request) -> MockResponse {
+        let path = request.url().path();
+        self.requested
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(path.to_owned());
+
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .rev()
+            .find(|(endpoint, _)| {
+                path.strip_suffix(endpoint.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('/'))
+            })
+            .map(|(_, response)| response.to_owned())
+            .unwrap_or_else(|| {
+                MockResponse::error(404, &format!("No mock response for [{}]", path))
+            })
+    }
+}
+
+impl Service<Request> for MockTransport {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Ready<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let response = self.respond(&request);
+        let http_response = http::Response::builder()
+            .status(response.status)
+            .url(request.url().to_owned())
+            .header(http::header::CONTENT_TYPE, response.content_type)
+            .body(response.body)
+            .unwrap_or_default();
+
+        future::ready(Ok(http_response.into()))
+    }
+}
+
+impl From<MockTransport> for HttpService {
+    fn from(transport: MockTransport) -> Self {
+        HttpService::new(transport)
+    }
+}
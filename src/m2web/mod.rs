@@ -1,3 +1,45 @@
+pub mod account;
+pub mod alarm;
+pub mod breaker;
+pub mod cache;
+pub mod cancellation;
 pub mod client;
+pub(crate) mod clock;
+pub mod config;
+pub mod credentials;
+pub mod device;
+pub mod diff;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod dns;
+pub mod ebd;
 pub mod error;
+pub mod event;
 pub mod ewon;
+pub(crate) mod failover;
+pub mod filter;
+pub mod handle;
+pub mod history;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod http_proxy;
+pub mod interceptor;
+pub mod limiter;
+pub mod metrics;
+#[cfg(feature = "test-util")]
+pub mod mock;
+pub mod parser;
+pub mod proxy;
+pub mod query;
+pub mod region;
+pub mod retry;
+#[cfg(feature = "scheduler")]
+pub mod scheduler;
+pub mod service;
+pub mod tag;
+pub mod timeouts;
+pub mod timezone;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tls;
+pub mod uptime;
+#[cfg(feature = "test-util")]
+pub mod vcr;
+pub mod watcher;
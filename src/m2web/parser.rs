@@ -0,0 +1,435 @@
+use crate::m2web::{error, tag::TagData};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::{str::FromStr, time::SystemTime};
+
+/// Formats of the times exported as text by the eWONs.
+const TIME_FORMATS: [&str; 4] = [
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+/// Parser of the data exported by an eWON.
+///
+/// The exports are made of lines of fields separated by semicolons, the first line being a header naming
+/// the columns. The strings are quoted, and the quotes inside a string are doubled. The numbers use the
+/// decimal separator configured on the eWON, which is a dot by default.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{error, parser::ExportParser};
+/// # fn parse_export() -> Result<(), error::Error> {
+/// let export = "\"TagName\";\"Value\"\r\n\"Temperature\";21,5\r\n";
+/// let table = ExportParser::new().decimal_separator(',').parse(export)?;
+///
+/// let value_column = table.required_column("Value")?;
+/// for row in table.rows() {
+///     let value: f64 = row.number(value_column)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportParser {
+    /// The separator of the fields.
+    separator: char,
+    /// The decimal separator of the numbers.
+    decimal_separator: char,
+    /// Indicates if the first line is a header naming the columns.
+    header: bool,
+}
+
+impl Default for ExportParser {
+    fn default() -> Self {
+        ExportParser::new()
+    }
+}
+
+impl ExportParser {
+    /// Create a parser of semicolon-separated exports, with a header and dot as decimal separator.
+    pub fn new() -> Self {
+        ExportParser {
+            separator: ';',
+            decimal_separator: '.',
+            header: true,
+        }
+    }
+
+    /// Separate the fields by this character.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Parse the numbers with this decimal separator.
+    pub fn decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// Indicate if the first line of the export is a header naming the columns.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Parse the export into a table.
+    ///
+    /// The empty lines are skipped. The lines are numbered from 1, empty lines included, so the errors
+    /// point to the right line of the export.
+    pub fn parse(&self, export: &str) -> Result<ExportTable, error::Error> {
+        let mut lines = export
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let header = match self.header {
+            true => match lines.next() {
+                Some((index, line)) => self
+                    .split_line(index + 1, line)?
+                    .into_iter()
+                    .map(|field| field.text.trim().to_owned())
+                    .collect(),
+                None => vec![],
+            },
+            false => vec![],
+        };
+        let rows = lines
+            .map(|(index, line)| {
+                Ok(ExportRow {
+                    line_number: index + 1,
+                    line: line.trim_end_matches('\r').to_owned(),
+                    fields: self.split_line(index + 1, line)?,
+                    decimal_separator: self.decimal_separator,
+                })
+            })
+            .collect::<Result<Vec<ExportRow>, error::Error>>()?;
+
+        Ok(ExportTable { header, rows })
+    }
+
+    /// Split a line into its fields.
+    fn split_line(&self, line_number: usize, line: &str) -> Result<Vec<ExportField>, error::Error> {
+        let line = line.trim_end_matches('\r');
+        let mut fields = vec![];
+        let mut chars = line.chars().peekable();
+
+        loop {
+            let quoted = chars.peek() == Some(&'"');
+            let mut text = String::new();
+            if quoted {
+                chars.next();
+                let mut terminated = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            text.push('"');
+                        }
+                        '"' => {
+                            terminated = true;
+                            break;
+                        }
+                        _ => text.push(c),
+                    }
+                }
+                if !terminated {
+                    return Err(parse_error(
+                        line_number,
+                        line,
+                        "unterminated quoted string".to_string(),
+                    ));
+                }
+            }
+
+            let mut separated = false;
+            for c in chars.by_ref() {
+                if c == self.separator {
+                    separated = true;
+                    break;
+                }
+                text.push(c);
+            }
+            fields.push(ExportField { text, quoted });
+
+            if !separated {
+                return Ok(fields);
+            }
+        }
+    }
+}
+
+/// Field of an exported line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportField {
+    /// The unquoted text of the field.
+    pub text: String,
+    /// Indicates if the field is quoted, which is the case of strings.
+    pub quoted: bool,
+}
+
+/// Line of an export, split into its fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportRow {
+    /// The number of the line in the export, from 1.
+    line_number: usize,
+    /// The raw line, used to give the context of the errors.
+    line: String,
+    /// The fields of the line.
+    fields: Vec<ExportField>,
+    /// The decimal separator of the numbers.
+    decimal_separator: char,
+}
+
+impl ExportRow {
+    /// Return the number of the line in the export, from 1.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Return the fields of the line.
+    pub fn fields(&self) -> &[ExportField] {
+        &self.fields
+    }
+
+    /// Return the field at the given column.
+    pub fn field(&self, column: usize) -> Result<&ExportField, error::Error> {
+        self.fields
+            .get(column)
+            .ok_or_else(|| self.error(format!("missing field at column {}", column + 1)))
+    }
+
+    /// Return the text of the field at the given column.
+    pub fn text(&self, column: usize) -> Result<&str, error::Error> {
+        Ok(&self.field(column)?.text)
+    }
+
+    /// Parse the field at the given column as a number.
+    pub fn number<T: FromStr>(&self, column: usize) -> Result<T, error::Error> {
+        let text = self.text(column)?.trim();
+        let parsed = match self.decimal_separator {
+            '.' => text.parse(),
+            decimal_separator => text.replace(decimal_separator, ".").parse(),
+        };
+
+        parsed.map_err(|_| self.error(format!("[{}] is not a valid number", text)))
+    }
+
+    /// Parse the field at the given column as a flag, which is set unless it is 0 or empty.
+    pub fn flag(&self, column: usize) -> Result<bool, error::Error> {
+        match self.text(column)?.trim() {
+            "" => Ok(false),
+            _ => Ok(self.number::<f64>(column)? != 0.0),
+        }
+    }
+
+    /// Parse the field at the given column as a time exported in the given timezone.
+    ///
+    /// The eWONs export their times either as seconds since the Unix epoch, shifted to their local time, or as
+    /// text, for example `14/11/2023 22:13:20`. A local time repeated when the clock goes back is read as its
+    /// earliest occurrence, while a local time skipped when the clock goes forward is an error.
+    pub fn time(&self, column: usize, timezone: Tz) -> Result<SystemTime, error::Error> {
+        let text = self.text(column)?.trim();
+        let local = match text.parse::<i64>() {
+            Ok(secs) => DateTime::from_timestamp(secs, 0).map(|time| time.naive_utc()),
+            Err(_) => TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok()),
+        }
+        .ok_or_else(|| self.error(format!("[{}] is not a valid time", text)))?;
+
+        timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|time| SystemTime::from(time.with_timezone(&Utc)))
+            .ok_or_else(|| {
+                self.error(format!(
+                    "[{}] does not exist in timezone {}",
+                    text, timezone
+                ))
+            })
+    }
+
+    /// Parse the field at the given column as the value of a tag: a string if quoted, a number otherwise.
+    pub fn data(&self, column: usize) -> Result<TagData, error::Error> {
+        let field = self.field(column)?;
+        match field.quoted {
+            true => Ok(TagData::Text(field.text.to_owned())),
+            false => Ok(TagData::Number(self.number(column)?)),
+        }
+    }
+
+    /// Return an error about the line, with its number and its content.
+    pub fn error(&self, message: String) -> error::Error {
+        parse_error(self.line_number, &self.line, message)
+    }
+}
+
+/// Export split into its header and its lines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportTable {
+    /// The names of the columns, empty if the export has no header.
+    header: Vec<String>,
+    /// The lines following the header.
+    rows: Vec<ExportRow>,
+}
+
+impl ExportTable {
+    /// Return the names of the columns.
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    /// Return the lines following the header.
+    pub fn rows(&self) -> &[ExportRow] {
+        &self.rows
+    }
+
+    /// Return the index of the column with this name, if any.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|column| column == name)
+    }
+
+    /// Return the index of the column with this name, or an error if the export has no such column.
+    pub fn required_column(&self, name: &str) -> Result<usize, error::Error> {
+        self.column(name).ok_or_else(|| error::Error {
+            http_status: 500,
+            api_code: None,
+            source: None,
+            kind: error::ErrorKind::ExportParsing(format!("missing column [{}]", name)),
+        })
+    }
+}
+
+/// Return an error about a line of an export which could not be parsed.
+fn parse_error(line_number: usize, line: &str, message: String) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        source: None,
+        kind: error::ErrorKind::ExportParsing(format!(
+            "line {}: {} in [{}]",
+            line_number, message, line
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{parser::ExportParser, tag::TagData};
+    use chrono_tz::{Europe, Tz};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn export_parser_ok() {
+        let export = "\u{feff}\"TagName\";\"Value\";\"Comment\"\r\n\
+            \r\n\
+            \"Temp; \"\"inside\"\"\";21,5;\r\n\
+            \"Recipe\";\"PET-500\";\"\"\r\n";
+
+        let table = ExportParser::new()
+            .decimal_separator(',')
+            .parse(export)
+            .unwrap();
+
+        assert_eq!(table.header(), ["TagName", "Value", "Comment"]);
+        assert_eq!(table.column("Comment"), Some(2));
+        assert_eq!(table.rows().len(), 2);
+        assert_eq!(table.rows()[0].line_number(), 3);
+        assert_eq!(table.rows()[0].text(0).unwrap(), "Temp; \"inside\"");
+        assert_eq!(table.rows()[0].data(1).unwrap(), TagData::Number(21.5));
+        assert_eq!(table.rows()[0].text(2).unwrap(), "");
+        assert_eq!(
+            table.rows()[1].data(1).unwrap(),
+            TagData::Text("PET-500".to_string())
+        );
+    }
+
+    #[test]
+    fn export_parser_without_header_ok() {
+        let table = ExportParser::new()
+            .separator(',')
+            .header(false)
+            .parse("1,2\n3,4\n")
+            .unwrap();
+
+        assert!(table.header().is_empty());
+        assert_eq!(table.rows()[1].number::<u32>(1).unwrap(), 4);
+    }
+
+    #[test]
+    fn export_parser_ko() {
+        let export = "\"TagName\";\"Value\"\n\"Temperature\";hot\n\"Recipe;1\n";
+
+        let table = ExportParser::new().parse(export);
+        let err = match table {
+            Ok(_) => panic!("parse should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: line 3: unterminated quoted string in [\"Recipe;1]"
+        );
+
+        let table = ExportParser::new()
+            .parse("\"TagName\";\"Value\"\n\"Temperature\";hot\n")
+            .unwrap();
+        let err = match table.rows()[0].number::<f64>(1) {
+            Ok(_) => panic!("number should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: line 2: [hot] is not a valid number in [\"Temperature\";hot]"
+        );
+        assert_eq!(
+            format!("{}", table.required_column("Quality").unwrap_err()),
+            "Unable to parse export: missing column [Quality]"
+        );
+    }
+
+    #[test]
+    fn export_time_ok() {
+        let table = ExportParser::new()
+            .parse(
+                "\"TimeInt\";\"TimeStr\"\r\n\
+                1700000000;\"14/11/2023 22:13:20\"\r\n\
+                1698546600;\"2023-10-29 02:30:00\"\r\n",
+            )
+            .unwrap();
+        let rows = table.rows();
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(rows[0].time(0, Tz::UTC).unwrap(), at(1700000000));
+        assert_eq!(rows[0].time(1, Tz::UTC).unwrap(), at(1700000000));
+        assert_eq!(rows[0].time(0, Europe::Paris).unwrap(), at(1699996400));
+        assert_eq!(rows[0].time(1, Europe::Paris).unwrap(), at(1699996400));
+        // 02:30 on 29/10/2023 happens twice in Paris: the earliest, in summer time, is kept.
+        assert_eq!(rows[1].time(0, Europe::Paris).unwrap(), at(1698539400));
+        assert_eq!(rows[1].time(1, Europe::Paris).unwrap(), at(1698539400));
+    }
+
+    #[test]
+    fn export_time_ko() {
+        let table = ExportParser::new()
+            .parse("\"TimeStr\"\r\n\"26/03/2023 02:30:00\"\r\n\"yesterday\"\r\n")
+            .unwrap();
+
+        let err = match table.rows()[0].time(0, Europe::Paris) {
+            Ok(_) => panic!("time should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: line 2: [26/03/2023 02:30:00] does not exist in timezone Europe/Paris in [\"26/03/2023 02:30:00\"]"
+        );
+        assert!(table.rows()[0].time(0, Tz::UTC).is_ok());
+        assert_eq!(
+            format!("{}", table.rows()[1].time(0, Tz::UTC).unwrap_err()),
+            "Unable to parse export: line 3: [yesterday] is not a valid time in [\"yesterday\"]"
+        );
+    }
+}
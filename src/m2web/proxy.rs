@@ -0,0 +1,178 @@
+use crate::m2web::{client::Client, error, ewon::EwonRef};
+use flate2::read::GzDecoder;
+use futures::{stream, Stream};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::{borrow::Cow, io::Read};
+
+/// Characters of the eWON name which are encoded in the path of a proxied request, the spaces aside.
+const EWON_NAME_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b' ')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Encode the name of an eWON like Talk2M does for the `encoded_name` of the eWONs, with the spaces as `+`.
+///
+/// The name selecting an eWON by its name then gives the same path to proxied requests, and the same circuit
+/// of the circuit breaker, as the `Ewon` returned by the API.
+pub(in crate::m2web) fn encode_ewon_name(name: &str) -> String {
+    utf8_percent_encode(name, EWON_NAME_ENCODE_SET)
+        .to_string()
+        .replace(' ', "+")
+}
+
+/// Bytes starting a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Return `true` if the data starts like a gzip stream.
+pub(in crate::m2web) fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&GZIP_MAGIC)
+}
+
+/// Stream the chunks of the body of the response, as they are received.
+#[cfg(not(target_arch = "wasm32"))]
+pub(in crate::m2web) fn body_chunks(
+    http_response: reqwest::Response,
+) -> impl Stream<Item = Result<impl AsRef<[u8]>, reqwest::Error>> {
+    stream::unfold(http_response, |mut http_response| async move {
+        let chunk = http_response.chunk().await.transpose()?;
+        Some((chunk, http_response))
+    })
+}
+
+/// Stream the body of the response in one chunk, since the HTTP client of the browser reads it as a whole.
+#[cfg(target_arch = "wasm32")]
+pub(in crate::m2web) fn body_chunks(
+    http_response: reqwest::Response,
+) -> impl Stream<Item = Result<impl AsRef<[u8]>, reqwest::Error>> {
+    stream::once(http_response.bytes())
+}
+
+/// Return an error about a gzip stream which could not be decompressed.
+pub(in crate::m2web) fn gzip_error(error: std::io::Error) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::ExportParsing(format!("invalid gzip stream: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+/// Response of an eWON to a proxied request.
+///
+/// The M2Web API relays the request to the web server of the eWON, then returns the response of the eWON
+/// as is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProxyResponse {
+    /// The HTTP status code returned by the eWON.
+    pub status: u16,
+    /// The content type of the response, if any.
+    pub content_type: Option<String>,
+    /// The raw body of the response.
+    pub body: Vec<u8>,
+}
+
+impl ProxyResponse {
+    /// Return `true` if the eWON returned a success status code.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Return the body of the response as text, replacing the invalid UTF-8 sequences.
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+
+    /// Return the response with its body decompressed, if the body is compressed with gzip.
+    ///
+    /// The bodies compressed by the M2Web API, and announced as such, are already decompressed. This only
+    /// concerns the eWONs returning compressed data, for example a large export, without announcing it.
+    pub fn gunzip(mut self) -> Result<Self, error::Error> {
+        if is_gzip(&self.body) {
+            let mut body = vec![];
+            GzDecoder::new(self.body.as_slice())
+                .read_to_end(&mut body)
+                .map_err(gzip_error)?;
+            self.body = body;
+        }
+
+        Ok(self)
+    }
+
+    /// Return the response if the eWON returned a success status code, or an error otherwise.
+    pub fn error_for_status(self) -> Result<Self, error::Error> {
+        match self.is_success() {
+            true => Ok(self),
+            false => Err(error::Error {
+                http_status: self.status,
+                api_code: None,
+                source: None,
+                kind: error::ErrorKind::DeviceError(self.text().trim().to_owned()),
+            }),
+        }
+    }
+}
+
+/// Proxy to the web server of a device of the LAN of an eWON.
+///
+/// The M2Web API relays the requests to the eWON, which relays them to the device at the given IP address
+/// and port, for example to the web server of a PLC. A proxy is created by `Client::lan_proxy()`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, proxy::ProxyResponse};
+/// # #[tokio::test]
+/// # async fn get_plc_status() -> Result<ProxyResponse, error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let response = client
+///     .lan_proxy("bea-test", "10.0.0.50", 80)
+///     .get("/status")
+///     .await?;
+///
+/// // Do something useful, for example:
+/// println!("{}", response.text());
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct LanProxy<'c, 'a, 'e> {
+    /// The client used to request the API.
+    client: &'c Client<'a>,
+    /// The eWON the device is connected to.
+    ewon: EwonRef<'e>,
+    /// The IP address of the device on the LAN of the eWON.
+    ip: &'e str,
+    /// The port of the web server of the device.
+    port: u16,
+}
+
+impl<'c, 'a, 'e> LanProxy<'c, 'a, 'e> {
+    /// Create a proxy to the device, through the eWON.
+    pub(in crate::m2web) fn new(
+        client: &'c Client<'a>,
+        ewon: EwonRef<'e>,
+        ip: &'e str,
+        port: u16,
+    ) -> Self {
+        LanProxy {
+            client,
+            ewon,
+            ip,
+            port,
+        }
+    }
+
+    /// Send a GET request to the web server of the device.
+    ///
+    /// The response of the device is returned as is, whatever its status code.
+    pub async fn get(&self, device_path: &str) -> Result<ProxyResponse, error::Error> {
+        let proxy_path = format!(
+            "proxy/{}/{}/{}",
+            self.ip,
+            self.port,
+            device_path.trim_start_matches('/')
+        );
+
+        self.client.proxy_get(self.ewon, &proxy_path, None).await
+    }
+}
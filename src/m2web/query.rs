@@ -0,0 +1,94 @@
+use crate::m2web::{
+    client::Client,
+    error,
+    ewon::{Ewon, EwonStatus},
+    filter::EwonFilter,
+};
+
+/// Query of eWONs.
+///
+/// Combine the pool selection, performed by the M2Web API, with the client-side criteria of an
+/// `EwonFilter`. The query is lazy: the API is only requested when `fetch()` is called. A query is
+/// created by `Client::ewons()`.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, ewon::Ewon};
+/// # #[tokio::test]
+/// # async fn get_online_http_ewons() -> Result<Vec<Ewon>, error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let ewons = client
+///     .ewons()
+///     .in_pool("emea")
+///     .online()
+///     .with_service("http")
+///     .fetch()
+///     .await?;
+/// # }
+/// ```
+pub struct EwonQuery<'c, 'a> {
+    /// The client used to request the API.
+    client: &'c Client<'a>,
+    /// The pool the eWONs must belong to.
+    pool: Option<String>,
+    /// The criteria applied once the eWONs are returned by the API.
+    filter: EwonFilter,
+}
+
+impl<'c, 'a> EwonQuery<'c, 'a> {
+    /// Create a query selecting all the eWONs of the corporate account.
+    pub(in crate::m2web) fn new(client: &'c Client<'a>) -> Self {
+        EwonQuery {
+            client,
+            pool: None,
+            filter: EwonFilter::default(),
+        }
+    }
+
+    /// Only select the eWONs belonging to this pool.
+    pub fn in_pool(mut self, pool: &str) -> Self {
+        self.pool = Some(pool.to_owned());
+        self
+    }
+
+    /// Only select the eWONs with this status.
+    pub fn status(mut self, status: EwonStatus) -> Self {
+        self.filter = self.filter.status(status);
+        self
+    }
+
+    /// Only select the online eWONs.
+    pub fn online(self) -> Self {
+        self.status(EwonStatus::Online)
+    }
+
+    /// Only select the offline eWONs.
+    pub fn offline(self) -> Self {
+        self.status(EwonStatus::Offline)
+    }
+
+    /// Only select the eWONs whose description contains this substring.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.filter = self.filter.description(description);
+        self
+    }
+
+    /// Only select the eWONs which have one custom attribute set to this value.
+    pub fn with_custom_attribute(mut self, value: &str) -> Self {
+        self.filter = self.filter.custom_attribute(value);
+        self
+    }
+
+    /// Only select the eWONs which have this service enabled.
+    pub fn with_service(mut self, name: &str) -> Self {
+        self.filter = self.filter.service(name);
+        self
+    }
+
+    /// Request the API and return the eWONs matching the query.
+    pub async fn fetch(self) -> Result<Vec<Ewon>, error::Error> {
+        self.client
+            .get_ewons_filtered(self.pool.as_deref(), &self.filter)
+            .await
+    }
+}
@@ -0,0 +1,32 @@
+/// The default API base url, which serves all regions.
+pub const DEFAULT_T2M_URL: &str = "https://m2web.talk2m.com/t2mapi";
+
+/// Talk2M regions.
+///
+/// Each region is served by its own M2Web servers. The global url redirects to the right region, but
+/// targeting the region of the account directly avoids the redirection.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Region {
+    /// The global M2Web servers.
+    #[default]
+    Global,
+    /// The first european M2Web servers.
+    Eu1,
+    /// The second european M2Web servers.
+    Eu2,
+    /// The first american M2Web servers.
+    Us1,
+}
+
+impl Region {
+    /// Return the API base url of the region.
+    pub fn url(&self) -> &'static str {
+        match self {
+            Region::Global => DEFAULT_T2M_URL,
+            Region::Eu1 => "https://eu1.m2web.talk2m.com/t2mapi",
+            Region::Eu2 => "https://eu2.m2web.talk2m.com/t2mapi",
+            Region::Us1 => "https://us1.m2web.talk2m.com/t2mapi",
+        }
+    }
+}
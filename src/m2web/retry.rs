@@ -0,0 +1,524 @@
+use crate::m2web::{
+    breaker::CircuitBreaker,
+    clock::{self, Instant},
+    error,
+    failover::{is_connect, Failover},
+    interceptor::Interceptor,
+    limiter::RateLimiter,
+    metrics::MetricsObserver,
+    service::{service_error, HttpService},
+};
+use chrono::{DateTime, Utc};
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+#[cfg(feature = "tracing")]
+use tower::BoxError;
+
+/// Throttling of a request by the API, reported to the hook of a `RetryPolicy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThrottleEvent {
+    /// The path of the url of the throttled request, without its parameters.
+    pub url_path: String,
+    /// The attempt of the request which was throttled, starting from 1.
+    pub attempt: u32,
+    /// The delay the API asked to wait, or the backoff delay if it did not.
+    pub delay: Duration,
+    /// Indicates if the request is retried after the delay, or if the throttling is returned as an error.
+    pub retrying: bool,
+    /// The message of the API.
+    pub message: String,
+}
+
+/// Hook called on each throttling of a request.
+type ThrottleHook = Arc<dyn Fn(&ThrottleEvent) + Send + Sync>;
+
+/// Components of a client around the sending of its requests, each of them optional.
+#[derive(Clone, Copy)]
+pub(crate) struct Middleware<'m> {
+    /// The rate limiter of the requests.
+    pub(crate) rate_limiter: Option<&'m RateLimiter>,
+    /// The circuit breaker of the requests.
+    pub(crate) circuit_breaker: Option<&'m CircuitBreaker>,
+    /// The tower service sending the requests, instead of their HTTP client.
+    pub(crate) http_service: Option<&'m HttpService>,
+    /// The failover of the requests to the fallback urls of the API.
+    pub(crate) failover: Option<&'m Failover>,
+    /// The observer of the requests.
+    pub(crate) metrics_observer: Option<&'m dyn MetricsObserver>,
+    /// The hooks called around each request, in order.
+    pub(crate) interceptors: &'m [Arc<dyn Interceptor>],
+}
+
+/// Policy of retry of the requests failing on a transient error.
+///
+/// A request failing on a transient error, a server error or a lost connection for example, is sent again
+/// after a backoff delay, until it succeeds or its attempts are exhausted. The delay starts from the initial
+/// backoff and is multiplied after each attempt, up to the maximum backoff; the jitter randomly shortens it,
+/// so that the clients failing together do not retry together. Only the idempotent requests are retried.
+///
+/// A request throttled by the API is retried after the delay of the `Retry-After` header of the response, or
+/// the delay given by the message of the API, as long as attempts remain and the time waited for the
+/// throttling of the request does not exceed its maximum. Each throttling is reported to the hook of the
+/// policy, if any.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, retry::RetryPolicy};
+/// # use std::time::Duration;
+/// let client = ClientBuilder::default()
+///     .retry_policy(
+///         RetryPolicy::new()
+///             .max_attempts(5)
+///             .initial_backoff(Duration::from_secs(1))
+///             .retry_on_statuses(&[500, 502, 503, 504, 522])
+///             .on_throttle(|event| println!("{} throttled for {:?}", event.url_path, event.delay)),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// The number of attempts of a request, including the first one.
+    max_attempts: u32,
+    /// The delay before the first retry.
+    initial_backoff: Duration,
+    /// The maximum delay between two attempts.
+    max_backoff: Duration,
+    /// The factor applied to the delay after each attempt.
+    multiplier: f64,
+    /// The maximum fraction of the delay randomly removed from it, between 0 and 1.
+    jitter: f64,
+    /// The HTTP status codes of the responses to retry.
+    statuses: Vec<u16>,
+    /// Retry the requests failing to connect or losing their connection.
+    connection_errors: bool,
+    /// Retry the requests timing out.
+    timeouts: bool,
+    /// Retry the requests throttled by the API.
+    throttling: bool,
+    /// The maximum time waited for the throttling of a request.
+    max_throttle_wait: Duration,
+    /// The hook called on each throttling of a request.
+    on_throttle: Option<ThrottleHook>,
+}
+
+impl RetryPolicy {
+    /// Create a policy of 3 attempts, starting from a 500 ms backoff doubled after each attempt, up to 30 s,
+    /// retrying the server errors, the connection errors, the timeouts and the throttling, for up to 5 min.
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            statuses: vec![500, 502, 503, 504],
+            connection_errors: true,
+            timeouts: true,
+            throttling: true,
+            max_throttle_wait: Duration::from_secs(300),
+            on_throttle: None,
+        }
+    }
+
+    /// Create a policy which never retries the requests.
+    pub fn never() -> Self {
+        RetryPolicy::new().max_attempts(1)
+    }
+
+    /// Set the number of attempts of a request, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the maximum delay between two attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set the factor applied to the delay after each attempt, 1 for a constant delay.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the maximum fraction of the delay randomly removed from it, 0 for no jitter.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Retry the responses of these HTTP status codes.
+    pub fn retry_on_statuses(mut self, statuses: &[u16]) -> Self {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Retry the requests failing to connect or losing their connection, or not.
+    pub fn retry_on_connection_errors(mut self, connection_errors: bool) -> Self {
+        self.connection_errors = connection_errors;
+        self
+    }
+
+    /// Retry the requests timing out, or not.
+    pub fn retry_on_timeouts(mut self, timeouts: bool) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Retry the requests throttled by the API, or not.
+    pub fn retry_on_throttling(mut self, throttling: bool) -> Self {
+        self.throttling = throttling;
+        self
+    }
+
+    /// Set the maximum time waited for the throttling of a request, beyond which the throttling is returned as
+    /// an error.
+    pub fn max_throttle_wait(mut self, max_throttle_wait: Duration) -> Self {
+        self.max_throttle_wait = max_throttle_wait;
+        self
+    }
+
+    /// Call the hook on each throttling of a request, retried or not.
+    pub fn on_throttle(
+        mut self,
+        on_throttle: impl Fn(&ThrottleEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_throttle = Some(Arc::new(on_throttle));
+        self
+    }
+
+    /// Return the number of attempts of a request, including the first one.
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Return the delay before the retry following the attempt, starting from 1, without jitter.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .powi(attempt.saturating_sub(1).min(i32::MAX as u32) as i32);
+
+        self.initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff)
+    }
+
+    /// Check if a response of this HTTP status code is retried.
+    pub fn retries_status(&self, http_status: StatusCode) -> bool {
+        self.statuses.contains(&http_status.as_u16())
+    }
+
+    /// Check if a request failing with this error is retried.
+    pub fn retries_error(&self, error: &reqwest::Error) -> bool {
+        (self.connection_errors && (is_connect(error) || error.is_request()))
+            || (self.timeouts && error.is_timeout())
+    }
+
+    /// Return the delay before the retry following the attempt, starting from 1, with jitter.
+    fn delay(&self, attempt: u32) -> Duration {
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+
+        self.backoff(attempt).mul_f64(1.0 - self.jitter * random)
+    }
+
+    /// Send the request, and send it again while it fails on a transient error and attempts remain.
+    ///
+    /// Each attempt fails at once if the circuit breaker, if any, holds the circuit of its endpoint open, waits
+    /// for a token of the rate limiter, if any, is modified by the interceptors, and is sent through the HTTP
+    /// service, if any, or else by the HTTP client of the request, to the fallback urls of the API if it fails
+    /// to connect, and observed by the metrics observer, if any. The requests which are not idempotent and
+    /// those which cannot be cloned, streaming their body, are sent once. A throttled request which is not
+    /// retried is returned as an error.
+    pub(crate) async fn send(
+        &self,
+        request: RequestBuilder,
+        idempotent: bool,
+        middleware: Middleware<'_>,
+    ) -> Result<Response, error::Error> {
+        let Middleware {
+            rate_limiter,
+            circuit_breaker,
+            http_service,
+            failover,
+            metrics_observer,
+            interceptors,
+        } = middleware;
+        let (http_client, request) = request.build_split();
+        let mut request = request?;
+        let mut attempt = 1;
+        let mut throttled_for = Duration::ZERO;
+
+        loop {
+            let retry = match idempotent && attempt < self.max_attempts {
+                true => request.try_clone(),
+                false => None,
+            };
+            if let Some(circuit_breaker) = circuit_breaker {
+                circuit_breaker.check(request.url().path())?;
+            }
+            if let Some(rate_limiter) = rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            for interceptor in interceptors {
+                interceptor.on_request(&mut request)?;
+            }
+            let endpoint = request.url().path().to_owned();
+            if let Some(metrics_observer) = metrics_observer {
+                metrics_observer.on_request_start(&endpoint);
+            }
+            let sent = Instant::now();
+            let transmit = |request| async {
+                match http_service {
+                    Some(http_service) => http_service.send(request).await,
+                    None => http_client.execute(request).await.map_err(Into::into),
+                }
+            };
+            let http_response = match failover {
+                Some(failover) => failover.send(request, transmit).await,
+                None => transmit(request).await,
+            };
+            if let Some(metrics_observer) = metrics_observer {
+                metrics_observer.on_request_end(
+                    &endpoint,
+                    http_response
+                        .as_ref()
+                        .ok()
+                        .map(|http_response| http_response.status().as_u16()),
+                    sent.elapsed(),
+                );
+            }
+            if let Some(circuit_breaker) = circuit_breaker {
+                circuit_breaker.record(
+                    &endpoint,
+                    http_response
+                        .as_ref()
+                        .is_ok_and(|http_response| !http_response.status().is_server_error()),
+                );
+            }
+            #[cfg(feature = "tracing")]
+            trace_attempt(&http_response, attempt, sent.elapsed());
+            if let Ok(ref http_response) = http_response {
+                for interceptor in interceptors {
+                    interceptor.on_response(http_response)?;
+                }
+            }
+            let (retry, delay) = match (retry, http_response) {
+                (Some(retry), Err(error))
+                    if error
+                        .downcast_ref::<reqwest::Error>()
+                        .is_some_and(|error| self.retries_error(error)) =>
+                {
+                    (retry, self.delay(attempt))
+                }
+                (_, Err(error)) => return Err(service_error(error)),
+                (retry, Ok(http_response))
+                    if http_response.status() == StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    let url_path = http_response.url().path().to_owned();
+                    let (retry_after, message) = read_throttling(http_response).await;
+                    let delay = retry_after.unwrap_or_else(|| self.delay(attempt));
+                    let retry = retry.filter(|_| {
+                        self.throttling
+                            && throttled_for
+                                .checked_add(delay)
+                                .is_some_and(|throttled_for| {
+                                    throttled_for <= self.max_throttle_wait
+                                })
+                    });
+                    if let Some(ref on_throttle) = self.on_throttle {
+                        on_throttle(&ThrottleEvent {
+                            url_path,
+                            attempt,
+                            delay,
+                            retrying: retry.is_some(),
+                            message: message.clone(),
+                        });
+                    }
+                    match retry {
+                        Some(retry) => {
+                            throttled_for = throttled_for.saturating_add(delay);
+                            (retry, delay)
+                        }
+                        None => {
+                            return Err(error::Error::from_api_response(
+                                StatusCode::TOO_MANY_REQUESTS,
+                                None,
+                                &message,
+                            ))
+                        }
+                    }
+                }
+                (Some(retry), Ok(http_response)) if self.retries_status(http_response.status()) => {
+                    (retry, self.delay(attempt))
+                }
+                (_, Ok(http_response)) => return Ok(http_response),
+            };
+            clock::sleep(delay).await;
+            request = retry;
+            attempt += 1;
+        }
+    }
+}
+
+/// Record the outcome of an attempt of a request, with the path of its url but not its parameters, which hold
+/// the credentials.
+#[cfg(feature = "tracing")]
+fn trace_attempt(http_response: &Result<Response, BoxError>, attempt: u32, duration: Duration) {
+    let duration_ms = duration.as_millis() as u64;
+
+    match http_response {
+        Ok(http_response) => tracing::debug!(
+            endpoint = http_response.url().path(),
+            attempt,
+            status = http_response.status().as_u16(),
+            duration_ms,
+            outcome = match http_response.status().is_success() {
+                true => "success",
+                false => "failure",
+            },
+            "request to API"
+        ),
+        Err(error) => {
+            let error = error.downcast_ref::<reqwest::Error>();
+            tracing::warn!(
+                endpoint = error.and_then(reqwest::Error::url).map(|url| url.path()),
+                attempt,
+                duration_ms,
+                outcome = match error {
+                    Some(error) if error.is_timeout() => "timeout",
+                    Some(error) if is_connect(error) => "connection error",
+                    _ => "error",
+                },
+                "request to API failed"
+            )
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("statuses", &self.statuses)
+            .field("connection_errors", &self.connection_errors)
+            .field("timeouts", &self.timeouts)
+            .field("throttling", &self.throttling)
+            .field("max_throttle_wait", &self.max_throttle_wait)
+            .field("on_throttle", &self.on_throttle.is_some())
+            .finish()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::new()
+    }
+}
+
+/// Read the delay to wait and the message of a throttled response.
+///
+/// The delay is read from the `Retry-After` header, in seconds or as a date, or else from the message of the
+/// API, for example "Too many requests, retry in 30 seconds".
+async fn read_throttling(http_response: Response) -> (Option<Duration>, String) {
+    let retry_after = http_response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|retry_after| retry_after.to_str().ok())
+        .and_then(parse_retry_after);
+    let http_body = http_response.text().await.unwrap_or_default();
+    // The throttling responses do not always hold a JSON body.
+    let message = match serde_json::from_str::<serde_json::Value>(&http_body) {
+        Ok(api_response) => api_response["message"].as_str().map(str::to_owned),
+        Err(_) => Some(http_body.trim().to_owned()),
+    }
+    .filter(|message| !message.is_empty())
+    .unwrap_or_else(|| "Too many requests".to_string());
+
+    (retry_after.or_else(|| message_delay(&message)), message)
+}
+
+/// Parse the value of a `Retry-After` header, a number of seconds or a date.
+fn parse_retry_after(retry_after: &str) -> Option<Duration> {
+    match retry_after.trim().parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => DateTime::parse_from_rfc2822(retry_after.trim())
+            .ok()
+            .map(|date| {
+                (date.with_timezone(&Utc) - Utc::now())
+                    .to_std()
+                    .unwrap_or_default()
+            }),
+    }
+}
+
+/// Parse the delay given by a message of the API, a number followed by a unit of seconds or minutes.
+fn message_delay(message: &str) -> Option<Duration> {
+    let words = message
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    words.windows(2).find_map(|words| {
+        let count = words[0].parse::<u64>().ok()?;
+        let unit = words[1].to_ascii_lowercase();
+        match unit.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => Some(Duration::from_secs(count)),
+            "min" | "mins" | "minute" | "minutes" => {
+                Some(Duration::from_secs(count.saturating_mul(60)))
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::retry::{message_delay, parse_retry_after};
+    use std::time::Duration;
+
+    #[test]
+    fn parse_retry_after_ok() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn message_delay_ok() {
+        assert_eq!(
+            message_delay("Too many requests, retry in 30 seconds"),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            message_delay("Rate limit of 60 requests per minute exceeded, wait 2 min"),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            message_delay("Retry in 18446744073709551615 minutes"),
+            Some(Duration::from_secs(u64::MAX))
+        );
+        assert_eq!(message_delay("Too many requests"), None);
+    }
+}
@@ -0,0 +1,449 @@
+use crate::m2web::{
+    client::Client,
+    ebd::{Ebd, EbdData},
+    error,
+    history::LogRecord,
+};
+use futures::{
+    future::{self, Either, LocalBoxFuture},
+    stream::FuturesUnordered,
+    StreamExt,
+};
+use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
+
+/// Number of outcomes kept for the subscribers which lag behind.
+const OUTCOMES_CAPACITY: usize = 256;
+
+/// Recurrence of a scheduled job.
+///
+/// Like a cron entry such as `*/15 * * * *`, the runs are aligned on the wall clock: a job run every
+/// 15 minutes runs at :00, :15, :30 and :45, whenever the scheduler is started. The runs could be shifted
+/// by an offset, for example to run at :05, :20, :35 and :50.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Schedule {
+    /// The time between two runs.
+    period: Duration,
+    /// The shift of the runs from the multiples of the period since the Unix epoch.
+    offset: Duration,
+}
+
+impl Schedule {
+    /// Run the job at each multiple of the period since the Unix epoch.
+    ///
+    /// A zero period is rounded up to one millisecond.
+    pub fn every(period: Duration) -> Self {
+        Schedule {
+            period: period.max(Duration::from_millis(1)),
+            offset: Duration::ZERO,
+        }
+    }
+
+    /// Shift the runs by this offset.
+    pub fn offset(mut self, offset: Duration) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Return the time of the first run strictly after the given time.
+    pub fn next_after(&self, time: SystemTime) -> SystemTime {
+        let period = self.period.as_nanos();
+        let offset = self.offset.as_nanos() % period;
+        let elapsed = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        let next = match elapsed.checked_sub(offset) {
+            Some(shifted) => (shifted / period + 1) * period + offset,
+            None => offset,
+        };
+        let next = Duration::new((next / 1_000_000_000) as u64, (next % 1_000_000_000) as u32);
+
+        SystemTime::UNIX_EPOCH + next
+    }
+}
+
+/// Behavior of a job still running when its next run is due.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Overlap {
+    /// Skip the run, the job runs again at its next scheduled time.
+    #[default]
+    Skip,
+    /// Run the job again as soon as the current run completes, the missed runs being merged into one.
+    RunLate,
+}
+
+/// Recurring task run by a `Scheduler`.
+///
+/// The task is run with the client of the scheduler. A failed run is attempted again as many times as
+/// the job allows, each attempt being numbered from 1, so a task could only redo the work which failed.
+pub trait Job {
+    /// Run the task once.
+    fn run<'s>(
+        &'s mut self,
+        client: &'s Client<'_>,
+        attempt: u32,
+    ) -> LocalBoxFuture<'s, Result<(), error::Error>>;
+}
+
+/// Receiver of the values exported by an `ExportJob`.
+///
+/// The trait is implemented for any closure taking the name of an eWON and its logged values.
+pub trait RecordSink {
+    /// Called with the values exported from each eWON.
+    fn write(&mut self, ewon: &str, records: Vec<LogRecord>) -> Result<(), error::Error>;
+}
+
+impl<F> RecordSink for F
+where
+    F: FnMut(&str, Vec<LogRecord>) -> Result<(), error::Error>,
+{
+    fn write(&mut self, ewon: &str, records: Vec<LogRecord>) -> Result<(), error::Error> {
+        self(ewon, records)
+    }
+}
+
+/// Job exporting the logging of several eWONs to a `RecordSink`.
+///
+/// The historical or real-time logging of each eWON is exported, as described by the Export Block
+/// Descriptor, then written to the sink. The run fails if any eWON fails, once all the eWONs have been
+/// exported; its next attempts only export the eWONs which failed.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{ebd::{Ebd, EbdData, EbdTime}, error, history::LogRecord, scheduler::ExportJob};
+/// # use std::time::Duration;
+/// let ebd = Ebd::new(EbdData::HistoricalLog).start(EbdTime::Ago(Duration::from_secs(15 * 60)));
+/// let job = ExportJob::new(ebd, &["bea-test", "bea-spare"], |ewon: &str, records: Vec<LogRecord>| {
+///     println!("{}: {} values", ewon, records.len());
+///     Ok(())
+/// });
+/// ```
+pub struct ExportJob<S: RecordSink> {
+    /// The Export Block Descriptor of the logging.
+    ebd: Ebd,
+    /// The names of the exported eWONs.
+    ewons: Vec<String>,
+    /// The eWONs which are not exported yet by the current run.
+    pending: Vec<String>,
+    /// The receiver of the exported values.
+    sink: S,
+}
+
+impl<S: RecordSink> ExportJob<S> {
+    /// Create a job exporting the logging of the eWONs, selected by their name, to the sink.
+    pub fn new(ebd: Ebd, ewons: &[&str], sink: S) -> Self {
+        ExportJob {
+            ebd,
+            ewons: ewons.iter().map(|ewon| ewon.to_string()).collect(),
+            pending: vec![],
+            sink,
+        }
+    }
+}
+
+impl<S: RecordSink> Job for ExportJob<S> {
+    fn run<'s>(
+        &'s mut self,
+        client: &'s Client<'_>,
+        attempt: u32,
+    ) -> LocalBoxFuture<'s, Result<(), error::Error>> {
+        Box::pin(async move {
+            if attempt <= 1 {
+                self.pending = self.ewons.to_owned();
+            }
+
+            let mut first_error = None;
+            let mut failed = vec![];
+            for ewon in std::mem::take(&mut self.pending) {
+                let records = match self.ebd.data() {
+                    EbdData::RealTimeLog => {
+                        client.get_realtime_data(ewon.as_str(), &self.ebd).await
+                    }
+                    _ => client.get_historical_data(ewon.as_str(), &self.ebd).await,
+                };
+                if let Err(err) = records.and_then(|records| self.sink.write(&ewon, records)) {
+                    first_error.get_or_insert(err);
+                    failed.push(ewon);
+                }
+            }
+            self.pending = failed;
+
+            match first_error {
+                Some(err) => Err(err),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+/// Outcome of a run of a scheduled job.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobOutcome {
+    /// The name of the job.
+    pub job: String,
+    /// The time at which the run started.
+    pub started_at: SystemTime,
+    /// The number of attempts of the run.
+    pub attempts: u32,
+    /// The error of the last attempt, if the run failed.
+    pub error: Option<String>,
+}
+
+impl JobOutcome {
+    /// Return `true` if the run succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Job registered in a `Scheduler`, with its schedule and its retry and overlap control.
+pub struct ScheduledJob<'j> {
+    /// The name of the job, used in its outcomes.
+    name: String,
+    /// The recurrence of the job.
+    schedule: Schedule,
+    /// The task of the job, taken while it is running.
+    job: Option<Box<dyn Job + 'j>>,
+    /// The number of additional attempts of a failed run.
+    retries: u32,
+    /// The delay between two attempts of a failed run.
+    retry_delay: Duration,
+    /// The behavior of the job still running when its next run is due.
+    overlap: Overlap,
+    /// The time of the next run, known once the scheduler is started.
+    next_run: Option<SystemTime>,
+    /// Indicates if a run is due as soon as the current run completes.
+    run_late: bool,
+}
+
+impl<'j> ScheduledJob<'j> {
+    /// Schedule the job, without retry of the failed runs and skipping the overlapping runs.
+    pub fn new(name: &str, schedule: Schedule, job: impl Job + 'j) -> Self {
+        ScheduledJob {
+            name: name.to_owned(),
+            schedule,
+            job: Some(Box::new(job)),
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            overlap: Overlap::default(),
+            next_run: None,
+            run_late: false,
+        }
+    }
+
+    /// Attempt a failed run again up to this number of times.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Wait this delay between two attempts of a failed run.
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Behave this way when the job is still running when its next run is due.
+    pub fn overlap(mut self, overlap: Overlap) -> Self {
+        self.overlap = overlap;
+        self
+    }
+}
+
+/// Scheduler of recurring jobs.
+///
+/// Run the jobs at their scheduled times, concurrently with each other, and broadcast a `JobOutcome` once
+/// each run completes. A job never runs concurrently with itself: when a job is still running at its next
+/// scheduled time, its `Overlap` decides whether the run is skipped or delayed.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{
+/// #     client::ClientBuilder, ebd::{Ebd, EbdData, EbdTime}, error, history::LogRecord,
+/// #     scheduler::{ExportJob, Schedule, ScheduledJob, Scheduler},
+/// # };
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn schedule_exports() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let ebd = Ebd::new(EbdData::HistoricalLog).start(EbdTime::Ago(Duration::from_secs(15 * 60)));
+/// let job = ExportJob::new(ebd, &["bea-test"], |ewon: &str, records: Vec<LogRecord>| {
+///     println!("{}: {} values", ewon, records.len());
+///     Ok(())
+/// });
+///
+/// let mut scheduler = Scheduler::new();
+/// scheduler.add(
+///     ScheduledJob::new("history", Schedule::every(Duration::from_secs(15 * 60)), job)
+///         .retries(3)
+///         .retry_delay(Duration::from_secs(30)),
+/// );
+/// scheduler.run(&client).await;
+/// # }
+/// ```
+pub struct Scheduler<'j> {
+    /// The scheduled jobs.
+    jobs: Vec<ScheduledJob<'j>>,
+    /// The channel used to broadcast the outcomes.
+    sender: broadcast::Sender<JobOutcome>,
+}
+
+impl<'j> Default for Scheduler<'j> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl<'j> Scheduler<'j> {
+    /// Create a scheduler without any job.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(OUTCOMES_CAPACITY);
+
+        Scheduler {
+            jobs: vec![],
+            sender,
+        }
+    }
+
+    /// Add a job to the scheduler.
+    pub fn add(&mut self, job: ScheduledJob<'j>) -> &mut Self {
+        self.jobs.push(job);
+        self
+    }
+
+    /// Return a receiver of the outcomes broadcast by the scheduler.
+    pub fn subscribe(&self) -> broadcast::Receiver<JobOutcome> {
+        self.sender.subscribe()
+    }
+
+    /// Run the jobs at their scheduled times, forever.
+    ///
+    /// The failed runs do not stop the scheduler, they are only reported by their outcome. The scheduler is
+    /// stopped by dropping the returned future, which cancels the running jobs.
+    pub async fn run(&mut self, client: &Client<'_>) {
+        let start = SystemTime::now();
+        for job in &mut self.jobs {
+            job.next_run = Some(job.schedule.next_after(start));
+        }
+
+        let mut running = FuturesUnordered::new();
+        loop {
+            let now = SystemTime::now();
+            for (index, scheduled) in self.jobs.iter_mut().enumerate() {
+                let due = scheduled.run_late && scheduled.job.is_some()
+                    || scheduled.next_run.is_some_and(|next_run| next_run <= now);
+                if !due {
+                    continue;
+                }
+                if scheduled.next_run.is_some_and(|next_run| next_run <= now) {
+                    scheduled.next_run = Some(scheduled.schedule.next_after(now));
+                }
+
+                match scheduled.job.take() {
+                    Some(job) => {
+                        scheduled.run_late = false;
+                        running.push(Self::run_job(
+                            index,
+                            job,
+                            client,
+                            scheduled.name.to_owned(),
+                            scheduled.retries,
+                            scheduled.retry_delay,
+                        ));
+                    }
+                    None => scheduled.run_late = scheduled.overlap == Overlap::RunLate,
+                }
+            }
+
+            let next_run = self
+                .jobs
+                .iter()
+                .filter_map(|scheduled| scheduled.next_run)
+                .min();
+            let sleep = match next_run {
+                Some(next_run) => {
+                    tokio::time::sleep(next_run.duration_since(now).unwrap_or_default())
+                }
+                // A scheduler without any job never wakes up.
+                None => tokio::time::sleep(Duration::MAX),
+            };
+            let completed = match running.is_empty() {
+                true => {
+                    sleep.await;
+                    None
+                }
+                false => match future::select(Box::pin(sleep), running.next()).await {
+                    Either::Left(_) => None,
+                    Either::Right((completed, _)) => completed,
+                },
+            };
+
+            if let Some((index, job, outcome)) = completed {
+                self.jobs[index].job = Some(job);
+                // An error only means there is no subscriber for now.
+                let _ = self.sender.send(outcome);
+            }
+        }
+    }
+
+    /// Run a job, attempting it again while it fails and it could be retried.
+    async fn run_job<'c>(
+        index: usize,
+        mut job: Box<dyn Job + 'j>,
+        client: &'c Client<'_>,
+        name: String,
+        retries: u32,
+        retry_delay: Duration,
+    ) -> (usize, Box<dyn Job + 'j>, JobOutcome) {
+        let started_at = SystemTime::now();
+        let mut attempts = 0;
+        let error = loop {
+            attempts += 1;
+            match job.run(client, attempts).await {
+                Ok(()) => break None,
+                Err(err) if attempts > retries => break Some(err.to_string()),
+                Err(_) => tokio::time::sleep(retry_delay).await,
+            }
+        };
+
+        (
+            index,
+            job,
+            JobOutcome {
+                job: name,
+                started_at,
+                attempts,
+                error,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::scheduler::Schedule;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn schedule_next_after_ok() {
+        let schedule = Schedule::every(Duration::from_secs(15 * 60));
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(schedule.next_after(at(1700000000)), at(1700000100));
+        assert_eq!(schedule.next_after(at(1700000100)), at(1700001000));
+        assert_eq!(
+            schedule
+                .offset(Duration::from_secs(5 * 60))
+                .next_after(at(1700000000)),
+            at(1700000400)
+        );
+        assert_eq!(
+            schedule
+                .offset(Duration::from_secs(5 * 60))
+                .next_after(at(0)),
+            at(300)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::m2web::error;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Client as HttpClient;
+use reqwest::{Request, Response};
+use std::{
+    fmt,
+    task::{Context, Poll},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use tower::Layer;
+use tower::{util::BoxCloneSyncService, BoxError, Service, ServiceExt};
+
+/// Future returned by an `HttpService`.
+type ResponseFuture =
+    <BoxCloneSyncService<Request, Response, BoxError> as Service<Request>>::Future;
+
+/// Tower service sending the requests of a client to the API.
+///
+/// By default, a client sends its requests with its HTTP client. An `HttpService` set on the builder sends
+/// them instead, which allows to compose the standard tower middleware, timeouts, rate limits, tracing or
+/// retries, in front of the HTTP client. The service is itself a tower service, which may be wrapped in
+/// other layers.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, service::HttpService};
+/// use std::time::Duration;
+/// use tower::{timeout::TimeoutLayer, ServiceBuilder};
+///
+/// let http_service = HttpService::new(
+///     ServiceBuilder::new()
+///         .layer(TimeoutLayer::new(Duration::from_secs(60)))
+///         .map_request(|request: reqwest::Request| {
+///             println!("{} {}", request.method(), request.url().path());
+///             request
+///         })
+///         .service(reqwest::Client::new()),
+/// );
+/// let client = ClientBuilder::default()
+///     .http_service(http_service)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct HttpService {
+    /// The boxed service.
+    inner: BoxCloneSyncService<Request, Response, BoxError>,
+}
+
+impl HttpService {
+    /// Box the service sending the requests.
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Service<Request, Response = Response> + Clone + Send + Sync + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
+        HttpService {
+            inner: BoxCloneSyncService::new(service.map_err(Into::into)),
+        }
+    }
+
+    /// Wrap the HTTP client in the layer.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn layered<L>(http_client: HttpClient, layer: L) -> Self
+    where
+        L: Layer<HttpClient>,
+        L::Service: Service<Request, Response = Response> + Clone + Send + Sync + 'static,
+        <L::Service as Service<Request>>::Error: Into<BoxError>,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        HttpService::new(layer.layer(http_client))
+    }
+
+    /// Send the request through the service.
+    pub(crate) async fn send(&self, request: Request) -> Result<Response, BoxError> {
+        self.inner.clone().oneshot(request).await
+    }
+}
+
+impl Service<Request> for HttpService {
+    type Response = Response;
+    type Error = BoxError;
+    type Future = ResponseFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.inner.call(request)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<HttpClient> for HttpService {
+    fn from(http_client: HttpClient) -> Self {
+        HttpService::new(http_client)
+    }
+}
+
+impl fmt::Debug for HttpService {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HttpService").finish_non_exhaustive()
+    }
+}
+
+/// Map an error of the service to an error, keeping the errors of the HTTP client.
+pub(crate) fn service_error(error: BoxError) -> error::Error {
+    match error.downcast::<reqwest::Error>() {
+        Ok(error) => (*error).into(),
+        Err(error) if error.is::<tower::timeout::error::Elapsed>() => error::Error {
+            http_status: 408,
+            api_code: None,
+            kind: error::ErrorKind::Timeout(format!("Request to API timed out: {}", error)),
+            source: Some(error),
+        },
+        Err(error) => error::Error {
+            http_status: 500,
+            api_code: None,
+            kind: error::ErrorKind::UnknownError(format!("HTTP service error: {}", error)),
+            source: Some(error),
+        },
+    }
+}
@@ -0,0 +1,334 @@
+use crate::m2web::{error, parser::ExportParser};
+use std::{fmt, time::SystemTime};
+
+/// Value of a tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TagData {
+    /// The value of a boolean, integer or floating point tag.
+    Number(f64),
+    /// The value of a string tag.
+    Text(String),
+}
+
+/// Display the value of a tag.
+impl fmt::Display for TagData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagData::Number(number) => write!(f, "{}", number),
+            TagData::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl From<f64> for TagData {
+    fn from(number: f64) -> Self {
+        TagData::Number(number)
+    }
+}
+
+impl From<i32> for TagData {
+    fn from(number: i32) -> Self {
+        TagData::Number(number.into())
+    }
+}
+
+impl From<bool> for TagData {
+    fn from(boolean: bool) -> Self {
+        TagData::Number(u8::from(boolean).into())
+    }
+}
+
+impl From<&str> for TagData {
+    fn from(text: &str) -> Self {
+        TagData::Text(text.to_owned())
+    }
+}
+
+impl From<String> for TagData {
+    fn from(text: String) -> Self {
+        TagData::Text(text)
+    }
+}
+
+/// Instant value of a tag.
+///
+/// Each tag of an eWON is read with these parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagValue {
+    /// The id of the tag on the eWON.
+    pub id: u32,
+    /// The unique name of the tag.
+    pub name: String,
+    /// The value of the tag.
+    pub value: TagData,
+    /// The alarm status of the tag, 0 if the tag is not in alarm.
+    pub alarm_status: u32,
+    /// The OPC quality of the value.
+    pub quality: u16,
+    /// The time at which the value has been read.
+    pub timestamp: SystemTime,
+}
+
+impl TagValue {
+    /// Return `true` if the OPC quality of the value is good.
+    pub fn is_good_quality(&self) -> bool {
+        self.quality & 0xC0 == 0xC0
+    }
+
+    /// Parse the instant values exported by the eWON, read at the given time.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per tag.
+    pub(in crate::m2web) fn parse_instant_values(
+        export: &str,
+        timestamp: SystemTime,
+    ) -> Result<Vec<TagValue>, error::Error> {
+        let table = ExportParser::new().parse(export)?;
+        let (id_column, name_column, value_column) = (
+            table.required_column("TagId")?,
+            table.required_column("TagName")?,
+            table.required_column("Value")?,
+        );
+        let (alarm_status_column, quality_column) = (
+            table.required_column("AlStatus")?,
+            table.required_column("Quality")?,
+        );
+
+        table
+            .rows()
+            .iter()
+            .map(|row| {
+                Ok(TagValue {
+                    id: row.number(id_column)?,
+                    name: row.text(name_column)?.to_owned(),
+                    value: row.data(value_column)?,
+                    alarm_status: row.number(alarm_status_column)?,
+                    quality: row.number(quality_column)?,
+                    timestamp,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Type of a tag.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TagType {
+    /// A boolean tag.
+    Boolean,
+    /// A floating point tag.
+    Float,
+    /// A signed integer tag.
+    Integer,
+    /// An unsigned integer tag.
+    DWord,
+    /// A string tag.
+    String,
+    /// A type unknown to this crate, with its code.
+    Other(u32),
+}
+
+impl From<u32> for TagType {
+    fn from(code: u32) -> Self {
+        match code {
+            0 => TagType::Boolean,
+            1 => TagType::Float,
+            2 => TagType::Integer,
+            3 => TagType::DWord,
+            6 => TagType::String,
+            _ => TagType::Other(code),
+        }
+    }
+}
+
+/// Groups of tags, used to select the tags of an export.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TagGroup {
+    /// The group A.
+    A,
+    /// The group B.
+    B,
+    /// The group C.
+    C,
+    /// The group D.
+    D,
+}
+
+/// Display the letter of the group.
+impl fmt::Display for TagGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagGroup::A => write!(f, "A"),
+            TagGroup::B => write!(f, "B"),
+            TagGroup::C => write!(f, "C"),
+            TagGroup::D => write!(f, "D"),
+        }
+    }
+}
+
+/// Alarm parameters of a tag.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TagAlarm {
+    /// The threshold under which the low alarm is raised.
+    pub low: f64,
+    /// The threshold above which the high alarm is raised.
+    pub high: f64,
+    /// The threshold under which the low-low alarm is raised.
+    pub low_low: f64,
+    /// The threshold above which the high-high alarm is raised.
+    pub high_high: f64,
+    /// The deadband applied to the thresholds.
+    pub deadband: f64,
+    /// The time, in seconds, the value must exceed a threshold before the alarm is raised.
+    pub delay: u32,
+    /// The message displayed with the alarm.
+    pub hint: String,
+}
+
+/// Tag parameters.
+///
+/// Each tag configured on an eWON is described by these parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    /// The id of the tag on the eWON.
+    pub id: u32,
+    /// The unique name of the tag.
+    pub name: String,
+    /// The user description of the tag.
+    pub description: String,
+    /// The type of the tag.
+    pub tag_type: TagType,
+    /// The unit of the value, empty if none is set.
+    pub unit: String,
+    /// The alarm parameters, if the alarm of the tag is enabled.
+    pub alarm: Option<TagAlarm>,
+    /// The groups the tag belongs to.
+    pub groups: Vec<TagGroup>,
+}
+
+impl Tag {
+    /// Parse the tag list exported by the eWON.
+    ///
+    /// The export starts with a header naming the columns, followed by a line per tag. Only the tag id and
+    /// name are mandatory; the other parameters default to empty values when their column is missing.
+    pub(in crate::m2web) fn parse_tag_list(export: &str) -> Result<Vec<Tag>, error::Error> {
+        let table = ExportParser::new().parse(export)?;
+        let (id_column, name_column) =
+            (table.required_column("Id")?, table.required_column("Name")?);
+
+        table
+            .rows()
+            .iter()
+            .map(|row| {
+                let text = |name: &str| match table.column(name) {
+                    Some(column) => Ok(row.text(column)?.to_owned()),
+                    None => Ok::<String, error::Error>(String::new()),
+                };
+                let number = |name: &str| match table.column(name) {
+                    Some(column) if !row.text(column)?.trim().is_empty() => row.number(column),
+                    _ => Ok(0.0),
+                };
+                let flag = |name: &str| match table.column(name) {
+                    Some(column) => row.flag(column),
+                    None => Ok(false),
+                };
+
+                let mut groups = vec![];
+                for (name, group) in [
+                    ("IVGroupA", TagGroup::A),
+                    ("IVGroupB", TagGroup::B),
+                    ("IVGroupC", TagGroup::C),
+                    ("IVGroupD", TagGroup::D),
+                ] {
+                    if flag(name)? {
+                        groups.push(group);
+                    }
+                }
+
+                Ok(Tag {
+                    id: row.number(id_column)?,
+                    name: row.text(name_column)?.to_owned(),
+                    description: text("Description")?,
+                    tag_type: TagType::from(number("Type")? as u32),
+                    unit: text("CustomUnit")?,
+                    alarm: match flag("AlEnabled")? {
+                        true => Some(TagAlarm {
+                            low: number("AlLow")?,
+                            high: number("AlHigh")?,
+                            low_low: number("AlLoLo")?,
+                            high_high: number("AlHiHi")?,
+                            deadband: number("AlLevelDB")?,
+                            delay: number("AlTimeDB")? as u32,
+                            hint: text("AlHint")?,
+                        }),
+                        false => None,
+                    },
+                    groups,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::tag::{Tag, TagData, TagGroup, TagType, TagValue};
+    use std::time::SystemTime;
+
+    #[test]
+    fn parse_instant_values_ok() {
+        let export = "\"TagId\";\"TagName\";\"Value\";\"AlStatus\";\"AlType\";\"Quality\"\r\n\
+            1;\"Temperature\";21.5;0;0;65472\r\n\
+            2;\"Recipe\";\"PET-500\";2;1;65472\r\n\
+            3;\"Pressure\";0;0;0;0\r\n";
+        let timestamp = SystemTime::now();
+
+        let tags = TagValue::parse_instant_values(export, timestamp).unwrap();
+
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[0].name, "Temperature");
+        assert_eq!(tags[0].value, TagData::Number(21.5));
+        assert!(tags[0].is_good_quality());
+        assert_eq!(tags[1].value, TagData::Text("PET-500".to_string()));
+        assert_eq!(tags[1].alarm_status, 2);
+        assert!(!tags[2].is_good_quality());
+        assert_eq!(tags[2].timestamp, timestamp);
+    }
+
+    #[test]
+    fn parse_instant_values_ko() {
+        let export = "\"TagId\";\"TagName\";\"Value\";\"AlStatus\";\"AlType\";\"Quality\"\n\
+            1;\"Temperature\";hot;0;0;65472\n";
+
+        let err = match TagValue::parse_instant_values(export, SystemTime::now()) {
+            Ok(_) => panic!("parse_instant_values should have returned an error::ExportParsing"),
+            Err(err) => err,
+        };
+
+        assert_eq!(
+            format!("{}", err),
+            "Unable to parse export: line 2: [hot] is not a valid number in [1;\"Temperature\";hot;0;0;65472]"
+        );
+    }
+
+    #[test]
+    fn parse_tag_list_ok() {
+        let export = "\"Id\";\"Name\";\"Description\";\"AlEnabled\";\"AlHint\";\"AlHigh\";\"AlLow\";\"AlTimeDB\";\"AlLevelDB\";\"IVGroupA\";\"IVGroupB\";\"IVGroupC\";\"IVGroupD\";\"Type\";\"CustomUnit\"\r\n\
+            1;\"Temperature\";\"Oven temperature\";1;\"Oven too hot\";250;0;10;2;1;0;1;0;1;\"°C\"\r\n\
+            2;\"Recipe\";\"\";0;\"\";0;0;0;0;0;0;0;0;6;\"\"\r\n";
+
+        let tags = Tag::parse_tag_list(export).unwrap();
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].description, "Oven temperature");
+        assert_eq!(tags[0].tag_type, TagType::Float);
+        assert_eq!(tags[0].unit, "°C");
+        assert_eq!(tags[0].groups, vec![TagGroup::A, TagGroup::C]);
+        let alarm = tags[0].alarm.as_ref().unwrap();
+        assert_eq!(alarm.high, 250.0);
+        assert_eq!(alarm.delay, 10);
+        assert_eq!(alarm.hint, "Oven too hot");
+        assert_eq!(tags[1].tag_type, TagType::String);
+        assert_eq!(tags[1].alarm, None);
+        assert!(tags[1].groups.is_empty());
+    }
+}
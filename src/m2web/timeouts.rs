@@ -0,0 +1,62 @@
+use reqwest::RequestBuilder;
+use std::time::Duration;
+
+/// Timeouts of the requests to the API.
+///
+/// Each timeout is unlimited unless set. The total timeout limits each attempt of a request, from its
+/// connection to the end of its response, while the read timeout limits the time between two reads of the
+/// response, which catches the relays hanging in the middle of a large response.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::client::ClientBuilder;
+/// # use std::time::Duration;
+/// let client = ClientBuilder::default()
+///     .connect_timeout(Duration::from_secs(10))
+///     .read_timeout(Duration::from_secs(30))
+///     .timeout(Duration::from_secs(120))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timeouts {
+    /// The maximum time to connect to the API.
+    pub connect: Option<Duration>,
+    /// The maximum time between two reads of a response.
+    pub read: Option<Duration>,
+    /// The maximum time of a request, from its connection to the end of its response.
+    pub total: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Create unlimited timeouts.
+    pub fn new() -> Self {
+        Timeouts::default()
+    }
+
+    /// Set the maximum time to connect to the API.
+    pub fn connect(mut self, connect: Duration) -> Self {
+        self.connect = Some(connect);
+        self
+    }
+
+    /// Set the maximum time between two reads of a response.
+    pub fn read(mut self, read: Duration) -> Self {
+        self.read = Some(read);
+        self
+    }
+
+    /// Set the maximum time of a request, from its connection to the end of its response.
+    pub fn total(mut self, total: Duration) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Apply the total timeout to the request.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.total {
+            Some(total) => request.timeout(total),
+            None => request,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// Timezones of the eWONs, used to read the times they export.
+///
+/// The eWONs export their times in their local time, unless the export is requested in UTC. The timezone of
+/// an eWON is looked up by its name: the timezone set for this eWON is used if any, otherwise the default
+/// timezone, which is UTC unless set otherwise.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, timezone::DeviceTimezones};
+/// use chrono_tz::{America, Europe};
+///
+/// let timezones = DeviceTimezones::new()
+///     .default_timezone(Europe::Paris)
+///     .device("bea-test", America::New_York);
+///
+/// let client = ClientBuilder::default()
+///     .device_timezones(timezones)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeviceTimezones<'a> {
+    /// The timezone of the eWONs without their own timezone.
+    default: Tz,
+    /// The timezones of the eWONs, keyed by the name of the eWON.
+    devices: HashMap<&'a str, Tz>,
+}
+
+impl<'a> DeviceTimezones<'a> {
+    /// Create a provider reading all the times in UTC.
+    pub fn new() -> Self {
+        DeviceTimezones::default()
+    }
+
+    /// Read the times of the eWONs without their own timezone in this timezone.
+    pub fn default_timezone(mut self, timezone: Tz) -> Self {
+        self.default = timezone;
+        self
+    }
+
+    /// Read the times of the eWON with this name in this timezone.
+    pub fn device(mut self, ewon_name: &'a str, timezone: Tz) -> Self {
+        self.devices.insert(ewon_name, timezone);
+        self
+    }
+
+    /// Return `true` if some eWONs have their own timezone.
+    pub fn has_devices(&self) -> bool {
+        !self.devices.is_empty()
+    }
+
+    /// Return the timezone of the eWON with this name.
+    pub fn get(&self, ewon_name: &str) -> Tz {
+        self.devices.get(ewon_name).copied().unwrap_or(self.default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::timezone::DeviceTimezones;
+    use chrono_tz::{America, Europe, Tz};
+
+    #[test]
+    fn device_timezones_ok() {
+        let timezones = DeviceTimezones::new().device("bea-test", America::New_York);
+        assert!(timezones.has_devices());
+        assert_eq!(timezones.get("bea-test"), America::New_York);
+        assert_eq!(timezones.get("bea-spare"), Tz::UTC);
+
+        let timezones = timezones.default_timezone(Europe::Paris);
+        assert_eq!(timezones.get("bea-test"), America::New_York);
+        assert_eq!(timezones.get("bea-spare"), Europe::Paris);
+        assert!(!DeviceTimezones::new().has_devices());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::m2web::error;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::aws_lc_rs,
+    pki_types::{pem::PemObject, CertificateDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, SignatureScheme,
+};
+use rustls_platform_verifier::Verifier;
+use sha2::{Digest, Sha256};
+use std::{iter, str::FromStr, sync::Arc};
+
+/// Pin of the certificate of the Talk2M servers, or of its public key.
+///
+/// A client with pins only connects to the servers whose chain of certificates, once verified against the
+/// root certificates, holds a pinned certificate or public key. Pinning a public key rather than a
+/// certificate survives the renewals of the certificate which keep its key.
+///
+/// A pin is parsed from the SHA-256 fingerprint of a certificate in hexadecimal, such as printed by
+/// `openssl x509 -fingerprint -sha256`, or from the SHA-256 hash of a public key in base64, prefixed with
+/// `sha256//` as for the `--pinnedpubkey` option of curl.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, tls::CertificatePin};
+/// let client = ClientBuilder::default()
+///     .pin(
+///         "sha256//YLh1dUR9y6Kja30RrAn7JKnbQG/uEtLMkBgFF2Fuihg="
+///             .parse::<CertificatePin>()
+///             .unwrap(),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CertificatePin {
+    /// The SHA-256 hash of a certificate, in DER.
+    Certificate([u8; 32]),
+    /// The SHA-256 hash of the public key of a certificate, its subject public key info in DER.
+    PublicKey([u8; 32]),
+}
+
+impl CertificatePin {
+    /// Return the pin of the certificate, in DER.
+    pub fn of_certificate(certificate: &[u8]) -> Self {
+        CertificatePin::Certificate(Sha256::digest(certificate).into())
+    }
+
+    /// Return the pin of the public key of the certificate, in DER.
+    pub fn of_public_key(certificate: &[u8]) -> Result<Self, error::Error> {
+        let certificate = CertificateDer::from(certificate);
+        let certificate = webpki::EndEntityCert::try_from(&certificate)
+            .map_err(|error| tls_error(format!("unable to parse the certificate: {}", error)))?;
+
+        Ok(CertificatePin::PublicKey(
+            Sha256::digest(certificate.subject_public_key_info()).into(),
+        ))
+    }
+
+    /// Check if the certificate, in DER, matches the pin.
+    fn matches(&self, certificate: &CertificateDer<'_>) -> bool {
+        match self {
+            CertificatePin::Certificate(_) => *self == CertificatePin::of_certificate(certificate),
+            CertificatePin::PublicKey(_) => {
+                CertificatePin::of_public_key(certificate).is_ok_and(|pin| pin == *self)
+            }
+        }
+    }
+}
+
+impl FromStr for CertificatePin {
+    type Err = error::Error;
+
+    fn from_str(pin: &str) -> Result<Self, Self::Err> {
+        let invalid_pin = || tls_error(format!("invalid certificate pin [{}]", pin));
+        let hash = match pin.strip_prefix("sha256//") {
+            Some(public_key) => STANDARD.decode(public_key).map_err(|_| invalid_pin())?,
+            None => {
+                let fingerprint = pin.replace(':', "");
+                (0..fingerprint.len())
+                    .step_by(2)
+                    .map(|i| {
+                        fingerprint
+                            .get(i..i + 2)
+                            .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid_pin)?
+            }
+        };
+        let hash = <[u8; 32]>::try_from(hash).map_err(|_| invalid_pin())?;
+
+        Ok(match pin.starts_with("sha256//") {
+            true => CertificatePin::PublicKey(hash),
+            false => CertificatePin::Certificate(hash),
+        })
+    }
+}
+
+/// Verifier of the certificates of the servers, checking their pins once their chain is verified.
+#[derive(Debug)]
+struct PinnedVerifier {
+    /// The verifier of the chain of certificates, against the platform and the extra root certificates.
+    inner: Verifier,
+    /// The pins, one of which the chain of certificates must match.
+    pins: Vec<CertificatePin>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+
+        match iter::once(end_entity)
+            .chain(intermediates)
+            .any(|certificate| self.pins.iter().any(|pin| pin.matches(certificate)))
+        {
+            true => Ok(verified),
+            false => Err(rustls::Error::General(
+                "the certificate of the server does not match its pins".to_string(),
+            )),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner
+            .verify_tls12_signature(message, certificate, signature)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner
+            .verify_tls13_signature(message, certificate, signature)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Parse the root certificates of the PEM bundles.
+pub(crate) fn root_certificates(
+    pem_bundles: &[&[u8]],
+) -> Result<Vec<CertificateDer<'static>>, error::Error> {
+    pem_bundles
+        .iter()
+        .flat_map(|pem_bundle| CertificateDer::pem_slice_iter(pem_bundle))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| tls_error(format!("unable to parse the root certificates: {}", error)))
+}
+
+/// Build the TLS configuration verifying the certificates of the servers against the platform and the extra
+/// root certificates, then against the pins.
+pub(crate) fn pinned_tls_config(
+    root_certificates: Vec<CertificateDer<'static>>,
+    pins: &[CertificatePin],
+) -> Result<ClientConfig, error::Error> {
+    let provider = Arc::new(aws_lc_rs::default_provider());
+    let verifier = PinnedVerifier {
+        inner: Verifier::new_with_extra_roots(root_certificates, provider.clone()).map_err(
+            |error| tls_error(format!("unable to load the root certificates: {}", error)),
+        )?,
+        pins: pins.to_vec(),
+    };
+
+    Ok(ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|error| tls_error(error.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth())
+}
+
+/// Map an error of the TLS configuration to an error.
+fn tls_error(message: String) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        source: None,
+        kind: error::ErrorKind::InternalError(format!("TLS error: {}", message)),
+    }
+}
@@ -0,0 +1,313 @@
+use crate::m2web::{
+    ewon::{Ewon, EwonStatus},
+    watcher::StatusEvent,
+};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    time::{Duration, SystemTime},
+};
+
+/// Uptime statistics of an eWON over the window of an `UptimeTracker`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UptimeStats {
+    /// The UID of the eWON to the M2Web API.
+    pub id: u32,
+    /// The unique name of the eWON.
+    pub name: String,
+    /// The observed duration, which starts with the window or the first record of the eWON.
+    pub observed: Duration,
+    /// The percentage of the observed duration during which the eWON was online.
+    pub uptime_percentage: f64,
+    /// The number of times the eWON went from online to another status.
+    pub failures: u32,
+    /// The longest duration during which the eWON was not online, including the current outage.
+    pub longest_outage: Duration,
+    /// The mean time between failures, if the eWON failed at least once.
+    pub mtbf: Option<Duration>,
+}
+
+/// Status history of an eWON.
+struct History {
+    /// The unique name of the eWON.
+    name: String,
+    /// The successive statuses of the eWON: from each time, the eWON is online or not.
+    statuses: Vec<(SystemTime, bool)>,
+}
+
+/// Tracker of the uptime of the eWONs.
+///
+/// Record the status changes of the eWONs, like broadcast by a `StatusWatcher`, and compute uptime
+/// statistics over a rolling window. The status of each eWON is only known from its first record, so
+/// recording the statuses returned by `get_ewons()` before the first event gives more accurate statistics.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, uptime::UptimeTracker, watcher::StatusWatcher};
+/// # use std::time::{Duration, SystemTime};
+/// # #[tokio::test]
+/// # async fn track_uptime() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let mut tracker = UptimeTracker::new(Duration::from_secs(30 * 24 * 3600));
+/// let mut watcher = StatusWatcher::new(Duration::from_secs(60));
+///
+/// tracker.record_ewons(&client.get_ewons(None).await?, SystemTime::now());
+/// loop {
+///     for event in watcher.poll(&client).await? {
+///         tracker.record(&event);
+///     }
+///     println!("{}", tracker.to_csv(SystemTime::now()));
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct UptimeTracker {
+    /// The duration over which the statistics are computed.
+    window: Duration,
+    /// The status history of each eWON, by id.
+    histories: HashMap<u32, History>,
+}
+
+impl UptimeTracker {
+    /// Create a tracker computing the statistics over the given window.
+    pub fn new(window: Duration) -> Self {
+        UptimeTracker {
+            window,
+            histories: HashMap::new(),
+        }
+    }
+
+    /// Record a status change of an eWON.
+    pub fn record(&mut self, event: &StatusEvent) {
+        self.record_status(&event.ewon, &event.new, event.at);
+    }
+
+    /// Record the statuses of the eWONs at the given time.
+    pub fn record_ewons(&mut self, ewons: &[Ewon], at: SystemTime) {
+        ewons
+            .iter()
+            .for_each(|ewon| self.record_status(ewon, &ewon.status, at));
+    }
+
+    /// Record the status of an eWON at the given time, and forget what is older than the window.
+    fn record_status(&mut self, ewon: &Ewon, status: &EwonStatus, at: SystemTime) {
+        let history = self.histories.entry(ewon.id).or_insert_with(|| History {
+            name: ewon.name.to_owned(),
+            statuses: vec![],
+        });
+        history.name = ewon.name.to_owned();
+
+        let online = status == &EwonStatus::Online;
+        match history.statuses.last() {
+            Some((_, last_online)) if *last_online == online => {}
+            _ => history.statuses.push((at, online)),
+        }
+
+        // Keep the last status older than the window, which is the status at the start of the window.
+        if let Some(window_start) = at.checked_sub(self.window) {
+            let older = history
+                .statuses
+                .iter()
+                .take_while(|(since, _)| *since <= window_start)
+                .count();
+            if older > 1 {
+                history.statuses.drain(..older - 1);
+            }
+        }
+    }
+
+    /// Return the statistics of all eWONs over the window ending at the given time, sorted by id.
+    pub fn stats(&self, now: SystemTime) -> Vec<UptimeStats> {
+        let window_start = now
+            .checked_sub(self.window)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut stats = self
+            .histories
+            .iter()
+            .filter_map(|(id, history)| Self::history_stats(*id, history, window_start, now))
+            .collect::<Vec<UptimeStats>>();
+        stats.sort_by_key(|stats| stats.id);
+
+        stats
+    }
+
+    /// Return the statistics of all eWONs over the window ending at the given time, as CSV.
+    pub fn to_csv(&self, now: SystemTime) -> String {
+        let mut csv =
+            "id,name,observed_secs,uptime_percentage,failures,longest_outage_secs,mtbf_secs\n"
+                .to_string();
+        self.stats(now).iter().for_each(|stats| {
+            let _ = writeln!(
+                csv,
+                "{},\"{}\",{},{:.3},{},{},{}",
+                stats.id,
+                stats.name.replace('"', "\"\""),
+                stats.observed.as_secs(),
+                stats.uptime_percentage,
+                stats.failures,
+                stats.longest_outage.as_secs(),
+                stats
+                    .mtbf
+                    .map(|mtbf| mtbf.as_secs().to_string())
+                    .unwrap_or_default(),
+            );
+        });
+
+        csv
+    }
+
+    /// Compute the statistics of an eWON between both times.
+    fn history_stats(
+        id: u32,
+        history: &History,
+        window_start: SystemTime,
+        now: SystemTime,
+    ) -> Option<UptimeStats> {
+        let (first_since, _) = history.statuses.first()?;
+        let start = (*first_since).max(window_start);
+
+        let mut uptime = Duration::ZERO;
+        let mut failures = 0;
+        let mut outage = Duration::ZERO;
+        let mut longest_outage = Duration::ZERO;
+        let mut previous_online = None;
+
+        for (index, (since, online)) in history.statuses.iter().enumerate() {
+            let until = history
+                .statuses
+                .get(index + 1)
+                .map(|(until, _)| *until)
+                .unwrap_or(now);
+            if until <= start || *since >= now {
+                continue;
+            }
+            let duration = until
+                .min(now)
+                .duration_since((*since).max(start))
+                .unwrap_or_default();
+
+            if *online {
+                uptime += duration;
+                outage = Duration::ZERO;
+            } else {
+                if previous_online == Some(true) {
+                    failures += 1;
+                }
+                outage += duration;
+                longest_outage = longest_outage.max(outage);
+            }
+            previous_online = Some(*online);
+        }
+
+        let observed = now.duration_since(start).unwrap_or_default();
+        let uptime_percentage = match observed.is_zero() {
+            true => 0.0,
+            false => uptime.as_secs_f64() * 100.0 / observed.as_secs_f64(),
+        };
+
+        Some(UptimeStats {
+            id,
+            name: history.name.to_owned(),
+            observed,
+            uptime_percentage,
+            failures,
+            longest_outage,
+            mtbf: match failures {
+                0 => None,
+                _ => Some(uptime / failures),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        ewon::{Ewon, EwonStatus},
+        uptime::UptimeTracker,
+        watcher::StatusEvent,
+    };
+    use std::time::{Duration, SystemTime};
+
+    fn event(old: EwonStatus, new: EwonStatus, at: SystemTime) -> StatusEvent {
+        StatusEvent {
+            ewon: Ewon {
+                id: 1206698,
+                name: "bea-test".to_string(),
+                status: new.to_owned(),
+                ..Default::default()
+            },
+            old,
+            new,
+            at,
+        }
+    }
+
+    #[test]
+    fn uptime_tracker_stats_ok() {
+        let hour = Duration::from_secs(3600);
+        let start = SystemTime::UNIX_EPOCH + 1000 * hour;
+        let mut tracker = UptimeTracker::new(10 * hour);
+
+        tracker.record_ewons(
+            &[Ewon {
+                id: 1206698,
+                name: "bea-test".to_string(),
+                status: EwonStatus::Online,
+                ..Default::default()
+            }],
+            start,
+        );
+        tracker.record(&event(
+            EwonStatus::Online,
+            EwonStatus::Offline,
+            start + 2 * hour,
+        ));
+        tracker.record(&event(
+            EwonStatus::Offline,
+            EwonStatus::Online,
+            start + 3 * hour,
+        ));
+        tracker.record(&event(
+            EwonStatus::Online,
+            EwonStatus::Offline,
+            start + 6 * hour,
+        ));
+
+        let stats = tracker.stats(start + 8 * hour);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].observed, 8 * hour);
+        assert_eq!(stats[0].uptime_percentage, 62.5);
+        assert_eq!(stats[0].failures, 2);
+        assert_eq!(stats[0].longest_outage, 2 * hour);
+        assert_eq!(stats[0].mtbf, Some(hour * 5 / 2));
+
+        assert_eq!(
+            tracker.to_csv(start + 8 * hour),
+            "id,name,observed_secs,uptime_percentage,failures,longest_outage_secs,mtbf_secs\n\
+            1206698,\"bea-test\",28800,62.500,2,7200,9000\n"
+        );
+    }
+
+    #[test]
+    fn uptime_tracker_rolling_window_ok() {
+        let hour = Duration::from_secs(3600);
+        let start = SystemTime::UNIX_EPOCH + 1000 * hour;
+        let mut tracker = UptimeTracker::new(4 * hour);
+
+        tracker.record(&event(EwonStatus::Online, EwonStatus::Offline, start));
+        tracker.record(&event(
+            EwonStatus::Offline,
+            EwonStatus::Online,
+            start + 5 * hour,
+        ));
+
+        // The window starts 1 hour before the eWON is back online.
+        let stats = tracker.stats(start + 8 * hour);
+        assert_eq!(stats[0].observed, 4 * hour);
+        assert_eq!(stats[0].uptime_percentage, 75.0);
+        assert_eq!(stats[0].failures, 0);
+        assert_eq!(stats[0].longest_outage, hour);
+        assert_eq!(stats[0].mtbf, None);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::m2web::{error, service::HttpService};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::BoxFuture;
+use reqwest::{
+    header::CONTENT_TYPE, Client as HttpClient, Request, Response, ResponseBuilderExt, Url,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+use tower::{BoxError, Service};
+
+/// Parameters holding the credentials, scrubbed from the urls and the bodies recorded.
+const CREDENTIALS: [&str; 9] = [
+    "t2maccount",
+    "t2musername",
+    "t2mpassword",
+    "t2mdeveloperid",
+    "t2msession",
+    "t2mdeviceusername",
+    "t2mdevicepassword",
+    "t2mtoken",
+    "t2mdevid",
+];
+
+/// Value replacing the credentials recorded.
+const SCRUBBED: &str = "scrubbed";
+
+/// Exchange with the API, as stored in a fixture file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Interaction {
+    /// The method of the request.
+    method: String,
+    /// The path and the parameters of the url of the request, with the credentials scrubbed.
+    url: String,
+    /// The HTTP status of the response.
+    status: u16,
+    /// The content type of the response, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    /// The body of the response, with the credentials scrubbed, encoded in base64 unless it is text.
+    body: String,
+    /// Whether the body is encoded in base64.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    base64: bool,
+}
+
+/// Content of a fixture file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Cassette {
+    /// The interactions, in the order they were recorded.
+    interactions: Vec<Interaction>,
+}
+
+/// Transport recording the exchanges with the API to a fixture file, and replaying them.
+///
+/// The transport, available behind the `test-util` feature, allows to test the applications using this
+/// crate deterministically, without access to a Talk2M account. Set as the HTTP service of a client, a
+/// recording transport sends the requests with its HTTP client, and records them with their responses;
+/// `save()` then writes them to the fixture file, with the credentials scrubbed from the urls and from the
+/// JSON bodies, so that the file can be committed. A replaying transport answers each request with the
+/// first response recorded for the same method, path and parameters, and not replayed yet, whatever the
+/// url of the API and the credentials of the client; it fails the requests which were not recorded.
+/// The clones of a transport share its interactions.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, vcr::VcrTransport};
+/// # #[tokio::test]
+/// # async fn count_ewons() -> Result<(), error::Error> {
+/// // Once, with the credentials of a real account:
+/// let transport = VcrTransport::record("tests/fixtures/getewons.json", reqwest::Client::new());
+/// let client = ClientBuilder::default()
+///     .t2m_account("account")
+///     .t2m_username("username")
+///     .t2m_password("password")
+///     .t2m_developer_id("developer_id")
+///     .http_service(transport.clone().into())
+///     .build()
+///     .unwrap();
+/// client.get_ewons(None).await?;
+/// transport.save()?;
+///
+/// // Then in the tests:
+/// let client = ClientBuilder::default()
+///     .http_service(VcrTransport::replay("tests/fixtures/getewons.json")?.into())
+///     .build()
+///     .unwrap();
+/// assert_eq!(client.get_ewons(None).await?.len(), 3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct VcrTransport {
+    /// The path of the fixture file.
+    path: PathBuf,
+    /// The HTTP client sending the requests, when recording them.
+    http_client: Option<HttpClient>,
+    /// The interactions recorded, or left to replay.
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl VcrTransport {
+    /// Create a transport sending the requests with the HTTP client, and recording them for the fixture
+    /// file at this path.
+    pub fn record(path: impl Into<PathBuf>, http_client: HttpClient) -> Self {
+        VcrTransport {
+            path: path.into(),
+            http_client: Some(http_client),
+            cassette: Arc::new(Mutex::new(Cassette::default())),
+        }
+    }
+
+    /// Create a transport replaying the interactions of the fixture file at this path.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self, error::Error> {
+        let path = path.into();
+        let cassette = serde_json::from_slice(&fs::read(&path)?)?;
+
+        Ok(VcrTransport {
+            path,
+            http_client: None,
+            cassette: Arc::new(Mutex::new(cassette)),
+        })
+    }
+
+    /// Write the interactions recorded so far to the fixture file, replacing it. A replaying transport
+    /// leaves the file as is.
+    pub fn save(&self) -> Result<(), error::Error> {
+        if self.http_client.is_none() {
+            return Ok(());
+        }
+        let cassette = self.cassette.lock().unwrap_or_else(PoisonError::into_inner);
+        fs::write(&self.path, serde_json::to_vec_pretty(&*cassette)?)?;
+
+        Ok(())
+    }
+
+    /// Send and record the request, or replay its response.
+    async fn exchange(self, request: Request) -> Result<Response, BoxError> {
+        let method = request.method().to_string();
+        let url = request.url().to_owned();
+        let recorded_url = scrub_url(&url);
+
+        match self.http_client {
+            Some(ref http_client) => {
+                let http_response = http_client.execute(request).await?;
+                let status = http_response.status().as_u16();
+                let content_type = http_response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|content_type| content_type.to_str().ok())
+                    .map(str::to_owned);
+                let body = http_response.bytes().await?;
+
+                self.cassette
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .interactions
+                    .push(Interaction::new(
+                        method,
+                        recorded_url,
+                        status,
+                        content_type.as_deref(),
+                        &body,
+                    ));
+
+                // The client receives the response as sent, with its credentials.
+                Ok(response(
+                    url,
+                    status,
+                    content_type.as_deref(),
+                    body.to_vec(),
+                ))
+            }
+            None => {
+                let interaction = {
+                    let mut cassette = self.cassette.lock().unwrap_or_else(PoisonError::into_inner);
+                    let position = cassette
+                        .interactions
+                        .iter()
+                        .position(|interaction| {
+                            interaction.method == method && interaction.url == recorded_url
+                        })
+                        .ok_or_else(|| {
+                            format!(
+                                "No recorded interaction for {} {} in {}",
+                                method,
+                                recorded_url,
+                                self.path.display()
+                            )
+                        })?;
+                    cassette.interactions.remove(position)
+                };
+                let body = match interaction.base64 {
+                    true => STANDARD.decode(&interaction.body)?,
+                    false => interaction.body.into_bytes(),
+                };
+
+                Ok(response(
+                    url,
+                    interaction.status,
+                    interaction.content_type.as_deref(),
+                    body,
+                ))
+            }
+        }
+    }
+}
+
+impl Interaction {
+    /// Create the interaction of the response, with the credentials scrubbed from its body.
+    fn new(
+        method: String,
+        url: String,
+        status: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Self {
+        let (body, base64) = match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut json) => {
+                scrub_json(&mut json);
+                (json.to_string(), false)
+            }
+            Err(_) => match std::str::from_utf8(body) {
+                Ok(text) => (text.to_owned(), false),
+                Err(_) => (STANDARD.encode(body), true),
+            },
+        };
+
+        Interaction {
+            method,
+            url,
+            status,
+            content_type: content_type.map(str::to_owned),
+            body,
+            base64,
+        }
+    }
+}
+
+impl Service<Request> for VcrTransport {
+    type Response = Response;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Response, BoxError>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        Box::pin(self.clone().exchange(request))
+    }
+}
+
+impl From<VcrTransport> for HttpService {
+    fn from(transport: VcrTransport) -> Self {
+        HttpService::new(transport)
+    }
+}
+
+/// Return the path and the parameters of the url, with the credentials scrubbed.
+fn scrub_url(url: &Url) -> String {
+    let mut scrubbed = Url::parse("http://localhost").expect("valid url");
+    scrubbed.set_path(url.path());
+    if url.query().is_some() {
+        scrubbed
+            .query_pairs_mut()
+            .extend_pairs(url.query_pairs().map(|(name, value)| {
+                match CREDENTIALS.contains(&name.as_ref()) {
+                    true => (name, SCRUBBED.into()),
+                    false => (name, value),
+                }
+            }));
+    }
+
+    match scrubbed.query() {
+        Some(query) => format!("{}?{}", scrubbed.path(), query),
+        None => scrubbed.path().to_owned(),
+    }
+}
+
+/// Scrub the credentials from the fields of the JSON value.
+fn scrub_json(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(fields) => {
+            fields
+                .iter_mut()
+                .for_each(|(name, value)| match CREDENTIALS.contains(&name.as_str()) {
+                    true => *value = SCRUBBED.into(),
+                    false => scrub_json(value),
+                })
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(scrub_json),
+        _ => {}
+    }
+}
+
+/// Build the response of the client.
+fn response(url: Url, status: u16, content_type: Option<&str>, body: Vec<u8>) -> Response {
+    let mut http_response = http::Response::builder().status(status).url(url);
+    if let Some(content_type) = content_type {
+        http_response = http_response.header(CONTENT_TYPE, content_type);
+    }
+
+    http_response.body(body).unwrap_or_default().into()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::vcr::{scrub_json, scrub_url};
+    use reqwest::Url;
+    use serde_json::json;
+
+    #[test]
+    fn scrub_ok() {
+        let url = Url::parse(
+            "https://m2web.talk2m.com/t2mapi/getewon?t2maccount=account&t2mpassword=p%40ss&name=bea-test",
+        )
+        .unwrap();
+        assert_eq!(
+            scrub_url(&url),
+            "/t2mapi/getewon?t2maccount=scrubbed&t2mpassword=scrubbed&name=bea-test"
+        );
+
+        let mut json =
+            json!({"success": true, "t2msession": "2b4a9", "ewons": [{"t2mtoken": "x"}]});
+        scrub_json(&mut json);
+        assert_eq!(
+            json,
+            json!({"success": true, "t2msession": "scrubbed", "ewons": [{"t2mtoken": "scrubbed"}]})
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use crate::m2web::{
+    client::Client,
+    clock,
+    diff::EwonDiff,
+    error,
+    ewon::{Ewon, EwonStatus},
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+use tokio::sync::broadcast;
+
+/// Number of events kept for the subscribers which lag behind.
+const EVENTS_CAPACITY: usize = 256;
+
+/// Hook called with the error of each failed poll.
+type ErrorHook = Box<dyn FnMut(&error::Error) + Send>;
+
+/// Change of the status of an eWON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusEvent {
+    /// The eWON, as returned by the poll which detected the change.
+    pub ewon: Ewon,
+    /// The status of the eWON before the change.
+    pub old: EwonStatus,
+    /// The status of the eWON after the change.
+    pub new: EwonStatus,
+    /// The time at which the change has been detected.
+    pub at: SystemTime,
+}
+
+/// Watcher of the status of the eWONs.
+///
+/// Poll the eWONs at a regular interval and broadcast a `StatusEvent` each time the status of an eWON
+/// changes. The first poll only records the status of the eWONs; the eWONs added or removed between
+/// two polls do not trigger any event. A failed poll, for example on a timeout of the API, does not stop the
+/// watcher, which polls again at the next interval.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, watcher::StatusWatcher};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn watch_ewons() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let mut watcher = StatusWatcher::new(Duration::from_secs(60));
+/// let mut events = watcher.subscribe();
+///
+/// tokio::spawn(async move {
+///     while let Ok(event) = events.recv().await {
+///         println!("{}: {} -> {}", event.ewon.name, event.old, event.new);
+///     }
+/// });
+/// watcher.run(&client).await;
+/// # }
+/// ```
+pub struct StatusWatcher {
+    /// The interval between two polls.
+    interval: Duration,
+    /// The pool the watched eWONs belong to.
+    pool: Option<String>,
+    /// The eWONs returned by the last poll.
+    ewons: Option<Vec<Ewon>>,
+    /// The channel used to broadcast the events.
+    sender: broadcast::Sender<StatusEvent>,
+    /// The hook called with the error of each failed poll, if any.
+    on_error: Option<ErrorHook>,
+}
+
+impl StatusWatcher {
+    /// Create a watcher polling all the eWONs at the given interval.
+    pub fn new(interval: Duration) -> Self {
+        let (sender, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        StatusWatcher {
+            interval,
+            pool: None,
+            ewons: None,
+            sender,
+            on_error: None,
+        }
+    }
+
+    /// Only watch the eWONs belonging to this pool.
+    pub fn pool(mut self, pool: &str) -> Self {
+        self.pool = Some(pool.to_owned());
+        self
+    }
+
+    /// Call the hook with the error of each poll failing while the watcher runs.
+    pub fn on_error(mut self, on_error: impl FnMut(&error::Error) + Send + 'static) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Return a receiver of the events broadcast by the watcher.
+    pub fn subscribe(&self) -> broadcast::Receiver<StatusEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Poll the eWONs once, broadcast and return the status changes since the last poll.
+    pub async fn poll(&mut self, client: &Client<'_>) -> Result<Vec<StatusEvent>, error::Error> {
+        let ewons = client.refresh_ewons(self.pool.as_deref()).await?;
+        let at = clock::now();
+
+        let events = match self.ewons {
+            Some(ref last_ewons) => EwonDiff::between(last_ewons, &ewons)
+                .changed
+                .into_iter()
+                .filter(|change| change.status_changed())
+                .map(|change| StatusEvent {
+                    old: change.old.status,
+                    new: change.new.status.to_owned(),
+                    ewon: change.new,
+                    at,
+                })
+                .collect(),
+            None => vec![],
+        };
+        self.ewons = Some(ewons);
+
+        // An error only means there is no subscriber for now.
+        events.iter().for_each(|event| {
+            let _ = self.sender.send(event.to_owned());
+        });
+
+        Ok(events)
+    }
+
+    /// Poll the eWONs at the watcher interval, forever.
+    ///
+    /// The failed polls do not stop the watcher, they are only reported to the error hook, and traced
+    /// behind the `tracing` feature. The watcher is stopped by dropping the returned future; the statuses
+    /// recorded so far are kept, so the watcher could be run again to resume the watch.
+    pub async fn run(&mut self, client: &Client<'_>) {
+        let mut interval = clock::interval(self.interval);
+        interval.set_missed_tick_behavior(clock::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.poll(client).await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %err, "poll of the eWON statuses failed");
+                if let Some(ref mut on_error) = self.on_error {
+                    on_error(&err);
+                }
+            }
+        }
+    }
+}
+
+/// Receiver of the status changes of the eWONs.
+///
+/// The trait is implemented for any closure taking a `&StatusEvent`.
+pub trait StatusNotifier {
+    /// Called each time the status of an eWON changes.
+    fn notify(&mut self, event: &StatusEvent);
+}
+
+impl<F> StatusNotifier for F
+where
+    F: FnMut(&StatusEvent),
+{
+    fn notify(&mut self, event: &StatusEvent) {
+        self(event)
+    }
+}
+
+/// Status of an eWON tracked by a `FlapNotifier`.
+struct TrackedStatus {
+    /// The last status notified, or observed by the first poll.
+    confirmed: EwonStatus,
+    /// The status observed by the last poll, if it differs from the confirmed one.
+    candidate: Option<EwonStatus>,
+    /// The number of successive polls which observed the candidate status.
+    polls: u32,
+}
+
+/// Notifier of the status changes of the eWONs, with flap suppression.
+///
+/// Poll the eWONs at a regular interval and call the `StatusNotifier` when the status of an eWON changes.
+/// To avoid the notifications of eWONs flapping between online and offline, for example on a weak cellular
+/// network, a new status must be observed by a number of successive polls before being notified. By default,
+/// a new status is notified as soon as it is observed. A failed poll does not stop the notifier, which polls
+/// again at the next interval.
+///
+/// # Example
+/// ```rust
+/// # use libewon::m2web::{client::ClientBuilder, error, watcher::{FlapNotifier, StatusEvent}};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn notify_offline_ewons() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+///
+/// // Only notify the eWONs offline for 3 successive polls.
+/// let mut notifier = FlapNotifier::new(|event: &StatusEvent| {
+///     println!("{} is now {}", event.ewon.name, event.new)
+/// })
+/// .offline_after(3);
+/// notifier.run(&client, Duration::from_secs(60)).await;
+/// # }
+/// ```
+pub struct FlapNotifier<N: StatusNotifier> {
+    /// The receiver of the status changes.
+    notifier: N,
+    /// The pool the watched eWONs belong to.
+    pool: Option<String>,
+    /// The number of successive polls required to notify an online eWON.
+    online_polls: u32,
+    /// The number of successive polls required to notify an offline eWON, or an eWON with another status.
+    offline_polls: u32,
+    /// The status tracked for each eWON, by id.
+    statuses: HashMap<u32, TrackedStatus>,
+    /// The hook called with the error of each failed poll, if any.
+    on_error: Option<ErrorHook>,
+}
+
+impl<N: StatusNotifier> FlapNotifier<N> {
+    /// Create a notifier calling the `StatusNotifier` as soon as a status change is observed.
+    pub fn new(notifier: N) -> Self {
+        FlapNotifier {
+            notifier,
+            pool: None,
+            online_polls: 1,
+            offline_polls: 1,
+            statuses: HashMap::new(),
+            on_error: None,
+        }
+    }
+
+    /// Only watch the eWONs belonging to this pool.
+    pub fn pool(mut self, pool: &str) -> Self {
+        self.pool = Some(pool.to_owned());
+        self
+    }
+
+    /// Only notify an eWON back online once observed online by this number of successive polls.
+    pub fn online_after(mut self, polls: u32) -> Self {
+        self.online_polls = polls.max(1);
+        self
+    }
+
+    /// Only notify an offline eWON once observed offline by this number of successive polls.
+    pub fn offline_after(mut self, polls: u32) -> Self {
+        self.offline_polls = polls.max(1);
+        self
+    }
+
+    /// Call the hook with the error of each poll failing while the notifier runs.
+    pub fn on_error(mut self, on_error: impl FnMut(&error::Error) + Send + 'static) -> Self {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Record the statuses of the eWONs, notify and return the confirmed status changes.
+    pub fn observe(&mut self, ewons: &[Ewon]) -> Vec<StatusEvent> {
+        let at = clock::now();
+        let mut events = vec![];
+
+        for ewon in ewons {
+            let tracked = match self.statuses.get_mut(&ewon.id) {
+                Some(tracked) => tracked,
+                None => {
+                    self.statuses.insert(
+                        ewon.id,
+                        TrackedStatus {
+                            confirmed: ewon.status.to_owned(),
+                            candidate: None,
+                            polls: 0,
+                        },
+                    );
+                    continue;
+                }
+            };
+
+            if ewon.status == tracked.confirmed {
+                tracked.candidate = None;
+                tracked.polls = 0;
+                continue;
+            }
+            if tracked.candidate.as_ref() != Some(&ewon.status) {
+                tracked.candidate = Some(ewon.status.to_owned());
+                tracked.polls = 0;
+            }
+            tracked.polls += 1;
+
+            let required_polls = match ewon.status {
+                EwonStatus::Online => self.online_polls,
+                _ => self.offline_polls,
+            };
+            if tracked.polls >= required_polls {
+                events.push(StatusEvent {
+                    ewon: ewon.to_owned(),
+                    old: std::mem::replace(&mut tracked.confirmed, ewon.status.to_owned()),
+                    new: ewon.status.to_owned(),
+                    at,
+                });
+                tracked.candidate = None;
+                tracked.polls = 0;
+            }
+        }
+
+        events.iter().for_each(|event| self.notifier.notify(event));
+
+        events
+    }
+
+    /// Poll the eWONs at the given interval, forever.
+    ///
+    /// The failed polls do not stop the notifier, they are only reported to the error hook, and traced
+    /// behind the `tracing` feature. They do not count as polls observing a status. The notifier is stopped
+    /// by dropping the returned future; the statuses recorded so far are kept, so the notifier could be run
+    /// again to resume the watch.
+    pub async fn run(&mut self, client: &Client<'_>, interval: Duration) {
+        let mut interval = clock::interval(interval);
+        interval.set_missed_tick_behavior(clock::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match client.refresh_ewons(self.pool.as_deref()).await {
+                Ok(ewons) => {
+                    self.observe(&ewons);
+                }
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %err, "poll of the eWON statuses failed");
+                    if let Some(ref mut on_error) = self.on_error {
+                        on_error(&err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::m2web::{
+        ewon::{Ewon, EwonStatus},
+        watcher::{FlapNotifier, StatusEvent},
+    };
+
+    fn ewons(status: EwonStatus) -> Vec<Ewon> {
+        vec![Ewon {
+            id: 1206698,
+            name: "bea-test".to_string(),
+            status,
+            ..Default::default()
+        }]
+    }
+
+    #[test]
+    fn flap_notifier_default_ok() {
+        let mut notified = vec![];
+        let mut notifier =
+            FlapNotifier::new(|event: &StatusEvent| notified.push(event.new.to_owned()));
+
+        assert!(notifier.observe(&ewons(EwonStatus::Online)).is_empty());
+        assert_eq!(notifier.observe(&ewons(EwonStatus::Offline)).len(), 1);
+        assert_eq!(notifier.observe(&ewons(EwonStatus::Online)).len(), 1);
+        drop(notifier);
+
+        assert_eq!(notified, vec![EwonStatus::Offline, EwonStatus::Online]);
+    }
+
+    #[test]
+    fn flap_notifier_suppressed_ok() {
+        let mut notified = vec![];
+        let mut notifier =
+            FlapNotifier::new(|event: &StatusEvent| notified.push(event.new.to_owned()))
+                .offline_after(3);
+
+        // The eWON flaps, nothing is notified.
+        assert!(notifier.observe(&ewons(EwonStatus::Online)).is_empty());
+        assert!(notifier.observe(&ewons(EwonStatus::Offline)).is_empty());
+        assert!(notifier.observe(&ewons(EwonStatus::Offline)).is_empty());
+        assert!(notifier.observe(&ewons(EwonStatus::Online)).is_empty());
+        assert!(notifier.observe(&ewons(EwonStatus::Offline)).is_empty());
+        assert!(notifier.observe(&ewons(EwonStatus::Offline)).is_empty());
+
+        // The eWON is offline for the third successive poll.
+        let events = notifier.observe(&ewons(EwonStatus::Offline));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].old, EwonStatus::Online);
+        assert_eq!(events[0].new, EwonStatus::Offline);
+
+        // The eWON is back online, which is notified immediately.
+        assert_eq!(notifier.observe(&ewons(EwonStatus::Online)).len(), 1);
+        drop(notifier);
+
+        assert_eq!(notified, vec![EwonStatus::Offline, EwonStatus::Online]);
+    }
+}
@@ -0,0 +1,249 @@
+//! Bridge republishing the status changes and the tag values of the eWONs to an MQTT broker.
+
+use crate::{
+    dmweb::{
+        data::{DataPoint, DataRecord},
+        sink::AsyncDataSink,
+    },
+    m2web::{error, tag, watcher::StatusEvent},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rumqttc::{AsyncClient, ClientError, QoS};
+use serde_json::json;
+use tokio::sync::broadcast;
+
+/// The default topic of the status changes.
+pub const DEFAULT_STATUS_TOPIC: &str = "ewon/{ewon}/status";
+/// The default topic of the tag values.
+pub const DEFAULT_VALUE_TOPIC: &str = "ewon/{ewon}/tags/{tag}";
+
+/// Bridge publishing the status changes and the tag values of the eWONs to an MQTT broker.
+///
+/// The topics are rendered from templates, where `{ewon}` is replaced by the name of the eWON and `{tag}`
+/// by the name of the tag. The characters reserved by MQTT, `/`, `+` and `#`, are replaced by `_` in the
+/// names. The payloads are JSON objects:
+/// - a status change holds the `ewon` name, its `id`, its `old` and `new` status and the time it has been
+///   detected `at`;
+/// - a tag value holds its `date`, its `value` and its `quality`, as a `DataPoint`.
+///
+/// The bridge publishes through a `rumqttc` client, whose event loop must be polled to send the messages
+/// to the broker.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{m2web::{client::ClientBuilder, error, watcher::StatusWatcher}, mqtt::MqttBridge};
+/// # use rumqttc::{AsyncClient, MqttOptions, QoS};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn bridge_ewons_to_mqtt() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let (mqtt_client, mut event_loop) =
+///     AsyncClient::new(MqttOptions::new("libewon", "localhost", 1883), 64);
+/// tokio::spawn(async move { while event_loop.poll().await.is_ok() {} });
+///
+/// let bridge = MqttBridge::new(mqtt_client)
+///     .status_topic("talk2m/{ewon}/status")
+///     .qos(QoS::AtLeastOnce)
+///     .retain(true);
+/// let mut watcher = StatusWatcher::new(Duration::from_secs(60));
+/// let events = watcher.subscribe();
+///
+/// tokio::spawn(async move { bridge.run(events).await });
+/// watcher.run(&client).await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MqttBridge {
+    /// The client of the broker.
+    client: AsyncClient,
+    /// The template of the topic of the status changes.
+    status_topic: String,
+    /// The template of the topic of the tag values.
+    value_topic: String,
+    /// The quality of service of the messages.
+    qos: QoS,
+    /// Indicates if the broker retains the last message of each topic.
+    retain: bool,
+}
+
+impl MqttBridge {
+    /// Create a bridge publishing to the default topics, at least once, without retaining the messages.
+    pub fn new(client: AsyncClient) -> Self {
+        MqttBridge {
+            client,
+            status_topic: DEFAULT_STATUS_TOPIC.to_string(),
+            value_topic: DEFAULT_VALUE_TOPIC.to_string(),
+            qos: QoS::AtLeastOnce,
+            retain: false,
+        }
+    }
+
+    /// Publish the status changes to this topic template.
+    pub fn status_topic(mut self, template: &str) -> Self {
+        self.status_topic = template.to_owned();
+        self
+    }
+
+    /// Publish the tag values to this topic template.
+    pub fn value_topic(mut self, template: &str) -> Self {
+        self.value_topic = template.to_owned();
+        self
+    }
+
+    /// Publish the messages with this quality of service.
+    pub fn qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Ask the broker to retain the last message of each topic, for the clients subscribing later.
+    pub fn retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Publish a status change.
+    pub async fn publish_status(&self, event: &StatusEvent) -> Result<(), error::Error> {
+        let payload = json!({
+            "ewon": event.ewon.name,
+            "id": event.ewon.id,
+            "old": event.old.to_string(),
+            "new": event.new.to_string(),
+            "at": DateTime::<Utc>::from(event.at).to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        });
+
+        self.publish(
+            render_topic(&self.status_topic, &event.ewon.name, ""),
+            &payload,
+        )
+        .await
+    }
+
+    /// Publish the instant values of the tags of an eWON, read through the M2Web API.
+    ///
+    /// The values are converted as data points, see `DataPoint::from()`.
+    pub async fn publish_tags(
+        &self,
+        ewon: &str,
+        tags: &[tag::TagValue],
+    ) -> Result<(), error::Error> {
+        for tag in tags {
+            self.publish(
+                render_topic(&self.value_topic, ewon, &tag.name),
+                &DataPoint::from(tag),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Publish the status changes received from a watcher, until it is dropped.
+    ///
+    /// The status changes missed by lagging behind the watcher are skipped. Return as soon as a status
+    /// change cannot be published.
+    pub async fn run(
+        &self,
+        mut events: broadcast::Receiver<StatusEvent>,
+    ) -> Result<(), error::Error> {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.publish_status(&event).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Publish a JSON payload to the topic.
+    async fn publish(
+        &self,
+        topic: String,
+        payload: &impl serde::Serialize,
+    ) -> Result<(), error::Error> {
+        self.client
+            .publish(topic, self.qos, self.retain, serde_json::to_vec(payload)?)
+            .await
+            .map_err(mqtt_error)
+    }
+}
+
+/// Publish the values synchronized from the Data Mailbox.
+impl AsyncDataSink for MqttBridge {
+    async fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        for record in records {
+            self.publish(
+                render_topic(&self.value_topic, &record.ewon_name, &record.tag_name),
+                &record.data_point,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), error::Error> {
+        Ok(())
+    }
+}
+
+/// Render the topic template with the names of the eWON and of the tag.
+fn render_topic(template: &str, ewon: &str, tag: &str) -> String {
+    let topic_level = |name: &str| name.replace(['/', '+', '#'], "_");
+
+    template
+        .replace("{ewon}", &topic_level(ewon))
+        .replace("{tag}", &topic_level(tag))
+}
+
+/// Map an error of the MQTT client to an internal error.
+fn mqtt_error(error: ClientError) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("MQTT error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dmweb::data::{DataPoint, TagValue},
+        m2web::tag,
+        mqtt::{render_topic, DEFAULT_STATUS_TOPIC, DEFAULT_VALUE_TOPIC},
+    };
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn render_topic_ok() {
+        assert_eq!(
+            render_topic(DEFAULT_STATUS_TOPIC, "bea-test", ""),
+            "ewon/bea-test/status"
+        );
+        assert_eq!(
+            render_topic(DEFAULT_VALUE_TOPIC, "bea/test", "Temperature#1"),
+            "ewon/bea_test/tags/Temperature_1"
+        );
+    }
+
+    #[test]
+    fn tag_payload_ok() {
+        let tag = tag::TagValue {
+            id: 1,
+            name: "Temperature".to_string(),
+            value: tag::TagData::Number(21.5),
+            alarm_status: 0,
+            quality: 0x40,
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+        };
+
+        let data_point = DataPoint::from(&tag);
+
+        assert_eq!(data_point.value, TagValue::Float(21.5));
+        assert_eq!(
+            serde_json::to_string(&data_point).unwrap(),
+            "{\"date\":\"2023-11-14T22:13:20Z\",\"value\":21.5,\"quality\":\"uncertain\"}"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Exporter of the status of the eWONs and of their tag values, in the Prometheus exposition format.
+
+use crate::{
+    dmweb::{data::DataRecord, sink::DataSink},
+    m2web::{
+        error,
+        ewon::{Ewon, EwonStatus},
+        tag,
+        watcher::StatusEvent,
+    },
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{Arc, PoisonError, RwLock},
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::broadcast,
+};
+
+/// The delay given to a scraper to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Metrics of an eWON.
+#[derive(Debug, Default)]
+struct EwonMetrics {
+    /// Indicates if the eWON is online, once its status is known.
+    up: Option<bool>,
+    /// The last time the eWON has been seen online, or has pushed a value.
+    last_seen: Option<SystemTime>,
+    /// The latest value of each numeric tag, by name.
+    tags: BTreeMap<String, f64>,
+}
+
+/// Metrics of the eWONs, by name, and the tags to export.
+#[derive(Debug, Default)]
+struct Metrics {
+    /// The metrics of each eWON, by name.
+    ewons: BTreeMap<String, EwonMetrics>,
+    /// The names of the tags to export, all the tags if none.
+    selected_tags: Option<HashSet<String>>,
+}
+
+impl Metrics {
+    /// Return the metrics of the eWON, created if unknown.
+    fn ewon(&mut self, ewon: &str) -> &mut EwonMetrics {
+        self.ewons.entry(ewon.to_owned()).or_default()
+    }
+
+    /// Record the value of the tag of the eWON, unless the tag is not selected or the value is a string.
+    fn record_tag(&mut self, ewon: &str, tag: &str, value: Option<f64>) {
+        let selected = match self.selected_tags {
+            Some(ref selected_tags) => selected_tags.contains(tag),
+            None => true,
+        };
+        if let (true, Some(value)) = (selected, value) {
+            self.ewon(ewon).tags.insert(tag.to_owned(), value);
+        }
+    }
+}
+
+/// Gauges of the status of the eWONs and of their tag values, exported to Prometheus.
+///
+/// The gauges are fed by the status watchers, the tag reads and the Data Mailbox synchronizations, and
+/// rendered in the Prometheus text exposition format:
+/// - `ewon_up` is 1 if the eWON is online, 0 otherwise;
+/// - `ewon_last_seen_timestamp_seconds` is the last time the eWON has been seen online or has pushed a
+///   value, in seconds since the Unix epoch;
+/// - `ewon_tag_value` is the latest value of a tag, the booleans being 0 or 1. The string tags are not
+///   exported.
+///
+/// The clones of the metrics share their gauges, which lets a task serve them while others feed them.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{m2web::{client::ClientBuilder, error, watcher::StatusWatcher}, prometheus::FleetMetrics};
+/// # use std::time::Duration;
+/// # use tokio::net::TcpListener;
+/// # #[tokio::test]
+/// # async fn export_ewons_to_prometheus() -> Result<(), error::Error> {
+/// let client = ClientBuilder::default().build()?;
+/// let metrics = FleetMetrics::new().tags(&["Temperature", "Pressure"]);
+/// let mut watcher = StatusWatcher::new(Duration::from_secs(60));
+///
+/// let events = watcher.subscribe();
+/// let fed_metrics = metrics.clone();
+/// tokio::spawn(async move { fed_metrics.run(events).await });
+/// let listener = TcpListener::bind("0.0.0.0:9898").await?;
+/// tokio::spawn(async move { metrics.serve(listener).await });
+/// watcher.run(&client).await;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FleetMetrics {
+    /// The metrics, shared by the clones.
+    metrics: Arc<RwLock<Metrics>>,
+}
+
+impl FleetMetrics {
+    /// Create empty metrics, exporting all the numeric tags.
+    pub fn new() -> Self {
+        FleetMetrics::default()
+    }
+
+    /// Only export the values of the tags with these names.
+    pub fn tags(self, tags: &[&str]) -> Self {
+        self.write().selected_tags = Some(tags.iter().map(|tag| tag.to_string()).collect());
+        self
+    }
+
+    /// Record the status of the eWONs, as returned by the M2Web API.
+    pub fn observe_ewons(&self, ewons: &[Ewon]) {
+        let now = SystemTime::now();
+        let mut metrics = self.write();
+        for ewon in ewons {
+            let ewon_metrics = metrics.ewon(&ewon.name);
+            ewon_metrics.up = Some(ewon.status == EwonStatus::Online);
+            if ewon.status == EwonStatus::Online {
+                ewon_metrics.last_seen = Some(now);
+            }
+        }
+    }
+
+    /// Record a status change, as detected by a watcher.
+    pub fn observe_status(&self, event: &StatusEvent) {
+        let mut metrics = self.write();
+        let ewon_metrics = metrics.ewon(&event.ewon.name);
+        ewon_metrics.up = Some(event.new == EwonStatus::Online);
+        if event.new == EwonStatus::Online {
+            ewon_metrics.last_seen = Some(event.at);
+        }
+    }
+
+    /// Record the instant values of the tags of an eWON, read through the M2Web API.
+    pub fn observe_tags(&self, ewon: &str, tags: &[tag::TagValue]) {
+        let mut metrics = self.write();
+        for tag in tags {
+            let value = match tag.value {
+                tag::TagData::Number(number) => Some(number),
+                tag::TagData::Text(_) => None,
+            };
+            metrics.record_tag(ewon, &tag.name, value);
+        }
+    }
+
+    /// Record the values synchronized from the Data Mailbox.
+    ///
+    /// The values are expected in chronological order, the last value of a tag being kept.
+    pub fn observe_records(&self, records: &[DataRecord]) {
+        let mut metrics = self.write();
+        for record in records {
+            let pushed_at = SystemTime::from(record.data_point.ts);
+            let ewon_metrics = metrics.ewon(&record.ewon_name);
+            if ewon_metrics.last_seen < Some(pushed_at) {
+                ewon_metrics.last_seen = Some(pushed_at);
+            }
+            metrics.record_tag(
+                &record.ewon_name,
+                &record.tag_name,
+                record.data_point.value.as_f64(),
+            );
+        }
+    }
+
+    /// Record the status changes received from a watcher, until it is dropped.
+    ///
+    /// The status changes missed by lagging behind the watcher are skipped.
+    pub async fn run(&self, mut events: broadcast::Receiver<StatusEvent>) {
+        loop {
+            match events.recv().await {
+                Ok(event) => self.observe_status(&event),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Render the gauges in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let metrics = self.metrics.read().unwrap_or_else(PoisonError::into_inner);
+        let mut text = String::new();
+
+        text.push_str("# HELP ewon_up Whether the eWON is online (1) or not (0).\n");
+        text.push_str("# TYPE ewon_up gauge\n");
+        for (ewon, ewon_metrics) in &metrics.ewons {
+            if let Some(up) = ewon_metrics.up {
+                text.push_str(&format!(
+                    "ewon_up{{ewon=\"{}\"}} {}\n",
+                    label(ewon),
+                    u8::from(up)
+                ));
+            }
+        }
+        text.push_str(
+            "# HELP ewon_last_seen_timestamp_seconds Last time the eWON has been seen online or has pushed a value.\n",
+        );
+        text.push_str("# TYPE ewon_last_seen_timestamp_seconds gauge\n");
+        for (ewon, ewon_metrics) in &metrics.ewons {
+            if let Some(last_seen) = ewon_metrics.last_seen {
+                text.push_str(&format!(
+                    "ewon_last_seen_timestamp_seconds{{ewon=\"{}\"}} {}\n",
+                    label(ewon),
+                    last_seen
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs_f64()
+                ));
+            }
+        }
+        text.push_str("# HELP ewon_tag_value Latest value of the tag.\n");
+        text.push_str("# TYPE ewon_tag_value gauge\n");
+        for (ewon, ewon_metrics) in &metrics.ewons {
+            for (tag, value) in &ewon_metrics.tags {
+                text.push_str(&format!(
+                    "ewon_tag_value{{ewon=\"{}\",tag=\"{}\"}} {}\n",
+                    label(ewon),
+                    label(tag),
+                    sample(*value)
+                ));
+            }
+        }
+
+        text
+    }
+
+    /// Serve the gauges to the scrapers connecting to the listener, forever.
+    ///
+    /// Any request is answered with the rendered gauges, whatever its path. The connections are served one
+    /// at a time, each scraper being given 5 seconds to send its request.
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), error::Error> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            // A scraper failing to read the gauges does not stop the exporter.
+            let _ = self.answer(stream).await;
+        }
+    }
+
+    /// Answer a scraper with the rendered gauges.
+    async fn answer(&self, mut stream: TcpStream) -> Result<(), error::Error> {
+        let mut request = vec![];
+        let mut buffer = [0; 1024];
+        while !request.windows(4).any(|end| end == b"\r\n\r\n") {
+            let read = match tokio::time::timeout(REQUEST_TIMEOUT, stream.read(&mut buffer)).await {
+                Ok(read) => read?,
+                Err(_) => return Ok(()),
+            };
+            if read == 0 {
+                return Ok(());
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+
+        let body = self.render();
+        stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+                    Content-Length: {}\r\n\
+                    Connection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await?;
+        stream.shutdown().await?;
+
+        Ok(())
+    }
+
+    /// Lock the metrics to update them.
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Metrics> {
+        self.metrics.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl DataSink for FleetMetrics {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        self.observe_records(records);
+
+        Ok(())
+    }
+}
+
+/// Escape the backslashes, the quotes and the line breaks of a label value.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format a sample value, the infinite values being `+Inf` and `-Inf`.
+fn sample(value: f64) -> String {
+    match value {
+        f64::INFINITY => "+Inf".to_string(),
+        f64::NEG_INFINITY => "-Inf".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        m2web::{
+            ewon::{Ewon, EwonStatus},
+            tag,
+        },
+        prometheus::FleetMetrics,
+    };
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn render_metrics_ok() {
+        let metrics = FleetMetrics::new().tags(&["Temperature", "Recipe"]);
+        let tag = |name: &str, value| tag::TagValue {
+            id: 1,
+            name: name.to_string(),
+            value,
+            alarm_status: 0,
+            quality: 192,
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+        };
+
+        metrics.observe_ewons(&[Ewon {
+            id: 1206698,
+            name: "bea \"test\"".to_string(),
+            status: EwonStatus::Offline,
+            ..Default::default()
+        }]);
+        metrics.observe_tags(
+            "bea \"test\"",
+            &[
+                tag("Temperature", tag::TagData::Number(21.5)),
+                tag("Pressure", tag::TagData::Number(1.2)),
+                tag("Recipe", tag::TagData::Text("PET-500".to_string())),
+            ],
+        );
+
+        assert_eq!(
+            metrics.render(),
+            "# HELP ewon_up Whether the eWON is online (1) or not (0).\n\
+            # TYPE ewon_up gauge\n\
+            ewon_up{ewon=\"bea \\\"test\\\"\"} 0\n\
+            # HELP ewon_last_seen_timestamp_seconds Last time the eWON has been seen online or has pushed a value.\n\
+            # TYPE ewon_last_seen_timestamp_seconds gauge\n\
+            # HELP ewon_tag_value Latest value of the tag.\n\
+            # TYPE ewon_tag_value gauge\n\
+            ewon_tag_value{ewon=\"bea \\\"test\\\"\",tag=\"Temperature\"} 21.5\n"
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! Processing of the series of values of the tags, fetched from the Data Mailbox or exported by the eWONs.
+
+use crate::{
+    dmweb::data::{DataRecord, TagValue},
+    m2web::{history::LogRecord, tag::TagData},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+/// Value of a series of values of a tag.
+///
+/// The trait is implemented by the `DataRecord`s, synchronized from the Data Mailbox, and by the
+/// `LogRecord`s, exported by an eWON.
+pub trait SeriesValue: Clone {
+    /// The key identifying the series the value belongs to.
+    type Key: Clone + Eq + Hash;
+
+    /// Return the key of the series the value belongs to.
+    fn series_key(&self) -> Self::Key;
+
+    /// Return the time at which the value has been logged.
+    fn time(&self) -> DateTime<Utc>;
+
+    /// Return the value as a number, unless it is a string.
+    fn number(&self) -> Option<f64>;
+
+    /// Return a copy of the value, logged at this time, with this number as value if any.
+    fn resampled(&self, time: DateTime<Utc>, number: Option<f64>) -> Self;
+}
+
+/// The values synchronized from the Data Mailbox belong to a series by eWON and tag ids.
+impl SeriesValue for DataRecord {
+    type Key = (u32, u32);
+
+    fn series_key(&self) -> Self::Key {
+        (self.ewon_id, self.tag_id)
+    }
+
+    fn time(&self) -> DateTime<Utc> {
+        self.data_point.ts
+    }
+
+    fn number(&self) -> Option<f64> {
+        self.data_point.value.as_f64()
+    }
+
+    fn resampled(&self, time: DateTime<Utc>, number: Option<f64>) -> Self {
+        let mut record = self.to_owned();
+        record.data_point.ts = time;
+        if let Some(number) = number {
+            record.data_point.value = TagValue::Float(number);
+        }
+
+        record
+    }
+}
+
+/// The values exported by an eWON belong to a series by tag id.
+impl SeriesValue for LogRecord {
+    type Key = u32;
+
+    fn series_key(&self) -> Self::Key {
+        self.tag_id
+    }
+
+    fn time(&self) -> DateTime<Utc> {
+        self.timestamp.into()
+    }
+
+    fn number(&self) -> Option<f64> {
+        match self.value {
+            TagData::Number(number) => Some(number),
+            TagData::Text(_) => None,
+        }
+    }
+
+    fn resampled(&self, time: DateTime<Utc>, number: Option<f64>) -> Self {
+        let mut record = self.to_owned();
+        record.timestamp = time.into();
+        record.initial = false;
+        if let Some(number) = number {
+            record.value = TagData::Number(number);
+        }
+
+        record
+    }
+}
+
+/// Aggregation of the values of an interval.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Aggregation {
+    /// The mean of the values, as a floating point number.
+    Mean,
+    /// The lowest value, with its type.
+    Min,
+    /// The highest value, with its type.
+    Max,
+    /// The last value, with its type.
+    Last,
+}
+
+/// Values of a series within an interval.
+struct Bucket<'v, V> {
+    /// The start of the interval.
+    start: DateTime<Utc>,
+    /// The values of the interval, in their order.
+    values: Vec<&'v V>,
+}
+
+/// Resample the values to a value per interval and per series.
+///
+/// The intervals are aligned on the Unix epoch, for example on the hour for an interval of an hour, and
+/// each resampled value is logged at the start of its interval. The values of a series are expected in
+/// chronological order, but the series may be interleaved. The string values are not aggregated: an
+/// interval holding a string value is resampled to its last value, whatever the aggregation. The other
+/// parameters of a resampled value, such as its quality, are those of the value selected, or of the last
+/// value of its interval for the mean.
+///
+/// The resampled values are sorted by series, in the order of their first value, then by time.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, sink::CsvSink, sync::FileCheckpoint}, m2web::error};
+/// # use libewon::{dmweb::{data::DataRecord, sink::DataSink}, series::{downsample, Aggregation}};
+/// # use std::{fs::File, time::Duration};
+/// # #[tokio::test]
+/// # async fn write_hourly_means() -> Result<u64, error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut csv = CsvSink::new(File::create("values.csv")?);
+///
+/// // Store the hourly means rather than the values logged every second.
+/// let mut sink = |records: &[DataRecord]| {
+///     csv.write(&downsample(records, Duration::from_secs(3600), Aggregation::Mean))
+/// };
+/// client
+///     .sync_stream(FileCheckpoint::new("values.transaction"))
+///     .write_to(&mut sink)
+///     .await
+/// # }
+/// ```
+pub fn downsample<V: SeriesValue>(
+    values: &[V],
+    interval: Duration,
+    aggregation: Aggregation,
+) -> Vec<V> {
+    let interval = TimeDelta::from_std(interval)
+        .unwrap_or(TimeDelta::MAX)
+        .max(TimeDelta::milliseconds(1));
+    let mut series: Vec<Vec<Bucket<V>>> = vec![];
+    let mut series_index: HashMap<V::Key, usize> = HashMap::new();
+
+    for value in values {
+        let index = *series_index.entry(value.series_key()).or_insert_with(|| {
+            series.push(vec![]);
+            series.len() - 1
+        });
+        let start = interval_start(value.time(), interval);
+        let buckets = &mut series[index];
+        match buckets
+            .iter_mut()
+            .rev()
+            .find(|bucket| bucket.start == start)
+        {
+            Some(bucket) => bucket.values.push(value),
+            None => buckets.push(Bucket {
+                start,
+                values: vec![value],
+            }),
+        }
+    }
+
+    series
+        .into_iter()
+        .flat_map(|mut buckets| {
+            buckets.sort_by_key(|bucket| bucket.start);
+            buckets
+                .into_iter()
+                .map(move |bucket| aggregate(&bucket, aggregation))
+        })
+        .collect()
+}
+
+/// Return the start of the interval the time belongs to.
+fn interval_start(time: DateTime<Utc>, interval: TimeDelta) -> DateTime<Utc> {
+    let interval_millis = interval.num_milliseconds();
+    let millis = time.timestamp_millis();
+
+    DateTime::from_timestamp_millis(millis - millis.rem_euclid(interval_millis)).unwrap_or(time)
+}
+
+/// Aggregate the values of the interval to a value logged at its start.
+fn aggregate<V: SeriesValue>(bucket: &Bucket<V>, aggregation: Aggregation) -> V {
+    let last = bucket.values[bucket.values.len() - 1];
+    let numbers = bucket
+        .values
+        .iter()
+        .map(|value| value.number())
+        .collect::<Option<Vec<f64>>>();
+    let numbers = match numbers {
+        Some(numbers) => numbers,
+        None => return last.resampled(bucket.start, None),
+    };
+
+    match aggregation {
+        Aggregation::Mean => last.resampled(
+            bucket.start,
+            Some(numbers.iter().sum::<f64>() / numbers.len() as f64),
+        ),
+        Aggregation::Min | Aggregation::Max => {
+            let index = (0..numbers.len())
+                .reduce(|selected, index| {
+                    let replaces = match aggregation {
+                        Aggregation::Min => numbers[index] < numbers[selected],
+                        _ => numbers[index] > numbers[selected],
+                    };
+                    match replaces {
+                        true => index,
+                        false => selected,
+                    }
+                })
+                .unwrap_or_default();
+            bucket.values[index].resampled(bucket.start, None)
+        }
+        Aggregation::Last => last.resampled(bucket.start, None),
+    }
+}
+
+/// Gap in a series of values, longer than the interval expected between two values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gap<V> {
+    /// The start of the gap, the time of the value before it or the start of the window.
+    pub start: DateTime<Utc>,
+    /// The end of the gap, the time of the value after it or the end of the window.
+    pub end: DateTime<Utc>,
+    /// The value before the gap, unless the gap starts with the window.
+    pub before: Option<V>,
+    /// The value after the gap, unless the gap ends with the window.
+    pub after: Option<V>,
+}
+
+impl<V: SeriesValue> Gap<V> {
+    /// Return the duration of the gap.
+    pub fn duration(&self) -> Duration {
+        (self.end - self.start).to_std().unwrap_or_default()
+    }
+
+    /// Return a value of the series, which identifies the eWON and the tag of the gap.
+    ///
+    /// The gaps reported by a `GapDetector` always hold a value.
+    pub fn value(&self) -> Option<&V> {
+        self.before.as_ref().or(self.after.as_ref())
+    }
+}
+
+/// Detector of the gaps in the series of values.
+///
+/// A gap is reported when two successive values of a series are logged further apart than the interval
+/// expected between two values, by default for all the series, or for some of them. When the values are
+/// fetched for a window of time, the gaps at the start and at the end of the window are reported too. The
+/// series without any value are unknown to the detector, so they are not reported.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, query::DataQuery}, m2web::error, series::GapDetector};
+/// # use std::time::Duration;
+/// # #[tokio::test]
+/// # async fn find_logging_holes() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let records = client
+///     .get_data(&DataQuery::default())
+///     .await?
+///     .into_records()
+///     .collect::<Vec<_>>();
+///
+/// // The tags are logged every minute, but the fast tag 12 of the eWON 7.
+/// let gaps = GapDetector::new(Duration::from_secs(60))
+///     .expected_interval((7, 12), Duration::from_secs(1))
+///     .detect(&records);
+/// for gap in &gaps {
+///     if let Some(record) = gap.value() {
+///         println!("{}/{}: no value from {} to {}", record.ewon_name, record.tag_name, gap.start, gap.end);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GapDetector<K> {
+    /// The interval expected between two values of the series without their own interval.
+    expected_interval: Duration,
+    /// The interval expected between two values of some series.
+    series_intervals: HashMap<K, Duration>,
+    /// The window of time the values have been fetched for, if any.
+    window: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl<K: Clone + Eq + Hash> GapDetector<K> {
+    /// Create a detector expecting a value at least at this interval in all the series.
+    pub fn new(expected_interval: Duration) -> Self {
+        GapDetector {
+            expected_interval,
+            series_intervals: HashMap::new(),
+            window: None,
+        }
+    }
+
+    /// Expect a value at least at this interval in this series.
+    pub fn expected_interval(mut self, series: K, expected_interval: Duration) -> Self {
+        self.series_intervals.insert(series, expected_interval);
+        self
+    }
+
+    /// Report the gaps at the start and at the end of the window the values have been fetched for.
+    pub fn window(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.window = Some((from, to));
+        self
+    }
+
+    /// Return the gaps of the series, sorted by series, in the order of their first value, then by time.
+    ///
+    /// The values of a series need not be in chronological order.
+    pub fn detect<V: SeriesValue<Key = K>>(&self, values: &[V]) -> Vec<Gap<V>> {
+        let mut series: Vec<Vec<&V>> = vec![];
+        let mut series_index: HashMap<K, usize> = HashMap::new();
+        for value in values {
+            let index = *series_index.entry(value.series_key()).or_insert_with(|| {
+                series.push(vec![]);
+                series.len() - 1
+            });
+            series[index].push(value);
+        }
+
+        let mut gaps = vec![];
+        for mut values in series {
+            values.sort_by_key(|value| value.time());
+            let expected_interval = self
+                .series_intervals
+                .get(&values[0].series_key())
+                .copied()
+                .unwrap_or(self.expected_interval);
+            let expected_interval =
+                TimeDelta::from_std(expected_interval).unwrap_or(TimeDelta::MAX);
+            let mut found = |start: DateTime<Utc>,
+                             end: DateTime<Utc>,
+                             before: Option<&V>,
+                             after: Option<&V>| {
+                if end - start > expected_interval {
+                    gaps.push(Gap {
+                        start,
+                        end,
+                        before: before.cloned(),
+                        after: after.cloned(),
+                    });
+                }
+            };
+
+            let (first, last) = (values[0], values[values.len() - 1]);
+            if let Some((from, _)) = self.window {
+                found(from, first.time(), None, Some(first));
+            }
+            for pair in values.windows(2) {
+                found(pair[0].time(), pair[1].time(), Some(pair[0]), Some(pair[1]));
+            }
+            if let Some((_, to)) = self.window {
+                found(last.time(), to, Some(last), None);
+            }
+        }
+
+        gaps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dmweb::data::{DataPoint, DataRecord, TagValue},
+        m2web::{history::LogRecord, tag::TagData},
+        series::{downsample, Aggregation, GapDetector},
+    };
+    use chrono::{TimeZone, Utc};
+    use std::time::{Duration, SystemTime};
+
+    /// Return a value of a tag of the eWON, logged at this time.
+    fn record(tag_id: u32, secs: i64, value: TagValue) -> DataRecord {
+        DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id,
+            tag_name: format!("Tag{}", tag_id),
+            data_point: DataPoint {
+                ts: Utc.timestamp_opt(secs, 0).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn downsample_records_ok() {
+        let records = [
+            record(1, 1699999990, TagValue::Int(10)),
+            record(1, 1700000000, TagValue::Int(60)),
+            record(2, 1700000010, TagValue::String("PET-500".to_string())),
+            record(1, 1700000030, TagValue::Int(20)),
+            record(1, 1700000070, TagValue::Int(5)),
+            record(2, 1700000020, TagValue::String("PET-330".to_string())),
+        ];
+        let minute = Duration::from_secs(60);
+
+        assert_eq!(
+            downsample(&records, minute, Aggregation::Mean),
+            [
+                record(1, 1699999980, TagValue::Float(30.0)),
+                record(1, 1700000040, TagValue::Float(5.0)),
+                record(2, 1699999980, TagValue::String("PET-330".to_string())),
+            ]
+        );
+        assert_eq!(
+            downsample(&records, minute, Aggregation::Max)[0],
+            record(1, 1699999980, TagValue::Int(60))
+        );
+        assert_eq!(
+            downsample(&records, minute, Aggregation::Min)[0],
+            record(1, 1699999980, TagValue::Int(10))
+        );
+        assert_eq!(
+            downsample(&records, minute, Aggregation::Last)[0],
+            record(1, 1699999980, TagValue::Int(20))
+        );
+    }
+
+    #[test]
+    fn downsample_log_ok() {
+        let log_record = |secs, value| LogRecord {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            tag_id: 1,
+            tag_name: "Temperature".to_string(),
+            value: TagData::Number(value),
+            quality: 192,
+            initial: secs == 1700000000,
+        };
+
+        let log = downsample(
+            &[
+                log_record(1700000000, 21.0),
+                log_record(1700000600, 22.0),
+                log_record(1700003600, 23.0),
+            ],
+            Duration::from_secs(3600),
+            Aggregation::Mean,
+        );
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(
+            log[0].timestamp,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1699999200)
+        );
+        assert_eq!(log[0].value, TagData::Number(21.5));
+        assert!(!log[0].initial);
+        assert_eq!(log[1].value, TagData::Number(23.0));
+    }
+
+    #[test]
+    fn detect_gaps_ok() {
+        let records = [
+            record(1, 1700000120, TagValue::Int(3)),
+            record(1, 1700000000, TagValue::Int(1)),
+            record(2, 1700000000, TagValue::Int(1)),
+            record(1, 1700000060, TagValue::Int(2)),
+            record(1, 1700000600, TagValue::Int(4)),
+            record(2, 1700000300, TagValue::Int(2)),
+        ];
+
+        let gaps = GapDetector::new(Duration::from_secs(60))
+            .expected_interval((7, 2), Duration::from_secs(300))
+            .detect(&records);
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].start, Utc.timestamp_opt(1700000120, 0).unwrap());
+        assert_eq!(gaps[0].end, Utc.timestamp_opt(1700000600, 0).unwrap());
+        assert_eq!(gaps[0].duration(), Duration::from_secs(480));
+        assert_eq!(gaps[0].value().unwrap().tag_name, "Tag1");
+
+        let gaps = GapDetector::new(Duration::from_secs(300))
+            .window(
+                Utc.timestamp_opt(1699999000, 0).unwrap(),
+                Utc.timestamp_opt(1700000700, 0).unwrap(),
+            )
+            .detect(&records);
+        assert_eq!(gaps.len(), 4);
+        assert_eq!(gaps[0].before, None);
+        assert_eq!(gaps[0].after, Some(record(1, 1700000000, TagValue::Int(1))));
+        assert_eq!(gaps[1].end, Utc.timestamp_opt(1700000600, 0).unwrap());
+        assert_eq!(gaps[2].value().unwrap().tag_id, 2);
+        assert_eq!(gaps[3].after, None);
+        assert_eq!(gaps[3].start, Utc.timestamp_opt(1700000300, 0).unwrap());
+    }
+}
@@ -0,0 +1,458 @@
+//! Local persistence of the eWONs, of their status history and of their values in a SQLite database.
+
+use crate::{
+    dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        sink::DataSink,
+    },
+    m2web::{
+        error,
+        ewon::{Ewon, EwonStatus},
+        watcher::StatusEvent,
+    },
+};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{path::Path, time::SystemTime};
+
+/// The statements creating the tables of the store, unless they exist.
+const SCHEMA: &str = "\
+    CREATE TABLE IF NOT EXISTS ewons (\
+        id INTEGER PRIMARY KEY, \
+        name TEXT NOT NULL, \
+        status TEXT NOT NULL, \
+        snapshot TEXT NOT NULL, \
+        updated_at INTEGER NOT NULL\
+    );\
+    CREATE TABLE IF NOT EXISTS status_history (\
+        ewon_id INTEGER NOT NULL, \
+        ewon TEXT NOT NULL, \
+        old TEXT NOT NULL, \
+        new TEXT NOT NULL, \
+        at INTEGER NOT NULL, \
+        PRIMARY KEY (ewon_id, at)\
+    );\
+    CREATE TABLE IF NOT EXISTS data_points (\
+        ewon_id INTEGER NOT NULL, \
+        ewon TEXT NOT NULL, \
+        tag_id INTEGER NOT NULL, \
+        tag TEXT NOT NULL, \
+        ts INTEGER NOT NULL, \
+        value TEXT NOT NULL, \
+        quality TEXT NOT NULL, \
+        PRIMARY KEY (ewon_id, tag_id, ts)\
+    );\
+    CREATE INDEX IF NOT EXISTS data_points_by_name ON data_points (ewon, tag, ts);";
+
+/// Status change of an eWON, as stored in the status history.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusChange {
+    /// The id of the eWON.
+    pub ewon_id: u32,
+    /// The name of the eWON.
+    pub ewon_name: String,
+    /// The status of the eWON before the change.
+    pub old: EwonStatus,
+    /// The status of the eWON after the change.
+    pub new: EwonStatus,
+    /// The time at which the change has been detected, to the millisecond.
+    pub at: SystemTime,
+}
+
+/// Store persisting the eWONs, their status history and their values in a SQLite database.
+///
+/// The store buffers the data locally, for example on an edge gateway while the backend is unreachable:
+/// - the eWONs are saved as snapshots, a snapshot replacing the previous one of the same eWON;
+/// - the status changes are appended to the status history;
+/// - the values are saved by eWON, tag and time, a value saved again replacing the previous one.
+///
+/// The times are stored in milliseconds since the Unix epoch.
+///
+/// # Example
+/// ```rust
+/// # use libewon::{dmweb::{client::DmClientBuilder, sync::FileCheckpoint}, m2web::error, sqlite::LocalStore};
+/// # #[tokio::test]
+/// # async fn buffer_dm_data() -> Result<(), error::Error> {
+/// let client = DmClientBuilder::default().build().unwrap();
+/// let mut store = LocalStore::open("/var/lib/collector/buffer.sqlite")?;
+///
+/// client
+///     .sync_stream(FileCheckpoint::new("/var/lib/collector/transaction"))
+///     .write_to(&mut store)
+///     .await?;
+/// let values = store.records("bea-test", "Temperature", None, None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct LocalStore {
+    /// The connection to the database.
+    connection: Connection,
+}
+
+impl LocalStore {
+    /// Open the database at this path, created if missing, and create the tables of the store.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, error::Error> {
+        LocalStore::with_connection(Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    /// Open a database held in memory, lost when the store is dropped.
+    pub fn open_in_memory() -> Result<Self, error::Error> {
+        LocalStore::with_connection(Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    /// Create the tables of the store on the connection.
+    fn with_connection(connection: Connection) -> Result<Self, error::Error> {
+        connection.execute_batch(SCHEMA).map_err(sqlite_error)?;
+
+        Ok(LocalStore { connection })
+    }
+
+    /// Save the snapshots of the eWONs, replacing their previous snapshots.
+    pub fn save_ewons(&mut self, ewons: &[Ewon]) -> Result<(), error::Error> {
+        let updated_at = millis(SystemTime::now());
+        let transaction = self.connection.transaction().map_err(sqlite_error)?;
+        {
+            let mut statement = transaction
+                .prepare_cached(
+                    "INSERT INTO ewons (id, name, status, snapshot, updated_at) VALUES (?1, ?2, ?3, ?4, ?5) \
+                    ON CONFLICT (id) DO UPDATE SET name = excluded.name, status = excluded.status, \
+                    snapshot = excluded.snapshot, updated_at = excluded.updated_at",
+                )
+                .map_err(sqlite_error)?;
+            for ewon in ewons {
+                statement
+                    .execute(params![
+                        ewon.id,
+                        ewon.name,
+                        ewon.status.to_string(),
+                        serde_json::to_string(ewon)?,
+                        updated_at,
+                    ])
+                    .map_err(sqlite_error)?;
+            }
+        }
+
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    /// Return the snapshots of the eWONs, sorted by name.
+    pub fn ewons(&self) -> Result<Vec<Ewon>, error::Error> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT snapshot FROM ewons ORDER BY name")
+            .map_err(sqlite_error)?;
+        let snapshots = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(sqlite_error)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(sqlite_error)?;
+
+        snapshots
+            .iter()
+            .map(|snapshot| Ok(serde_json::from_str(snapshot)?))
+            .collect()
+    }
+
+    /// Return the snapshot of the eWON with this name, if any.
+    pub fn ewon(&self, name: &str) -> Result<Option<Ewon>, error::Error> {
+        let snapshot = self
+            .connection
+            .query_row(
+                "SELECT snapshot FROM ewons WHERE name = ?1",
+                [name],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(sqlite_error)?;
+
+        match snapshot {
+            Some(snapshot) => Ok(Some(serde_json::from_str(&snapshot)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Append a status change to the status history, unless already recorded.
+    pub fn record_status(&mut self, event: &StatusEvent) -> Result<(), error::Error> {
+        self.connection
+            .execute(
+                "INSERT INTO status_history (ewon_id, ewon, old, new, at) VALUES (?1, ?2, ?3, ?4, ?5) \
+                ON CONFLICT DO NOTHING",
+                params![
+                    event.ewon.id,
+                    event.ewon.name,
+                    event.old.to_string(),
+                    event.new.to_string(),
+                    millis(event.at),
+                ],
+            )
+            .map_err(sqlite_error)?;
+
+        Ok(())
+    }
+
+    /// Return the status changes of the eWON with this name, detected since this time if any, oldest first.
+    pub fn status_history(
+        &self,
+        ewon: &str,
+        since: Option<SystemTime>,
+    ) -> Result<Vec<StatusChange>, error::Error> {
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT ewon_id, ewon, old, new, at FROM status_history \
+                WHERE ewon = ?1 AND at >= ?2 ORDER BY at",
+            )
+            .map_err(sqlite_error)?;
+        let changes = statement
+            .query_map(
+                params![ewon, since.map(millis).unwrap_or(i64::MIN)],
+                |row| {
+                    Ok(StatusChange {
+                        ewon_id: row.get(0)?,
+                        ewon_name: row.get(1)?,
+                        old: EwonStatus::from(row.get::<_, String>(2)?),
+                        new: EwonStatus::from(row.get::<_, String>(3)?),
+                        at: DateTime::<Utc>::from_timestamp_millis(row.get(4)?)
+                            .unwrap_or_default()
+                            .into(),
+                    })
+                },
+            )
+            .map_err(sqlite_error)?
+            .collect::<Result<_, _>>()
+            .map_err(sqlite_error)?;
+
+        Ok(changes)
+    }
+
+    /// Save the values, replacing those already saved for the same eWON, tag and time.
+    pub fn save_records(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        let transaction = self.connection.transaction().map_err(sqlite_error)?;
+        {
+            let mut statement = transaction
+                .prepare_cached(
+                    "INSERT INTO data_points (ewon_id, ewon, tag_id, tag, ts, value, quality) \
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) \
+                    ON CONFLICT (ewon_id, tag_id, ts) DO UPDATE SET ewon = excluded.ewon, \
+                    tag = excluded.tag, value = excluded.value, quality = excluded.quality",
+                )
+                .map_err(sqlite_error)?;
+            for record in records {
+                statement
+                    .execute(params![
+                        record.ewon_id,
+                        record.ewon_name,
+                        record.tag_id,
+                        record.tag_name,
+                        record.data_point.ts.timestamp_millis(),
+                        serde_json::to_string(&record.data_point.value)?,
+                        record.data_point.quality,
+                    ])
+                    .map_err(sqlite_error)?;
+            }
+        }
+
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    /// Return the values of the tag of the eWON, by names, logged between these times if any, oldest first.
+    ///
+    /// The start time is included, the end time is excluded.
+    pub fn records(
+        &self,
+        ewon: &str,
+        tag: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<DataRecord>, error::Error> {
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT ewon_id, ewon, tag_id, tag, ts, value, quality FROM data_points \
+                WHERE ewon = ?1 AND tag = ?2 AND ts >= ?3 AND ts < ?4 ORDER BY ts",
+            )
+            .map_err(sqlite_error)?;
+        let rows = statement
+            .query_map(
+                params![
+                    ewon,
+                    tag,
+                    from.map(|from| from.timestamp_millis()).unwrap_or(i64::MIN),
+                    to.map(|to| to.timestamp_millis()).unwrap_or(i64::MAX),
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, u32>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                    ))
+                },
+            )
+            .map_err(sqlite_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sqlite_error)?;
+
+        rows.into_iter()
+            .map(
+                |(ewon_id, ewon_name, tag_id, tag_name, ts, value, quality)| {
+                    Ok(DataRecord {
+                        ewon_id,
+                        ewon_name,
+                        tag_id,
+                        tag_name,
+                        data_point: DataPoint {
+                            ts: DateTime::from_timestamp_millis(ts).unwrap_or_default(),
+                            value: serde_json::from_str::<TagValue>(&value)?,
+                            quality,
+                        },
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// Delete the values logged before this time, and return the number of values deleted.
+    pub fn delete_records_before(&mut self, before: DateTime<Utc>) -> Result<usize, error::Error> {
+        self.connection
+            .execute(
+                "DELETE FROM data_points WHERE ts < ?1",
+                [before.timestamp_millis()],
+            )
+            .map_err(sqlite_error)
+    }
+}
+
+impl DataSink for LocalStore {
+    fn write(&mut self, records: &[DataRecord]) -> Result<(), error::Error> {
+        self.save_records(records)
+    }
+}
+
+/// Return the time in milliseconds since the Unix epoch.
+fn millis(time: SystemTime) -> i64 {
+    DateTime::<Utc>::from(time).timestamp_millis()
+}
+
+/// Map an error of SQLite to an internal error.
+fn sqlite_error(error: rusqlite::Error) -> error::Error {
+    error::Error {
+        http_status: 500,
+        api_code: None,
+        kind: error::ErrorKind::InternalError(format!("SQLite error: {}", error)),
+        source: Some(Box::new(error)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dmweb::data::{DataPoint, DataRecord, TagValue},
+        m2web::{
+            ewon::{Ewon, EwonStatus},
+            watcher::StatusEvent,
+        },
+        sqlite::LocalStore,
+    };
+    use chrono::{TimeZone, Utc};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn local_store_ewons_ok() {
+        let mut store = LocalStore::open_in_memory().unwrap();
+        let mut ewon = Ewon {
+            id: 1206698,
+            name: "bea-test".to_string(),
+            status: EwonStatus::Online,
+            ..Default::default()
+        };
+
+        store.save_ewons(&[ewon.to_owned()]).unwrap();
+        ewon.status = EwonStatus::Offline;
+        store.save_ewons(&[ewon.to_owned()]).unwrap();
+        let at = SystemTime::UNIX_EPOCH + Duration::from_millis(1700000000123);
+        let event = StatusEvent {
+            ewon: ewon.to_owned(),
+            old: EwonStatus::Online,
+            new: EwonStatus::Offline,
+            at,
+        };
+        store.record_status(&event).unwrap();
+        store.record_status(&event).unwrap();
+
+        assert_eq!(store.ewons().unwrap(), vec![ewon.to_owned()]);
+        assert_eq!(store.ewon("bea-test").unwrap(), Some(ewon));
+        assert_eq!(store.ewon("bea-prod").unwrap(), None);
+        let history = store.status_history("bea-test", None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new, EwonStatus::Offline);
+        assert_eq!(history[0].at, at);
+        assert!(store
+            .status_history("bea-test", Some(at + Duration::from_millis(1)))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn local_store_records_ok() {
+        let mut store = LocalStore::open_in_memory().unwrap();
+        let record = |secs, value| DataRecord {
+            ewon_id: 7,
+            ewon_name: "bea-test".to_string(),
+            tag_id: 12,
+            tag_name: "Temperature".to_string(),
+            data_point: DataPoint {
+                ts: Utc.timestamp_opt(secs, 0).unwrap(),
+                value,
+                quality: "good".to_string(),
+            },
+        };
+
+        store
+            .save_records(&[
+                record(1700000060, TagValue::Int(22)),
+                record(1700000000, TagValue::Float(21.5)),
+            ])
+            .unwrap();
+        store
+            .save_records(&[record(1700000060, TagValue::Float(22.5))])
+            .unwrap();
+
+        assert_eq!(
+            store
+                .records("bea-test", "Temperature", None, None)
+                .unwrap(),
+            vec![
+                record(1700000000, TagValue::Float(21.5)),
+                record(1700000060, TagValue::Float(22.5)),
+            ]
+        );
+        assert_eq!(
+            store
+                .records(
+                    "bea-test",
+                    "Temperature",
+                    Some(Utc.timestamp_opt(1700000000, 0).unwrap()),
+                    Some(Utc.timestamp_opt(1700000060, 0).unwrap())
+                )
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            store
+                .delete_records_before(Utc.timestamp_opt(1700000060, 0).unwrap())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .records("bea-test", "Temperature", None, None)
+                .unwrap(),
+            vec![record(1700000060, TagValue::Float(22.5))]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use libewon::m2web::{alarm, client, error};
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_alarms_unauthorized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtAR$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let alarms = match client.get_alarms("bea-test").await {
+        Ok(_) => panic!("get_alarms should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", alarms), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_alarms_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let export =
+        "\"TagId\";\"TagName\";\"AlStatus\";\"AlType\";\"TimeInt\";\"TimeStr\";\"UserAck\"\r\n\
+        1;\"Temperature\";2;1;1700000000;\"14/11/2023 22:13:20\";\"\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("AST_Param", "$dtAR$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let alarms = client.get_alarms("bea-test").await?;
+
+    assert_eq!(
+        alarms,
+        vec![alarm::Alarm {
+            tag_id: 1,
+            tag_name: "Temperature".to_string(),
+            status: alarm::AlarmStatus::Alarm,
+            alarm_type: 1,
+            since: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+            user: "".to_string(),
+        }]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ack_alarm_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("Command1", "ALACK \"Temperature\",0"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ExeScriptForm"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.ack_alarm("bea-test", "Temperature").await?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn api_code_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    // The API code, rather than the HTTP status, tells the device does not exist.
+    let json_response = json!({
+      "message": "Device [missing42] does not exist",
+      "code": 410,
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("name", "missing42"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = match client.get_ewon_by_name("missing42").await {
+        Ok(_) => panic!("get_ewon_by_name should have returned an error::Error 410"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.http_status(), 200);
+    assert_eq!(err.api_code(), Some(410));
+    assert!(matches!(err.kind(), error::ErrorKind::DeviceNotFound(_)));
+    assert_eq!(
+        format!("{}", err),
+        "HTTP 200: Device [missing42] does not exist"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn api_code_missing_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Invalid credentials",
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = match client.get_ewons(None).await {
+        Ok(_) => panic!("get_ewons should have returned an error::Error 403"),
+        Err(err) => err,
+    };
+
+    assert_eq!(err.http_status(), 403);
+    assert_eq!(err.api_code(), None);
+    assert!(matches!(
+        err.kind(),
+        error::ErrorKind::InvalidCredentials(_)
+    ));
+    assert_eq!(format!("{}", err), "HTTP 403: Invalid credentials");
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use libewon::m2web::{client, credentials::DeviceCredentials, error};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn backup_config_forbidden_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/config.txt"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let backup = match client.backup_config("bea-test").await {
+        Ok(_) => panic!("backup_config should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", backup), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn backup_config_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/config.txt"))
+        .and(query_param("t2mdeviceusername", "adm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            ":System\r\nIdentification:bea-test\r\n:TagList\r\n\"Id\";\"Name\"\r\n1;\"Temperature\"\r\n",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/comcfg.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(":ComCfg\r\nEthIP:10.0.0.53\r\n"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let backup = client.backup_config("bea-test").await?;
+
+    assert_eq!(
+        backup.config.value("System", "Identification"),
+        Some("bea-test")
+    );
+    assert_eq!(backup.config.section("TagList").unwrap().lines.len(), 2);
+    assert_eq!(backup.comcfg.value("ComCfg", "EthIP"), Some("10.0.0.53"));
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "blocking")]
+
+use libewon::{
+    blocking,
+    m2web::{client, error},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[test]
+fn blocking_get_ewons_ok() -> Result<(), error::Error> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let server = runtime.block_on(MockServer::start());
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = blocking::Client::new(
+        client::ClientBuilder::default()
+            .t2m_url(&server_uri)
+            .build()
+            .unwrap(),
+    )?;
+
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/t2mapi/getewons"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "ewons": [
+                    {
+                        "id": 1,
+                        "name": "bea-test",
+                        "encodedName": "bea-test",
+                        "status": "online",
+                        "description": "",
+                        "customAttributes": ["", "", ""],
+                        "m2webServer": "eu2.m2web.talk2m.com",
+                        "lanDevices": [],
+                        "ewonServices": []
+                    }
+                ],
+                "success": true
+            })))
+            .expect(1)
+            .mount(&server),
+    );
+
+    let ewons = client.get_ewons(None)?;
+    assert_eq!(ewons.len(), 1);
+    assert_eq!(ewons[0].name, "bea-test");
+
+    Ok(())
+}
+
+#[test]
+fn blocking_download_file_to_ok() -> Result<(), error::Error> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let server = runtime.block_on(MockServer::start());
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = blocking::Client::new(
+        client::ClientBuilder::default()
+            .t2m_url(&server_uri)
+            .build()
+            .unwrap(),
+    )?;
+
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/t2mapi/get/bea-test/usr/mydata.csv"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_bytes(b"recipe;speed\nPET-500;12\n".to_vec()),
+            )
+            .expect(1)
+            .mount(&server),
+    );
+
+    let mut written_file = vec![];
+    let written =
+        client.download_file_to("bea-test", "/usr/mydata.csv", &mut written_file, |_| {})?;
+    assert_eq!(written, 24);
+    assert_eq!(written_file, b"recipe;speed\nPET-500;12\n");
+
+    Ok(())
+}
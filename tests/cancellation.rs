@@ -0,0 +1,82 @@
+use libewon::m2web::{
+    cancellation::{Cancellation, CancellationToken},
+    client, error,
+};
+use std::time::{Duration, Instant};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn cancellation_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    let token = CancellationToken::new();
+    let cancellation = Cancellation::new().token(token.clone());
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/usr/mydata.csv"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(b"recipe;speed\nPET-500;12\n".to_vec())
+                .set_delay(Duration::from_secs(10)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        token.cancel();
+    });
+    let mut written_file = vec![];
+    let error = match cancellation
+        .run(client.download_file_to("bea-test", "/usr/mydata.csv", &mut written_file, |_| {}))
+        .await
+    {
+        Ok(_) => panic!("download_file_to should have returned an error::Cancelled"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", error), "Cancelled: Operation cancelled");
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(written_file.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deadline_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(10)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = match Cancellation::new()
+        .timeout(Duration::from_millis(100))
+        .run(client.get_ewons(None))
+        .await
+    {
+        Ok(_) => panic!("get_ewons should have returned an error::Timeout"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", error), "Timeout: Operation deadline exceeded");
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use libewon::m2web::{breaker::CircuitBreaker, client, error};
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn circuit_breaker_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .circuit_breaker(breaker.clone())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/eWON42/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The eWON is unreachable, the relay answers with a server error.
+    for _ in 0..2 {
+        client
+            .proxy_get("bea-test", "rcgi.bin/ParamForm", None)
+            .await?;
+    }
+    assert!(breaker.is_open("/t2mapi/get/bea-test"));
+    let error = client
+        .proxy_get("bea-test", "rcgi.bin/ParamForm", None)
+        .await
+        .unwrap_err();
+    assert!(format!("{}", error)
+        .starts_with("Circuit open: [/t2mapi/get/bea-test] failed 2 times in a row"));
+    client
+        .proxy_get("eWON42", "rcgi.bin/ParamForm", None)
+        .await?;
+
+    Ok(())
+}
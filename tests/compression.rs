@@ -0,0 +1,96 @@
+#![cfg(feature = "gzip")]
+
+use flate2::{write::GzEncoder, Compression};
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error},
+};
+use serde_json::json;
+use std::io::Write;
+use wiremock::{
+    matchers::{header_exists, method, path},
+    Mock, MockServer, Request, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn compression_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder
+        .write_all(
+            json!({
+                "historyCount": 1200,
+                "ewonCount": 2,
+                "success": true
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .and(|request: &Request| {
+            request
+                .headers
+                .get("accept-encoding")
+                .and_then(|accept_encoding| accept_encoding.to_str().ok())
+                .is_some_and(|accept_encoding| accept_encoding.contains("gzip"))
+        })
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .insert_header("content-type", "application/json")
+                .set_body_bytes(encoder.finish().unwrap()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = client.get_status().await?;
+
+    assert_eq!(status.history_count, 1200);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_compression_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .compression(false)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .and(header_exists("accept-encoding"))
+        .respond_with(ResponseTemplate::new(406))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.get_account_info().await?;
+
+    Ok(())
+}
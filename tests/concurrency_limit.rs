@@ -0,0 +1,77 @@
+use futures::future;
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error},
+};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn concurrency_limit_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .max_concurrent_requests(2)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/usr/mydata.csv"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(b"recipe;speed\nPET-500;12\n".to_vec())
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(4)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    let files =
+        future::try_join_all((0..4).map(|_| client.download_file("bea-test", "/usr/mydata.csv")))
+            .await?;
+
+    assert_eq!(files.len(), 4);
+    assert!(start.elapsed() >= Duration::from_millis(400));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_concurrency_limit_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .max_concurrent_requests(1)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "historyCount": 1200,
+                    "ewonCount": 2,
+                    "success": true
+                }))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    let statuses = future::try_join_all((0..3).map(|_| client.get_status())).await?;
+
+    assert_eq!(statuses.len(), 3);
+    assert!(start.elapsed() >= Duration::from_millis(600));
+
+    Ok(())
+}
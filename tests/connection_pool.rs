@@ -0,0 +1,76 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error},
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn connection_pool_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .pool_idle_timeout(Duration::from_secs(600))
+        .pool_max_idle_per_host(512)
+        .tcp_keepalive(Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["", "", ""],
+            "pools": [],
+            "accountType": "Free",
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    for _ in 0..2 {
+        let account_info = client.get_account_info().await?;
+        assert_eq!(account_info.account_name, "account2");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_connection_pool_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .pool_idle_timeout(Duration::from_secs(600))
+        .pool_max_idle_per_host(0)
+        .tcp_keepalive(Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    for _ in 0..2 {
+        let status = client.get_status().await?;
+        assert_eq!(status.ewon_count, 2);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use libewon::m2web::{client, credentials::DeviceCredentials, error};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn device_credentials_unknown_device_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_credentials(DeviceCredentials::new().device("bea-test", "operator", "secret"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-spare/usr/mydata.csv"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let file = match client.download_file("bea-spare", "/usr/mydata.csv").await {
+        Ok(_) => panic!("download_file should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+    assert_eq!(format!("{}", file), "eWON HTTP 401: Unauthorized");
+
+    let request = &server.received_requests().await.unwrap()[0];
+    assert!(!request
+        .url
+        .query_pairs()
+        .any(|(name, _)| name == "t2mdeviceusername"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_credentials_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_credentials(
+            DeviceCredentials::new()
+                .default_credentials("adm", "adm")
+                .device("bea test", "operator", "secret"),
+        )
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea+test/usr/mydata.csv"))
+        .and(query_param("t2mdeviceusername", "operator"))
+        .and(query_param("t2mdevicepassword", "secret"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-spare/usr/mydata.csv"))
+        .and(query_param("t2mdeviceusername", "adm"))
+        .and(query_param("t2mdevicepassword", "adm"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.download_file("bea test", "/usr/mydata.csv").await?;
+    client.download_file("bea-spare", "/usr/mydata.csv").await?;
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use chrono_tz::{America, Europe};
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData, EbdTime},
+    error,
+    timezone::DeviceTimezones,
+};
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn device_timezones_skipped_time_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_timezones(DeviceTimezones::new().default_timezone(Europe::Paris))
+        .build()
+        .unwrap();
+
+    let alarms_export = "\"TagId\";\"TagName\";\"AlStatus\";\"AlType\";\"TimeInt\"\r\n\
+        1;\"Temperature\";2;1;1679797800\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtAR$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(alarms_export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let alarms = match client.get_alarms("bea-test").await {
+        Ok(_) => panic!("get_alarms should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", alarms),
+        "Unable to parse export: line 2: [1679797800] does not exist in timezone Europe/Paris in [1;\"Temperature\";2;1;1679797800]"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_timezones_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_timezones(
+            DeviceTimezones::new()
+                .default_timezone(Europe::Paris)
+                .device("bea-test", America::New_York),
+        )
+        .build()
+        .unwrap();
+
+    let history_export = "\"TagId\";\"TagName\";\"TimeInt\";\"Value\"\r\n\
+        1;\"Temperature\";1700000000;21.5\r\n";
+    let alarms_export = "\"TagId\";\"TagName\";\"AlStatus\";\"AlType\";\"TimeInt\"\r\n\
+        1;\"Temperature\";2;1;1700000000\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(history_export, "text/plain"))
+        .expect(1)
+        .named("local historical logging")
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT$ut"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(history_export, "text/plain"))
+        .expect(1)
+        .named("UTC historical logging")
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtAR$ftT"))
+        .and(path("/t2mapi/get/bea-spare/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(alarms_export, "text/plain"))
+        .expect(1)
+        .named("active alarms")
+        .mount(&server)
+        .await;
+
+    let records = client
+        .get_historical_data("bea-test", &Ebd::new(EbdData::HistoricalLog))
+        .await?;
+    assert_eq!(
+        records[0].timestamp,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1700018000)
+    );
+
+    let records = client
+        .get_historical_data("bea-test", &Ebd::new(EbdData::HistoricalLog).utc())
+        .await?;
+    assert_eq!(
+        records[0].timestamp,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)
+    );
+
+    let alarms = client.get_alarms("bea-spare").await?;
+    assert_eq!(
+        alarms[0].since,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1699996400)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn device_timezones_absolute_window_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_timezones(DeviceTimezones::new().default_timezone(Europe::Paris))
+        .build()
+        .unwrap();
+
+    let history_export = "\"TagId\";\"TagName\";\"TimeInt\";\"Value\"\r\n\
+        1;\"Temperature\";1700000000;21.5\r\n";
+
+    // The absolute start is written in UTC, so the eWON is asked to read and export the times in UTC.
+    Mock::given(method("GET"))
+        .and(query_param(
+            "AST_Param",
+            "$dtHL$ftT$st14/11/2023 21:56:40$ut",
+        ))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(history_export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ebd = Ebd::new(EbdData::HistoricalLog).start(EbdTime::At(
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1699999000),
+    ));
+    let records = client.get_historical_data("bea-test", &ebd).await?;
+    assert_eq!(
+        records[0].timestamp,
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use libewon::{dmweb::client, m2web::error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn dm_client_invalid_credentials_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_token("wrong-token")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .and(query_param("t2mtoken", "wrong-token"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "success": false,
+            "code": 403,
+            "message": "Invalid credentials",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = match client.request::<serde_json::Value>("getstatus", None).await {
+        Ok(_) => panic!("request should have returned an error::InvalidCredentials"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", status), "HTTP 403: Invalid credentials");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_client_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_token("token2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getewon"))
+        .and(query_param("t2mtoken", "token2"))
+        .and(query_param(
+            "t2mdevid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("ewonId", "42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "id": 42,
+            "name": "bea-test",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewon: serde_json::Value = client
+        .request("getewon", Some(vec![("ewonId", "42")]))
+        .await?;
+
+    assert_eq!(ewon["name"], "bea-test");
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use libewon::{
+    dmweb::{client, query::DeleteScope},
+    m2web::error,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn delete_unknown_ewon_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/delete"))
+        .and(query_param("ewonId", "99"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "success": false,
+            "code": 400,
+            "message": "Unknown ewonId",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let deletion = match client.delete(DeleteScope::Ewon(99).confirm()).await {
+        Ok(_) => panic!("delete should have returned an error::MissingOrWrongParameter"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", deletion), "HTTP 400: Unknown ewonId");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/delete"))
+        .and(query_param("transactionId", "42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("delete up to transaction")
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/delete"))
+        .and(query_param("ewonId", "7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("clean eWON")
+        .mount(&server)
+        .await;
+
+    client
+        .delete(DeleteScope::UpToTransaction(42).confirm())
+        .await?;
+    client.delete(DeleteScope::Ewon(7).confirm()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use libewon::{dmweb::client, m2web::error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn dm_get_ewons_empty_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"success": true, "ewons": []})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewons = match client.get_ewons().await {
+        Ok(_) => panic!("get_ewons should have returned an error::NoContent"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", ewons),
+        "HTTP 204: No eWON were returned by API"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_get_ewons_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "ewons": [
+                {
+                    "id": 7,
+                    "name": "bea-test",
+                    "lastSynchroDate": "2023-11-14T22:15:00Z",
+                    "tags": [
+                        {"id": 12, "ewonTagId": 1, "name": "Temperature", "dataType": "Float"},
+                        {"id": 13, "ewonTagId": 2, "name": "Pressure", "dataType": "Float"},
+                    ],
+                },
+                {"id": 8, "name": "bea-spare", "lastSynchroDate": "2023-10-01T08:00:00Z"},
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewons = client.get_ewons().await?;
+
+    assert_eq!(ewons.len(), 2);
+    assert_eq!(ewons[0].last_synchro_date, "2023-11-14T22:15:00Z");
+    assert_eq!(ewons[0].tag_count(), 2);
+    assert_eq!(ewons[0].value_count(), 0);
+    assert_eq!(ewons[1].tag_count(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use libewon::{dmweb::client, m2web::error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_status_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>Maintenance</html>"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = match client.get_status().await {
+        Ok(_) => panic!("get_status should have returned an error::ResponseParsing"),
+        Err(err) => err,
+    };
+
+    assert!(format!("{}", status)
+        .starts_with("Unable to parse JSON response: JSON response syntax error"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_status_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "historyCount": 950000,
+            "ewonCount": 3,
+            "firstHistoryDate": "2023-01-01T00:00:00Z",
+            "lastHistoryDate": "2023-11-14T22:13:20Z",
+            "historyQuota": 1000000,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = client.get_status().await?;
+
+    assert_eq!(status.history_count, 950000);
+    assert_eq!(status.ewon_count, 3);
+    assert_eq!(status.remaining_quota(), Some(50000));
+    assert_eq!(status.usage(), Some(0.95));
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use libewon::{
+    dmweb::{client, data::TagValue, query::DataQuery},
+    m2web::error,
+};
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Return an eWON with a tag holding a value at each of the times, in seconds since 22:13:20.
+fn ewons(tag_id: u32, values: &[(u64, i64)]) -> serde_json::Value {
+    json!([{
+        "id": 7,
+        "name": "bea-test",
+        "tags": [{
+            "id": tag_id,
+            "name": format!("Tag{}", tag_id),
+            "history": values
+                .iter()
+                .map(|(secs, value)| json!({
+                    "date": format!("2023-11-14T22:13:{:02}Z", 20 + secs),
+                    "value": value,
+                }))
+                .collect::<Vec<_>>(),
+        }],
+    }])
+}
+
+#[tokio::test]
+async fn sync_all_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "10"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": 11,
+            "moreDataAvailable": true,
+            "ewons": ewons(12, &[(0, 1)]),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "11"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "success": false,
+            "code": 403,
+            "message": "Invalid credentials",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = match client.sync_all(Some(10), 10, |_| ()).await {
+        Ok(_) => panic!("sync_all should have returned an error::InvalidCredentials"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", data), "HTTP 403: Invalid credentials");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_all_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    for (last_transaction_id, values) in [(10, vec![(0, 1), (1, 2)]), (11, vec![(2, 3)])] {
+        Mock::given(method("GET"))
+            .and(path("/syncdata"))
+            .and(query_param(
+                "lastTransactionId",
+                last_transaction_id.to_string(),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "transactionId": last_transaction_id + 1,
+                "moreDataAvailable": true,
+                "ewons": ewons(12, &values),
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let mut pages = vec![];
+    let data = client
+        .sync_all(Some(10), 2, |progress| pages.push(*progress))
+        .await?;
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[1].values, 3);
+    assert_eq!(data.transaction_id, Some(12));
+    assert!(data.more_data_available);
+    assert_eq!(data.ewons.len(), 1);
+    assert_eq!(data.ewons[0].tags.len(), 1);
+    assert_eq!(data.ewons[0].tags[0].history.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_all_data_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("from", "2023-11-14T22:00:00Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "moreDataAvailable": true,
+            "ewons": ewons(12, &[(0, 1), (1, 2)]),
+        })))
+        .expect(1)
+        .named("first page")
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("from", "2023-11-14T22:13:21Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "moreDataAvailable": false,
+            "ewons": ewons(12, &[(1, 2), (2, 3)]),
+        })))
+        .expect(1)
+        .named("last page")
+        .mount(&server)
+        .await;
+
+    let query = DataQuery::new()
+        .from(SystemTime::UNIX_EPOCH + Duration::from_secs(1699999200))
+        .limit(2);
+    let mut pages = 0;
+    let data = client
+        .get_all_data(&query, 10, |progress| pages = progress.pages)
+        .await?;
+
+    assert_eq!(pages, 2);
+    assert!(!data.more_data_available);
+    assert_eq!(
+        data.ewons[0].tags[0]
+            .history
+            .iter()
+            .map(|data_point| data_point.value.to_owned())
+            .collect::<Vec<_>>(),
+        [TagValue::Int(1), TagValue::Int(2), TagValue::Int(3)]
+    );
+
+    Ok(())
+}
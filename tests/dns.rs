@@ -0,0 +1,68 @@
+use libewon::m2web::{client, dns::IpFamily, error};
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn dns_override_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let t2m_url = format!(
+        "http://m2web.plant.invalid:{}/t2mapi",
+        server.address().port()
+    );
+    let client = client::ClientBuilder::default()
+        .t2m_url(&t2m_url)
+        .resolve("m2web.plant.invalid", IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getstatistics"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": 1200,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (_, body) = client.request_raw("getstatistics", None).await?;
+    assert_eq!(body["requests"], 1200);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ip_family_ok() -> Result<(), error::Error> {
+    // The mock server listens on the IPv4 loopback only.
+    let server = MockServer::start().await;
+    let t2m_url = format!("http://localhost:{}/t2mapi", server.address().port());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&t2m_url)
+        .ip_family(IpFamily::Ipv4Only)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getstatistics"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.request_raw("getstatistics", None).await?;
+
+    let client = client::ClientBuilder::default()
+        .t2m_url(&t2m_url)
+        .ip_family(IpFamily::Ipv6Only)
+        .build()
+        .unwrap();
+    assert!(client.request_raw("getstatistics", None).await.is_err());
+
+    Ok(())
+}
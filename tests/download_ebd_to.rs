@@ -0,0 +1,86 @@
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData},
+    error,
+};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn download_ebd_to_unauthorized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut export = vec![];
+    let written = match client
+        .download_ebd_to(
+            "bea-test",
+            &Ebd::new(EbdData::HistoricalLog),
+            &mut export,
+            |_| {},
+        )
+        .await
+    {
+        Ok(_) => panic!("download_ebd_to should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", written), "eWON HTTP 401: Unauthorized");
+    assert!(export.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_ebd_to_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let history_export =
+        "\"TagId\";\"TimeInt\";\"TimeStr\";\"IsInitValue\";\"Value\";\"IQuality\"\r\n\
+        1;1700000000;\"14/11/2023 22:13:20\";0;21.5;192\r\n"
+            .repeat(1000);
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&history_export))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut export = vec![];
+    let mut progress = vec![];
+    let written = client
+        .download_ebd_to(
+            "bea-test",
+            &Ebd::new(EbdData::HistoricalLog),
+            &mut export,
+            |written| progress.push(written),
+        )
+        .await?;
+
+    assert_eq!(written, history_export.len() as u64);
+    assert_eq!(export, history_export.as_bytes());
+    assert_eq!(progress.last(), Some(&written));
+
+    Ok(())
+}
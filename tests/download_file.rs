@@ -0,0 +1,94 @@
+use libewon::m2web::{client, error, ewon::Ewon};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn download_file_not_found_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/usr/missing.csv"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("File not found"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let file = match client.download_file("bea-test", "/usr/missing.csv").await {
+        Ok(_) => panic!("download_file should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", file), "eWON HTTP 404: File not found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_file_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/usr/mydata.csv"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_bytes(b"recipe;speed\nPET-500;12\n".to_vec()),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let file = client.download_file("bea-test", "/usr/mydata.csv").await?;
+    assert_eq!(file, b"recipe;speed\nPET-500;12\n");
+
+    let mut written_file = vec![];
+    let written = client
+        .download_file_to("bea-test", "/usr/mydata.csv", &mut written_file, |_| {})
+        .await?;
+    assert_eq!(written, 24);
+    assert_eq!(written_file, file);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_file_name_with_space_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    let ewon = Ewon {
+        id: 1206698,
+        name: "bea test".to_string(),
+        encoded_name: "bea+test".to_string(),
+        ..Default::default()
+    };
+
+    // The eWON selected by its name and the one returned by the API are requested at the same path.
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea+test/usr/mydata.csv"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"recipe;speed\n".to_vec()))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let file = client.download_file("bea test", "/usr/mydata.csv").await?;
+    assert_eq!(file, b"recipe;speed\n");
+
+    let file = client.download_file(&ewon, "/usr/mydata.csv").await?;
+    assert_eq!(file, b"recipe;speed\n");
+
+    Ok(())
+}
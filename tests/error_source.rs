@@ -0,0 +1,56 @@
+use libewon::m2web::{client, error};
+use std::error::Error;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn error_source_json_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"success\": tru"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = match client.get_ewons(None).await {
+        Ok(_) => panic!("get_ewons should have returned a parsing error"),
+        Err(err) => err,
+    };
+
+    let source = err
+        .source()
+        .expect("the parsing error should have a source");
+    assert!(source.is::<serde_json::Error>());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn error_source_http_ok() -> Result<(), error::Error> {
+    // Nothing listens on the port 1 of the loopback.
+    let client = client::ClientBuilder::default()
+        .t2m_url("http://127.0.0.1:1/t2mapi")
+        .build()
+        .unwrap();
+
+    let err = match client.get_ewons(None).await {
+        Ok(_) => panic!("get_ewons should have returned a connection error"),
+        Err(err) => err,
+    };
+
+    let source = err
+        .source()
+        .expect("the connection error should have a source");
+    assert!(source.is::<reqwest::Error>());
+
+    Ok(())
+}
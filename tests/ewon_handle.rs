@@ -0,0 +1,119 @@
+use libewon::m2web::{client, error, ewon};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn getewon_response(status: &str) -> serde_json::Value {
+    json!({
+      "ewon": {
+        "id": 1206698,
+        "name": "bea-test",
+        "encodedName": "bea-test",
+        "status": status,
+        "description": "",
+        "customAttributes": ["bea", "", ""],
+        "m2webServer": "eu2.m2web.talk2m.com",
+        "lanDevices": [],
+        "ewonServices": []
+        },
+      "success": true
+    })
+}
+
+#[tokio::test]
+async fn ewon_handle_unknown_ewon_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Device [ewon42] does not exist",
+      "code": 410,
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("name", "ewon42"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(410).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewon = match client.ewon("ewon42").await {
+        Ok(_) => panic!("ewon should have returned an error::Error 410"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", ewon),
+        "HTTP 410: Device [ewon42] does not exist"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ewon_handle_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_device_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("name", "bea-test"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(getewon_response("offline")))
+        .expect(1)
+        .named("getewon by name")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("wakeup by id")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(getewon_response("online")))
+        .expect(1)
+        .named("getewon by id")
+        .mount(&server)
+        .await;
+
+    let mut ewon = client.ewon("bea-test").await?;
+
+    assert_eq!(ewon.id(), 1206698);
+    assert_eq!(ewon.name(), "bea-test");
+    assert_eq!(ewon.encoded_name(), "bea-test");
+    assert_eq!(ewon.m2web_server(), "eu2.m2web.talk2m.com");
+    assert_eq!(ewon.ewon().status, ewon::EwonStatus::Offline);
+
+    assert_eq!(ewon.wakeup().await?, ewon::WakeupOutcome { message: None });
+    assert!(ewon.refresh().await?.is_online());
+    assert!(ewon.ewon().is_online());
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData, EbdTime},
+    error,
+};
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn export_unauthorized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtEV$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let export = match client.export("bea-test", &Ebd::new(EbdData::Events)).await {
+        Ok(_) => panic!("export should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", export), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn export_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT$st_h2$tnTemperature"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("\"TagId\";\"TimeInt\"\r\n"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ebd = Ebd::new(EbdData::HistoricalLog)
+        .start(EbdTime::Ago(Duration::from_secs(2 * 3600)))
+        .tag("Temperature");
+    let export = client.export("bea-test", &ebd).await?;
+
+    assert_eq!(export, "\"TagId\";\"TimeInt\"\r\n");
+
+    Ok(())
+}
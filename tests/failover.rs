@@ -0,0 +1,50 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn failover_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    // Nothing listens on the first port of the range of the privileged ports.
+    let client = client::ClientBuilder::default()
+        .t2m_url("http://127.0.0.1:1/t2mapi")
+        .t2m_fallback_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getstatistics"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": 1200,
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    assert_eq!(client.serving_t2m_url(), "http://127.0.0.1:1/t2mapi");
+    let (_, body) = client.request_raw("getstatistics", None).await?;
+    assert_eq!(body["requests"], 1200);
+    assert_eq!(client.serving_t2m_url(), server_uri);
+    client.request_raw("getstatistics", None).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn failover_ko() -> Result<(), error::Error> {
+    let client = client::ClientBuilder::default()
+        .t2m_url("http://127.0.0.1:1/t2mapi")
+        .t2m_fallback_url("http://127.0.0.1:2/t2mapi")
+        .build()
+        .unwrap();
+
+    assert!(client.request_raw("getstatistics", None).await.is_err());
+    assert_eq!(client.serving_t2m_url(), "http://127.0.0.1:1/t2mapi");
+
+    Ok(())
+}
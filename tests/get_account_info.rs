@@ -0,0 +1,125 @@
+use libewon::m2web::{account, client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_account_info_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+        "code": 403,
+        "message": "Invalid credentials",
+        "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let account_info = match client.get_account_info().await {
+        Ok(_) => panic!("get_account_info should have returned an error::InvalidCredentials"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", account_info), "HTTP 403: Invalid credentials");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_account_info_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "accountReference": "42424",
+      "accountName": "account2",
+      "company": "SMAG Graphique",
+      "customAttributes": [
+        "customer",
+        "site",
+        ""
+      ],
+      "pools": [
+        {
+          "id": 3017,
+          "name": "emea"
+        },
+        {
+          "id": 3018,
+          "name": "apac"
+        }
+      ],
+      "accountType": "Pro",
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let account_info = client.get_account_info().await?;
+
+    assert_eq!(
+        account_info,
+        account::AccountInfo {
+            account_reference: "42424".to_string(),
+            account_name: "account2".to_string(),
+            company: "SMAG Graphique".to_string(),
+            account_type: "Pro".to_string(),
+            custom_attributes: ["customer".to_string(), "site".to_string(), "".to_string()],
+            pools: vec![
+                account::Pool {
+                    id: 3017,
+                    name: "emea".to_string(),
+                },
+                account::Pool {
+                    id: 3018,
+                    name: "apac".to_string(),
+                },
+            ],
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use libewon::{
+    dmweb::{client, query::DataQuery},
+    m2web::error,
+};
+use serde_json::json;
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_data_unknown_ewon_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "99"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "success": false,
+            "code": 400,
+            "message": "Unknown ewonId",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = match client.get_data(&DataQuery::new().ewon(99)).await {
+        Ok(_) => panic!("get_data should have returned an error::MissingOrWrongParameter"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", data), "HTTP 400: Unknown ewonId");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_data_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "7"))
+        .and(query_param("tagId", "12"))
+        .and(query_param("from", "2023-11-14T22:13:20Z"))
+        .and(query_param("to", "2023-11-14T23:13:20Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "moreDataAvailable": false,
+            "ewons": [{
+                "id": 7,
+                "name": "bea-test",
+                "tags": [{
+                    "id": 12,
+                    "name": "Temperature",
+                    "dataType": "Float",
+                    "history": [
+                        {"date": "2023-11-14T22:13:20Z", "value": 21.5, "quality": "good"},
+                    ],
+                }],
+            }],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let from = SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000);
+    let query = DataQuery::new()
+        .ewon(7)
+        .tag(12)
+        .window(from, from + Duration::from_secs(3600));
+    let data = client.get_data(&query).await?;
+
+    assert!(!data.more_data_available);
+    assert_eq!(data.ewons[0].tags[0].history.len(), 1);
+    assert_eq!(SystemTime::from(data.ewons[0].tags[0].history[0].ts), from);
+
+    Ok(())
+}
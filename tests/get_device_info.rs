@@ -0,0 +1,72 @@
+use libewon::m2web::{client, error};
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_device_info_missing_parameter_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtES$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("\"Name\";\"Value\"\r\n\"SerNum\";\"1234-5678-90\"\r\n"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = match client.get_device_info("bea-test").await {
+        Ok(_) => panic!("get_device_info should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", info),
+        "Unable to parse export: missing parameter [UpTime]"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_device_info_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"Name\";\"Value\"\r\n\
+        \"SerNum\";\"1234-5678-90\"\r\n\
+        \"FwrVersion\";\"14.7s0\"\r\n\
+        \"PCode\";\"Flexy205\"\r\n\
+        \"UpTime\";\"3600\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtES$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = client.get_device_info("bea-test").await?;
+
+    assert_eq!(info.serial_number, "1234-5678-90");
+    assert_eq!(info.firmware_version, "14.7s0");
+    assert_eq!(info.product_code, "Flexy205");
+    assert_eq!(info.uptime, Duration::from_secs(3600));
+
+    Ok(())
+}
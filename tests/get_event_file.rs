@@ -0,0 +1,67 @@
+use libewon::m2web::{client, error, event::EventLevel};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_event_file_missing_column_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/events.txt"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("\"TimeInt\";\"Level\"\r\n1700000000;\"Trace\"\r\n"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = match client.get_event_file("bea-test").await {
+        Ok(_) => panic!("get_event_file should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", events),
+        "Unable to parse export: missing column [Message]"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_event_file_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let file = "\"TimeInt\";\"TimeStr\";\"Level\";\"Class\";\"Event\"\r\n\
+        1700000000;\"14/11/2023 22:13:20\";\"Trace\";\"sys\";\"eWON started\"\r\n\
+        1700000060;\"14/11/2023 22:14:20\";\"Error\";\"vpn\";\"VPN connection lost\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/events.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(file, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = client.get_event_file("bea-test").await?;
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].message, "eWON started");
+    assert_eq!(events[1].level, EventLevel::Error);
+    assert_eq!(events[1].origin, "vpn");
+
+    Ok(())
+}
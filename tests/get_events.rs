@@ -0,0 +1,95 @@
+use libewon::m2web::{client, ebd::EbdTime, error, event};
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_events_missing_column_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtEV$ftT$st_h1$et_m0"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("\"TimeInt\";\"Message\"\r\n"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = match client
+        .get_events(
+            "bea-test",
+            EbdTime::Ago(Duration::from_secs(3600)),
+            EbdTime::Ago(Duration::ZERO),
+        )
+        .await
+    {
+        Ok(_) => panic!("get_events should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", events),
+        "Unable to parse export: missing column [Level]"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_events_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let export = "\"TimeInt\";\"TimeStr\";\"Level\";\"Source\";\"Message\"\r\n\
+        1700000000;\"14/11/2023 22:13:20\";\"Warning\";\"vpn\";\"VPN link down\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("AST_Param", "$dtEV$ftT$st_d1$et_m0"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = client
+        .get_events(
+            "bea-test",
+            EbdTime::Ago(Duration::from_secs(24 * 3600)),
+            EbdTime::Ago(Duration::ZERO),
+        )
+        .await?;
+
+    assert_eq!(
+        events,
+        vec![event::EventEntry {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+            level: event::EventLevel::Warning,
+            message: "VPN link down".to_string(),
+            origin: "vpn".to_string(),
+        }]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use libewon::m2web::{client, error, ewon};
+use serde_json::json;
+use std::collections::HashMap;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_ewon_by_encoded_name_invalid_ko() -> Result<(), error::Error> {
+    let client = client::ClientBuilder::default().build().unwrap();
+
+    let ewon = match client.get_ewon_by_encoded_name("eWON+%C3%28").await {
+        Ok(_) => panic!("get_ewon_by_encoded_name should have returned an error::Error 400"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", ewon),
+        "HTTP 400: Encoded name [eWON+%C3%28] is not a valid UTF-8 string"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_ewon_by_encoded_name_filled_ok() -> Result<(), error::Error> {
+    let server = wiremock::MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "ewon": {
+        "id": 639491,
+        "name": "eWON  FLEXOCOLOR SM2845",
+        "encodedName": "eWON++FLEXOCOLOR+SM2845",
+        "status": "online",
+        "description": "SM2845 SIRIUS DEBOBINEUR1000",
+        "customAttributes": [
+          "FRANCE",
+          "",
+          ""
+        ],
+        "m2webServer": "eu2.m2web.talk2m.com",
+        "lanDevices": [],
+        "ewonServices": []
+        },
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("name", "eWON  FLEXOCOLOR SM2845"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewon = client
+        .get_ewon_by_encoded_name("eWON++FLEXOCOLOR+SM2845")
+        .await?;
+
+    assert_eq!(
+        ewon,
+        ewon::Ewon {
+            id: 639491,
+            name: "eWON  FLEXOCOLOR SM2845".to_string(),
+            encoded_name: "eWON++FLEXOCOLOR+SM2845".to_string(),
+            status: ewon::EwonStatus::Online,
+            description: "SM2845 SIRIUS DEBOBINEUR1000".to_string(),
+            custom_attributes: ["FRANCE".to_string(), "".to_string(), "".to_string()],
+            m2web_server: "eu2.m2web.talk2m.com".to_string(),
+            lan_devices: vec![],
+            ewon_services: vec![],
+            extra: HashMap::new(),
+        }
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use libewon::m2web::{client, error, ewon};
 use serde_json::json;
+use std::collections::HashMap;
 use wiremock::{
     matchers::{method, path, query_param},
     Mock, ResponseTemplate,
@@ -76,7 +77,18 @@ async fn get_ewon_by_id_filled_ok() -> Result<(), error::Error> {
         ],
         "m2webServer": "eu2.m2web.talk2m.com",
         "lanDevices": [],
-        "ewonServices": []
+        "ewonServices": [
+          {
+            "name": "HTTP",
+            "port": 80,
+            "protocol": "tcp"
+          },
+          {
+            "name": "FTP",
+            "port": 21,
+            "protocol": "tcp"
+          }
+        ]
         },
       "success": true
     });
@@ -104,12 +116,24 @@ async fn get_ewon_by_id_filled_ok() -> Result<(), error::Error> {
             id: 1206698,
             name: "bea-test".to_string(),
             encoded_name: "bea-test".to_string(),
-            status: "offline".to_string(),
+            status: ewon::EwonStatus::Offline,
             description: "".to_string(),
             custom_attributes: ["bea".to_string(), "".to_string(), "".to_string()],
             m2web_server: "eu2.m2web.talk2m.com".to_string(),
             lan_devices: vec![],
-            ewon_services: vec![],
+            ewon_services: vec![
+                ewon::EwonService {
+                    name: "HTTP".to_string(),
+                    port: 80,
+                    protocol: "tcp".to_string(),
+                },
+                ewon::EwonService {
+                    name: "FTP".to_string(),
+                    port: 21,
+                    protocol: "tcp".to_string(),
+                },
+            ],
+            extra: HashMap::new(),
         }
     );
 
@@ -1,5 +1,6 @@
 use libewon::m2web::{client, error, ewon};
 use serde_json::json;
+use std::collections::HashMap;
 use wiremock::{
     matchers::{method, path, query_param},
     Mock, ResponseTemplate,
@@ -78,7 +79,15 @@ async fn get_ewon_by_name_filled_ok() -> Result<(), error::Error> {
           ""
         ],
         "m2webServer": "eu2.m2web.talk2m.com",
-        "lanDevices": [],
+        "lanDevices": [
+          {
+            "name": "plc",
+            "description": "Press PLC",
+            "ip": "10.0.0.53",
+            "port": 80,
+            "protocol": "http"
+          }
+        ],
         "ewonServices": []
         },
       "success": true
@@ -107,12 +116,19 @@ async fn get_ewon_by_name_filled_ok() -> Result<(), error::Error> {
             id: 1206698,
             name: "bea-test".to_string(),
             encoded_name: "bea-test".to_string(),
-            status: "offline".to_string(),
+            status: ewon::EwonStatus::Offline,
             description: "".to_string(),
             custom_attributes: ["bea".to_string(), "".to_string(), "".to_string()],
             m2web_server: "eu2.m2web.talk2m.com".to_string(),
-            lan_devices: vec![],
+            lan_devices: vec![ewon::LanDevice {
+                name: "plc".to_string(),
+                description: "Press PLC".to_string(),
+                ip: "10.0.0.53".to_string(),
+                port: 80,
+                protocol: "http".to_string(),
+            }],
             ewon_services: vec![],
+            extra: HashMap::new(),
         }
     );
 
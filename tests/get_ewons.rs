@@ -1,5 +1,6 @@
 use libewon::m2web::{client, error, ewon};
 use serde_json::json;
+use std::collections::HashMap;
 use wiremock::{
     matchers::{method, path, query_param},
     Mock, MockServer, ResponseTemplate,
@@ -123,23 +124,25 @@ async fn get_ewons_filled_ok() -> Result<(), error::Error> {
                 id: 1206698,
                 name: "bea-test".to_string(),
                 encoded_name: "bea-test".to_string(),
-                status: "offline".to_string(),
+                status: ewon::EwonStatus::Offline,
                 description: "".to_string(),
                 custom_attributes: ["bea".to_string(), "".to_string(), "".to_string(),],
                 m2web_server: "eu2.m2web.talk2m.com".to_string(),
                 lan_devices: vec![],
                 ewon_services: vec![],
+                extra: HashMap::new(),
             },
             ewon::Ewon {
                 id: 639491,
                 name: "eWON  FLEXOCOLOR SM2845".to_string(),
                 encoded_name: "eWON++FLEXOCOLOR+SM2845".to_string(),
-                status: "online".to_string(),
+                status: ewon::EwonStatus::Online,
                 description: "SM2845 SIRIUS DEBOBINEUR1000".to_string(),
                 custom_attributes: ["FRANCE".to_string(), "".to_string(), "".to_string(),],
                 m2web_server: "eu2.m2web.talk2m.com".to_string(),
                 lan_devices: vec![],
                 ewon_services: vec![],
+                extra: HashMap::new(),
             }
         ],
         &ewons
@@ -209,10 +212,12 @@ async fn get_ewons_filled_missing_fields_ko() -> Result<(), error::Error> {
         Err(err) => err,
     };
 
-    assert_eq!(
-        format!("{}", ewons),
-        "Unable to parse JSON response: JSON response data format does not match the expected one: missing field `status` at line 1 column 171"
-    );
+    // The endpoint and the body are kept to debug the response.
+    let error_message = format!("{}", ewons);
+    assert!(error_message.starts_with(
+        "Unable to parse JSON response: JSON response data format does not match the expected one: missing field `status` at line 1 column 171 from [getewons] in [{\"ewons\":[{"
+    ));
+    assert!(error_message.ends_with("\"success\":true}]"));
 
     Ok(())
 }
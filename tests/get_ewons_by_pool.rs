@@ -0,0 +1,105 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_ewons_by_pool_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response_account_info = json!({
+      "accountReference": "42424",
+      "accountName": "account2",
+      "company": "SMAG Graphique",
+      "customAttributes": ["", "", ""],
+      "pools": [
+        {
+          "id": 3017,
+          "name": "emea"
+        },
+        {
+          "id": 3018,
+          "name": "apac"
+        }
+      ],
+      "accountType": "Pro",
+      "success": true
+    });
+
+    let json_response_emea = json!({
+        "ewons": [
+        {
+            "id": 1206698,
+            "name": "bea-test",
+            "encodedName": "bea-test",
+            "status": "offline",
+            "description": "",
+            "customAttributes": [
+            "bea",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        }
+        ],
+        "success": true
+    });
+
+    let json_response_apac = json!({
+        "ewons": [],
+        "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response_account_info))
+        .expect(1)
+        .named("getaccountinfo")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("pool", "emea"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response_emea))
+        .expect(1)
+        .named("getewons emea")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("pool", "apac"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response_apac))
+        .expect(1)
+        .named("getewons apac")
+        .mount(&server)
+        .await;
+
+    let pools = client.get_ewons_by_pool().await?;
+
+    assert_eq!(pools.len(), 2);
+    assert_eq!(
+        pools["emea"]
+            .iter()
+            .map(|ewon| ewon.id)
+            .collect::<Vec<u32>>(),
+        vec![1206698]
+    );
+    assert!(pools["apac"].is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use libewon::m2web::{client, error, ewon, filter};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_ewons_filtered_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+        "ewons": [
+        {
+            "id": 1206698,
+            "name": "bea-test",
+            "encodedName": "bea-test",
+            "status": "offline",
+            "description": "",
+            "customAttributes": [
+            "bea",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        },
+        {
+            "id": 639491,
+            "name": "eWON  FLEXOCOLOR SM2845",
+            "encodedName": "eWON++FLEXOCOLOR+SM2845",
+            "status": "online",
+            "description": "SM2845 SIRIUS DEBOBINEUR1000",
+            "customAttributes": [
+            "FRANCE",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        }
+        ],
+        "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("pool", "emea"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let online_ewons = client
+        .get_ewons_filtered(
+            Some("emea"),
+            &filter::EwonFilter::default().status(ewon::EwonStatus::Online),
+        )
+        .await?;
+
+    assert_eq!(
+        online_ewons
+            .iter()
+            .map(|ewon| ewon.id)
+            .collect::<Vec<u32>>(),
+        vec![639491]
+    );
+
+    let italian_ewons = client
+        .get_ewons_filtered(
+            Some("emea"),
+            &filter::EwonFilter::default().custom_attribute("ITALY"),
+        )
+        .await?;
+
+    assert!(italian_ewons.is_empty());
+
+    Ok(())
+}
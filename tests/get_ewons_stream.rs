@@ -0,0 +1,133 @@
+use futures::StreamExt;
+use libewon::m2web::{client, error, ewon};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_ewons_stream_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+        "code": 403,
+        "message": "Invalid credentials",
+        "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("pool", ""))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ewons = client
+        .get_ewons_stream(None)
+        .collect::<Vec<Result<ewon::Ewon, error::Error>>>()
+        .await;
+
+    assert_eq!(ewons.len(), 1);
+    assert_eq!(
+        format!("{}", ewons[0].as_ref().unwrap_err()),
+        "HTTP 403: Invalid credentials"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_ewons_stream_filled_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+        "ewons": [
+        {
+            "id": 1206698,
+            "name": "bea-test",
+            "encodedName": "bea-test",
+            "status": "offline",
+            "description": "",
+            "customAttributes": [
+            "bea",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        },
+        {
+            "id": 639491,
+            "name": "eWON  FLEXOCOLOR SM2845",
+            "encodedName": "eWON++FLEXOCOLOR+SM2845",
+            "description": "SM2845 SIRIUS DEBOBINEUR1000",
+            "customAttributes": [
+            "FRANCE",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        }
+        ],
+        "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("pool", "emea"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut ewons = Box::pin(client.get_ewons_stream(Some("emea")));
+
+    // The first eWON is valid, the second one misses its status.
+    let first_ewon = ewons.next().await.unwrap()?;
+    assert_eq!(first_ewon.id, 1206698);
+    assert_eq!(first_ewon.status, ewon::EwonStatus::Offline);
+
+    let second_ewon = ewons.next().await.unwrap();
+    assert!(second_ewon.is_err());
+
+    assert!(ewons.next().await.is_none());
+
+    Ok(())
+}
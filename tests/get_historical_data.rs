@@ -0,0 +1,99 @@
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData, EbdTime},
+    error, history, tag,
+};
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_historical_data_wrong_ebd_ko() -> Result<(), error::Error> {
+    let client = client::ClientBuilder::default().build().unwrap();
+
+    let records = match client
+        .get_historical_data("bea-test", &Ebd::new(EbdData::Events))
+        .await
+    {
+        Ok(_) => panic!("get_historical_data should have returned an error::Error 400"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", records),
+        "HTTP 400: Export Block Descriptor [$dtEV$ftT] does not export the historical logging"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_historical_data_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let history_export =
+        "\"TagId\";\"TimeInt\";\"TimeStr\";\"IsInitValue\";\"Value\";\"IQuality\"\r\n\
+        1;1700000000;\"14/11/2023 22:13:20\";0;21.5;192\r\n\
+        1;1700000060;\"14/11/2023 22:14:20\";0;22;192\r\n";
+    let tags_export = "\"Id\";\"Name\";\"Type\"\r\n\
+        1;\"Temperature\";1\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT$st_h1$et_m0"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(history_export, "text/plain"))
+        .expect(1)
+        .named("historical logging")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtTL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(tags_export, "text/plain"))
+        .expect(1)
+        .named("tag list")
+        .mount(&server)
+        .await;
+
+    let ebd = Ebd::new(EbdData::HistoricalLog).window(
+        EbdTime::Ago(Duration::from_secs(3600)),
+        EbdTime::Ago(Duration::ZERO),
+    );
+    let records = client.get_historical_data("bea-test", &ebd).await?;
+
+    assert_eq!(
+        records,
+        vec![
+            history::LogRecord {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+                tag_id: 1,
+                tag_name: "Temperature".to_string(),
+                value: tag::TagData::Number(21.5),
+                quality: 192,
+                initial: false,
+            },
+            history::LogRecord {
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000060),
+                tag_id: 1,
+                tag_name: "Temperature".to_string(),
+                value: tag::TagData::Number(22.0),
+                quality: 192,
+                initial: false,
+            }
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use libewon::m2web::{account, client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_pools_empty_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "accountReference": "42424",
+      "accountName": "account2",
+      "company": "SMAG Graphique",
+      "customAttributes": ["", "", ""],
+      "pools": [],
+      "accountType": "Free",
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pools = match client.get_pools().await {
+        Ok(_) => panic!("get_pools should have returned an error::Error 204"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", pools),
+        "HTTP 204: No pool were returned by API"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_pools_filled_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "accountReference": "42424",
+      "accountName": "account2",
+      "company": "SMAG Graphique",
+      "customAttributes": ["", "", ""],
+      "pools": [
+        {
+          "id": 3017,
+          "name": "emea"
+        },
+        {
+          "id": 3018,
+          "name": "apac"
+        }
+      ],
+      "accountType": "Pro",
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pools = client.get_pools().await?;
+
+    assert_eq!(
+        pools,
+        vec![
+            account::Pool {
+                id: 3017,
+                name: "emea".to_string(),
+            },
+            account::Pool {
+                id: 3018,
+                name: "apac".to_string(),
+            },
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData, EbdTime},
+    error, history, tag,
+};
+use std::time::{Duration, SystemTime};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn get_realtime_data_wrong_ebd_ko() -> Result<(), error::Error> {
+    let client = client::ClientBuilder::default().build().unwrap();
+
+    let records = match client
+        .get_realtime_data("bea-test", &Ebd::new(EbdData::HistoricalLog))
+        .await
+    {
+        Ok(_) => panic!("get_realtime_data should have returned an error::Error 400"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", records),
+        "HTTP 400: Export Block Descriptor [$dtHL$ftT] does not export the real-time logging"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_realtime_data_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"TagId\";\"TagName\";\"TimeInt\";\"TimeStr\";\"Value\"\r\n\
+        3;\"Pressure\";1700000000;\"14/11/2023 22:13:20\";2.5\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtRL$ftT$st_m5$tnPressure"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ebd = Ebd::new(EbdData::RealTimeLog)
+        .start(EbdTime::Ago(Duration::from_secs(5 * 60)))
+        .tag("Pressure");
+    let records = client.get_realtime_data("bea-test", &ebd).await?;
+
+    assert_eq!(
+        records,
+        vec![history::LogRecord {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1700000000),
+            tag_id: 3,
+            tag_name: "Pressure".to_string(),
+            value: tag::TagData::Number(2.5),
+            quality: 192,
+            initial: false,
+        }]
+    );
+
+    Ok(())
+}
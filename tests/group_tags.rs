@@ -0,0 +1,63 @@
+use libewon::m2web::{client, error, tag::TagGroup};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn read_group_tags_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"TagId\";\"TagName\";\"Value\";\"AlStatus\";\"AlType\";\"Quality\"\r\n\
+        1;\"Temperature\";21.5;0;0;65472\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtIV$ftT$flAC"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = client
+        .read_group_tags("bea-test", &[TagGroup::A, TagGroup::C])
+        .await?;
+
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "Temperature");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_group_tags_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"Id\";\"Name\";\"IVGroupA\";\"IVGroupB\";\"IVGroupC\";\"IVGroupD\"\r\n\
+        2;\"Pressure\";0;1;0;0\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtTL$ftT$flB"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = client.list_group_tags("bea-test", &[TagGroup::B]).await?;
+
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].groups, vec![TagGroup::B]);
+
+    Ok(())
+}
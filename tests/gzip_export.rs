@@ -0,0 +1,106 @@
+use flate2::{write::GzEncoder, Compression};
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData},
+    error,
+};
+use std::io::Write;
+use wiremock::{
+    matchers::{header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const EXPORT: &str = "\"TagId\";\"TagName\";\"TimeInt\";\"Value\"\r\n\
+    1;\"Temperature\";1700000000;21.5\r\n\
+    1;\"Temperature\";1700000060;22\r\n";
+
+fn gzip(data: &str) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(data.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn export_gzip_corrupted_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let mut corrupted = gzip(EXPORT);
+    corrupted.truncate(corrupted.len() / 2);
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(corrupted))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let export = match client
+        .export("bea-test", &Ebd::new(EbdData::HistoricalLog))
+        .await
+    {
+        Ok(_) => panic!("export should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert!(format!("{}", export).starts_with("Unable to parse export: invalid gzip stream"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn export_gzip_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    // The compressed export is not announced by the eWON.
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip(EXPORT)))
+        .expect(3)
+        .mount(&server)
+        .await;
+    // The compressed export is announced by the M2Web API.
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-spare/rcgi.bin/ParamForm"))
+        .and(header("accept-encoding", "gzip"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .set_body_bytes(gzip(EXPORT)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ebd = Ebd::new(EbdData::HistoricalLog);
+    assert_eq!(client.export("bea-test", &ebd).await?, EXPORT);
+    assert_eq!(client.export("bea-spare", &ebd).await?, EXPORT);
+
+    let records = client.get_historical_data("bea-test", &ebd).await?;
+    assert_eq!(records.len(), 2);
+
+    let mut written_export = vec![];
+    let mut progress = vec![];
+    let written = client
+        .download_ebd_to("bea-test", &ebd, &mut written_export, |written| {
+            progress.push(written)
+        })
+        .await?;
+    assert_eq!(written, EXPORT.len() as u64);
+    assert_eq!(written_export, EXPORT.as_bytes());
+    assert_eq!(progress.last(), Some(&written));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error},
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::json;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn http_client() -> reqwest::Client {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-plant", HeaderValue::from_static("lyon"));
+
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn http_client_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let dm_server_uri = server.uri();
+    let http_client = http_client();
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .http_client(http_client.clone())
+        .build()
+        .unwrap();
+    let dm_client = DmClientBuilder::default()
+        .t2m_url(&dm_server_uri)
+        .http_client(http_client)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .and(header("x-plant", "lyon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .and(header("x-plant", "lyon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.get_account_info().await?;
+    dm_client.get_status().await?;
+
+    Ok(())
+}
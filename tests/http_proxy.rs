@@ -0,0 +1,81 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error, http_proxy::HttpProxy},
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn http_proxy_ok() -> Result<(), error::Error> {
+    let proxy = MockServer::start().await;
+    let proxy_uri = proxy.uri();
+    let client = client::ClientBuilder::default()
+        .t2m_url("http://m2web.talk2m.invalid/t2mapi")
+        .http_proxy(HttpProxy::new(&proxy_uri).credentials("svc-talk2m", "secret"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .and(header(
+            "proxy-authorization",
+            "Basic c3ZjLXRhbGsybTpzZWNyZXQ=",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let account_info = client.get_account_info().await?;
+
+    assert_eq!(account_info.account_name, "account2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn http_no_proxy_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .http_proxy(HttpProxy::new("http://proxy.plant.invalid:3128").no_proxy(&["127.0.0.1"]))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = client.get_status().await?;
+
+    assert_eq!(status.history_count, 1200);
+
+    Ok(())
+}
+
+#[test]
+fn http_proxy_ko() {
+    assert!(client::ClientBuilder::default()
+        .http_proxy(HttpProxy::new("not a url"))
+        .build()
+        .is_err());
+}
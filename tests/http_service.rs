@@ -0,0 +1,108 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error, service::HttpService},
+};
+use reqwest::header::HeaderValue;
+use serde_json::json;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tower::{service_fn, util::MapRequestLayer, BoxError, ServiceBuilder};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn http_service_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let dm_server_uri = server.uri();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counted_requests = requests.clone();
+    let http_service = HttpService::new(
+        ServiceBuilder::new()
+            .map_request(move |request: reqwest::Request| {
+                counted_requests.fetch_add(1, Ordering::SeqCst);
+                request
+            })
+            .service(reqwest::Client::new()),
+    );
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .http_service(HttpService::layered(
+            reqwest::Client::new(),
+            MapRequestLayer::new(|mut request: reqwest::Request| {
+                request
+                    .headers_mut()
+                    .insert("x-plant", HeaderValue::from_static("lyon"));
+                request
+            }),
+        ))
+        .build()
+        .unwrap();
+    let dm_client = DmClientBuilder::default()
+        .t2m_url(&dm_server_uri)
+        .http_service(http_service)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .and(header("x-plant", "lyon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    client.get_account_info().await?;
+    dm_client.get_status().await?;
+    dm_client.get_status().await?;
+
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn http_service_ko() -> Result<(), error::Error> {
+    let client = DmClientBuilder::default()
+        .http_service(HttpService::new(service_fn(
+            |_: reqwest::Request| async move {
+                Err::<reqwest::Response, BoxError>("relay unreachable".into())
+            },
+        )))
+        .build()
+        .unwrap();
+
+    let error = match client.get_status().await {
+        Ok(_) => panic!("get_status should have returned an error::UnknownError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", error),
+        "Unknown error: HTTP service error: relay unreachable"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "influx")]
+
+use chrono::{TimeZone, Utc};
+use libewon::{
+    dmweb::{
+        data::{DataPoint, DataRecord, TagValue},
+        influx::InfluxSinkBuilder,
+        sink::AsyncDataSink,
+    },
+    m2web::error,
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{body_string, header, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Return a value of a tag of the eWON.
+fn record(tag_name: &str, value: TagValue) -> DataRecord {
+    DataRecord {
+        ewon_id: 7,
+        ewon_name: "bea-test".to_string(),
+        tag_id: 12,
+        tag_name: tag_name.to_string(),
+        data_point: DataPoint {
+            ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+            value,
+            quality: "good".to_string(),
+        },
+    }
+}
+
+#[tokio::test]
+async fn influx_sink_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    Mock::given(method("POST"))
+        .and(path("/api/v2/write"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "code": "invalid",
+            "message": "unable to parse 'ewon,ewon=bea-test': missing fields",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sink = InfluxSinkBuilder::default()
+        .url(&server_uri)
+        .org("acme")
+        .bucket("ewons")
+        .token("influx-token")
+        .retry_delay(Duration::from_millis(10))
+        .build()
+        .unwrap();
+    sink.write(&[record("Temperature", TagValue::Float(21.5))])
+        .await?;
+    let err = match sink.flush().await {
+        Ok(_) => panic!("flush should have returned an error::MissingOrWrongParameter"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", err),
+        "HTTP 400: unable to parse 'ewon,ewon=bea-test': missing fields"
+    );
+    assert_eq!(sink.pending(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn influx_sink_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    Mock::given(method("POST"))
+        .and(path("/api/v2/write"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/write"))
+        .and(query_param("org", "acme"))
+        .and(query_param("bucket", "ewons"))
+        .and(query_param("precision", "ms"))
+        .and(header("authorization", "Token influx-token"))
+        .and(body_string(
+            "ewon,ewon=bea-test,ewon_id=7,tag=Temperature,tag_id=12,quality=good value=21.5 1700000000000\n\
+            ewon,ewon=bea-test,ewon_id=7,tag=Count,tag_id=12,quality=good value=42i 1700000000000\n",
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/write"))
+        .and(body_string(
+            "ewon,ewon=bea-test,ewon_id=7,tag=Running,tag_id=12,quality=good value=true 1700000000000\n",
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sink = InfluxSinkBuilder::default()
+        .url(&server_uri)
+        .org("acme")
+        .bucket("ewons")
+        .token("influx-token")
+        .batch_size(2)
+        .retry_delay(Duration::from_millis(10))
+        .build()
+        .unwrap();
+    sink.write(&[
+        record("Temperature", TagValue::Float(21.5)),
+        record("Count", TagValue::Int(42)),
+        record("Running", TagValue::Bool(true)),
+    ])
+    .await?;
+    assert_eq!(sink.pending(), 1);
+    sink.flush().await?;
+
+    assert_eq!(sink.pending(), 0);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error, interceptor::Interceptor},
+};
+use reqwest::{header::HeaderValue, Request, Response};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Interceptor recording the paths of the requests and the statuses of the responses.
+#[derive(Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+impl Interceptor for Recorder {
+    fn on_request(&self, request: &mut Request) -> Result<(), error::Error> {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("request {}", request.url().path()));
+        Ok(())
+    }
+
+    fn on_response(&self, response: &Response) -> Result<(), error::Error> {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("response {}", response.status().as_u16()));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn interceptor_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let recorder = Recorder::default();
+    let records = recorder.0.clone();
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .interceptor(|request: &mut Request| -> Result<(), error::Error> {
+            request
+                .headers_mut()
+                .insert("x-gateway-key", HeaderValue::from_static("secret"));
+            Ok(())
+        })
+        .interceptor(recorder)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .and(header("x-gateway-key", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let account_info = client.get_account_info().await?;
+
+    assert_eq!(account_info.account_name, "account2");
+    assert_eq!(
+        *records.lock().unwrap(),
+        vec!["request /t2mapi/getaccountinfo", "response 200"]
+    );
+
+    Ok(())
+}
+
+/// Interceptor rejecting the responses without a request id.
+struct RequireRequestId;
+
+impl Interceptor for RequireRequestId {
+    fn on_response(&self, response: &Response) -> Result<(), error::Error> {
+        match response.headers().contains_key("x-request-id") {
+            true => Ok(()),
+            false => Err(std::io::Error::other("missing request id").into()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn dm_interceptor_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .interceptor(RequireRequestId)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = match client.get_status().await {
+        Ok(_) => panic!("get_status should have returned the error of the interceptor"),
+        Err(err) => err,
+    };
+
+    assert!(format!("{}", error).contains("missing request id"));
+
+    Ok(())
+}
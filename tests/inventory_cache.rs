@@ -0,0 +1,74 @@
+use libewon::m2web::{cache::InventoryCache, client, error};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn ewon_json(id: u32, name: &str) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": name,
+        "encodedName": name,
+        "status": "online",
+        "description": "",
+        "customAttributes": ["", "", ""],
+        "m2webServer": "eu2.m2web.talk2m.com",
+        "lanDevices": [],
+        "ewonServices": []
+    })
+}
+
+#[tokio::test]
+async fn inventory_cache_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let cache = InventoryCache::new(Duration::from_secs(300));
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .inventory_cache(cache.clone())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ewons": [ewon_json(1206698, "bea-test"), ewon_json(639491, "eWON42")],
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewon"))
+        .and(query_param("name", "bea-test"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ewon": ewon_json(1206698, "bea-test"),
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    // The eWONs are fetched once, then returned from the cache.
+    assert_eq!(client.get_ewons(None).await?.len(), 2);
+    assert_eq!(client.get_ewons(None).await?.len(), 2);
+    assert_eq!(client.get_ewon_by_name("bea-test").await?.id, 1206698);
+    assert_eq!(client.get_ewon_by_id(639491).await?.name, "eWON42");
+
+    // The cache is bypassed, and refreshed.
+    assert_eq!(client.refresh_ewons(None).await?.len(), 2);
+    assert_eq!(client.get_ewons(None).await?.len(), 2);
+
+    // Once invalidated, the eWON is fetched again, then returned from the cache.
+    cache.invalidate();
+    assert_eq!(client.get_ewon_by_name("bea-test").await?.id, 1206698);
+    assert_eq!(client.get_ewon_by_name("bea-test").await?.id, 1206698);
+
+    // The eWON is requested again, whatever the cache holds.
+    assert_eq!(client.refresh_ewon("bea-test").await?.id, 1206698);
+    assert_eq!(client.get_ewon_by_name("bea-test").await?.id, 1206698);
+
+    Ok(())
+}
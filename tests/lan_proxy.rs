@@ -0,0 +1,66 @@
+use libewon::m2web::{client, credentials::DeviceCredentials, error};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn lan_proxy_not_found_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/proxy/10.0.0.50/80/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .lan_proxy("bea-test", "10.0.0.50", 80)
+        .get("/missing")
+        .await?;
+    assert_eq!(response.status, 404);
+
+    let error = match response.error_for_status() {
+        Ok(_) => panic!("error_for_status should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+    assert_eq!(format!("{}", error), "eWON HTTP 404: Not Found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn lan_proxy_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/proxy/10.0.0.50/8080/api/status"))
+        .and(query_param("t2mdeviceusername", "adm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("{\"run\":true}", "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .lan_proxy("bea-test", "10.0.0.50", 8080)
+        .get("/api/status")
+        .await?;
+
+    assert!(response.is_success());
+    assert_eq!(response.content_type.as_deref(), Some("application/json"));
+    assert_eq!(response.text(), "{\"run\":true}");
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use libewon::{
+    dmweb::{client, data::TagValue, latest::LatestValues, sync::MemoryCheckpoint},
+    m2web::error,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn latest_values_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+            "success": false,
+            "code": 403,
+            "message": "Invalid credentials",
+        })))
+        .mount(&server)
+        .await;
+
+    let latest_values = LatestValues::new();
+    let records = client
+        .sync_stream(MemoryCheckpoint::new(None))
+        .latest_values(latest_values.clone())
+        .collect::<Vec<_>>()
+        .await;
+
+    match &records[0] {
+        Ok(_) => panic!("sync_stream should have returned an error::InvalidCredentials"),
+        Err(err) => assert_eq!(format!("{}", err), "HTTP 403: Invalid credentials"),
+    }
+    assert!(latest_values.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn latest_values_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": 1,
+            "moreDataAvailable": false,
+            "ewons": [{
+                "id": 7,
+                "name": "bea-test",
+                "tags": [{
+                    "id": 12,
+                    "name": "Temperature",
+                    "history": [
+                        {"date": "2023-11-14T22:13:20Z", "value": 21.5, "quality": "good"},
+                        {"date": "2023-11-14T22:14:20Z", "value": 22.5, "quality": "good"},
+                    ],
+                }],
+            }],
+        })))
+        .mount(&server)
+        .await;
+
+    let latest_values = LatestValues::new();
+    let mut records = client
+        .sync_stream(MemoryCheckpoint::new(None))
+        .latest_values(latest_values.clone());
+
+    // The cache holds the values of a response as soon as its first value is consumed.
+    records.next().await.unwrap()?;
+    assert_eq!(
+        latest_values.latest("bea-test", "Temperature"),
+        Some((
+            Utc.with_ymd_and_hms(2023, 11, 14, 22, 14, 20).unwrap(),
+            TagValue::Float(22.5)
+        ))
+    );
+    assert_eq!(latest_values.latest("bea-test", "Pressure"), None);
+
+    Ok(())
+}
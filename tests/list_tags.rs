@@ -0,0 +1,86 @@
+use libewon::m2web::{client, error, tag};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn list_tags_missing_column_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtTL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("\"TagName\"\r\n\"Temperature\"\r\n"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = match client.list_tags("bea-test").await {
+        Ok(_) => panic!("list_tags should have returned an error::ExportParsing"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", tags),
+        "Unable to parse export: missing column [Id]"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_tags_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let export = "\"Id\";\"Name\";\"Description\";\"AlEnabled\";\"IVGroupA\";\"IVGroupB\";\"IVGroupC\";\"IVGroupD\";\"Type\";\"CustomUnit\"\r\n\
+        1;\"Temperature\";\"Oven temperature\";0;0;1;0;0;1;\"°C\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("AST_Param", "$dtTL$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = client.list_tags("bea-test").await?;
+
+    assert_eq!(
+        tags,
+        vec![tag::Tag {
+            id: 1,
+            name: "Temperature".to_string(),
+            description: "Oven temperature".to_string(),
+            tag_type: tag::TagType::Float,
+            unit: "°C".to_string(),
+            alarm: None,
+            groups: vec![tag::TagGroup::B],
+        }]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error, metrics::MetricsObserver, retry::RetryPolicy},
+};
+use serde_json::json;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Observer recording the starts and the ends of the requests.
+#[derive(Default)]
+struct Recorder(Mutex<Vec<(String, Option<Option<u16>>)>>);
+
+impl MetricsObserver for Recorder {
+    fn on_request_start(&self, endpoint: &str) {
+        self.0.lock().unwrap().push((endpoint.to_string(), None));
+    }
+
+    fn on_request_end(&self, endpoint: &str, status: Option<u16>, latency: Duration) {
+        assert!(latency < Duration::from_secs(5));
+        self.0
+            .lock()
+            .unwrap()
+            .push((endpoint.to_string(), Some(status)));
+    }
+}
+
+#[tokio::test]
+async fn metrics_observer_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let recorder = Arc::new(Recorder::default());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(
+            RetryPolicy::new()
+                .max_attempts(2)
+                .initial_backoff(Duration::from_millis(10)),
+        )
+        .metrics_observer(recorder.clone())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.get_account_info().await?;
+
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        vec![
+            ("/t2mapi/getaccountinfo".to_string(), None),
+            ("/t2mapi/getaccountinfo".to_string(), Some(Some(503))),
+            ("/t2mapi/getaccountinfo".to_string(), None),
+            ("/t2mapi/getaccountinfo".to_string(), Some(Some(200))),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_metrics_observer_connection_ko() -> Result<(), error::Error> {
+    let recorder = Arc::new(Recorder::default());
+    let client = DmClientBuilder::default()
+        .t2m_url("http://127.0.0.1:1")
+        .metrics_observer(recorder.clone())
+        .build()
+        .unwrap();
+
+    assert!(client.get_status().await.is_err());
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        vec![
+            ("/getstatus".to_string(), None),
+            ("/getstatus".to_string(), Some(None)),
+        ]
+    );
+
+    Ok(())
+}
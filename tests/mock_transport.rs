@@ -0,0 +1,52 @@
+#![cfg(feature = "test-util")]
+
+use libewon::m2web::{
+    client, error,
+    ewon::Ewon,
+    mock::{MockResponse, MockTransport},
+};
+
+#[tokio::test]
+async fn mock_transport_ok() -> Result<(), error::Error> {
+    let ewons = vec![Ewon {
+        id: 1206698,
+        name: "bea-test".to_string(),
+        ..Ewon::default()
+    }];
+    let ewon = ewons[0].clone();
+    let transport = MockTransport::new()
+        .on("getewons", MockResponse::ewons(&ewons))
+        .on("getewon", MockResponse::ewon(&ewon));
+    let client = client::ClientBuilder::default()
+        .http_service(transport.clone().into())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get_ewons(None).await?, ewons);
+    assert_eq!(client.get_ewon_by_name("bea-test").await?, ewon);
+    assert_eq!(
+        transport.requested(),
+        vec!["/t2mapi/getewons", "/t2mapi/getewon"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mock_transport_ko() -> Result<(), error::Error> {
+    let transport = MockTransport::new()
+        .on("getewons", MockResponse::ewons(&[]))
+        .on("getewons", MockResponse::error(403, "Invalid credentials"));
+    let client = client::ClientBuilder::default()
+        .http_service(transport.into())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        format!("{}", client.get_ewons(None).await.unwrap_err()),
+        "HTTP 403: Invalid credentials"
+    );
+    assert!(client.get_ewon_by_name("bea-test").await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "prometheus")]
+
+use chrono::{TimeZone, Utc};
+use libewon::{
+    dmweb::data::{DataPoint, DataRecord, TagValue},
+    m2web::error,
+    prometheus::FleetMetrics,
+};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn prometheus_exporter_ko() -> Result<(), error::Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let metrics = FleetMetrics::new();
+    tokio::spawn(async move { metrics.serve(listener).await });
+
+    // A scraper closing the connection before sending its request does not stop the exporter.
+    drop(tokio::net::TcpStream::connect(address).await?);
+    let body = reqwest::get(format!("http://{}/metrics", address))
+        .await?
+        .text()
+        .await?;
+
+    assert!(body.starts_with("# HELP ewon_up"));
+    assert!(!body.contains("ewon_tag_value{"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn prometheus_exporter_ok() -> Result<(), error::Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let metrics = FleetMetrics::new();
+    metrics.observe_records(&[DataRecord {
+        ewon_id: 7,
+        ewon_name: "bea-test".to_string(),
+        tag_id: 12,
+        tag_name: "Running".to_string(),
+        data_point: DataPoint {
+            ts: Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap(),
+            value: TagValue::Bool(true),
+            quality: "good".to_string(),
+        },
+    }]);
+    let served_metrics = metrics.clone();
+    tokio::spawn(async move { served_metrics.serve(listener).await });
+
+    let response = reqwest::get(format!("http://{}/metrics", address)).await?;
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/plain; version=0.0.4; charset=utf-8"
+    );
+    let body = response.text().await?;
+
+    assert_eq!(body, metrics.render());
+    assert!(body.contains("ewon_last_seen_timestamp_seconds{ewon=\"bea-test\"} 1700000000\n"));
+    assert!(body.contains("ewon_tag_value{ewon=\"bea-test\",tag=\"Running\"} 1\n"));
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn proxy_get_invalid_credentials_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Invalid credentials",
+      "code": 403,
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = match client
+        .proxy_get("bea-test", "rcgi.bin/ParamForm", None)
+        .await
+    {
+        Ok(_) => panic!("proxy_get should have returned an error::Error 403"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", response), "HTTP 403: Invalid credentials");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn proxy_get_device_error_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/unknown.shtm"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not Found"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.proxy_get("bea-test", "/unknown.shtm", None).await?;
+
+    assert_eq!(response.status, 404);
+    assert!(!response.is_success());
+    assert_eq!(response.text(), "Not Found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn proxy_get_by_name_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("AST_Param", "$dtSV$"))
+        .and(path("/t2mapi/get/eWON++FLEXOCOLOR/rcgi.bin/ParamForm"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("SerNum:1234-5678-90\nVersion:14.7s0\n", "text/plain"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .proxy_get(
+            "eWON  FLEXOCOLOR",
+            "rcgi.bin/ParamForm",
+            Some(vec![("AST_Param", "$dtSV$")]),
+        )
+        .await?;
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.content_type.as_deref(), Some("text/plain"));
+    assert_eq!(response.text(), "SerNum:1234-5678-90\nVersion:14.7s0\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn proxy_get_by_id_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let device_server_uri = format!("{}/eu2", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_device_url(&device_server_uri)
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "ewon": {
+        "id": 639491,
+        "name": "eWON  FLEXOCOLOR SM2845",
+        "encodedName": "eWON++FLEXOCOLOR+SM2845",
+        "status": "online",
+        "description": "",
+        "customAttributes": ["", "", ""],
+        "m2webServer": "eu2.m2web.talk2m.com",
+        "lanDevices": [],
+        "ewonServices": []
+        },
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "639491"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .named("getewon")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/eu2/get/eWON++FLEXOCOLOR+SM2845/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Version:14.7s0\n"))
+        .expect(1)
+        .named("get")
+        .mount(&server)
+        .await;
+
+    let response = client.proxy_get(639491, "rcgi.bin/ParamForm", None).await?;
+
+    assert!(response.is_success());
+    assert_eq!(response.text(), "Version:14.7s0\n");
+
+    Ok(())
+}
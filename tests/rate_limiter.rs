@@ -0,0 +1,62 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error, limiter::RateLimiter},
+};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn rate_limiter_shared_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let dm_server_uri = server.uri();
+    let limiter = RateLimiter::new(10, Duration::from_secs(1)).burst(1);
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .rate_limiter(limiter.clone())
+        .build()
+        .unwrap();
+    let dm_client = DmClientBuilder::default()
+        .t2m_url(&dm_server_uri)
+        .rate_limiter(limiter)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    client.get_account_info().await?;
+    dm_client.get_status().await?;
+    client.get_account_info().await?;
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+
+    Ok(())
+}
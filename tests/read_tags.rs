@@ -0,0 +1,80 @@
+use libewon::m2web::{client, error, tag};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn read_tags_unauthorized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtIV$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = match client.read_tags("bea-test").await {
+        Ok(_) => panic!("read_tags should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", tags), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_tags_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let export = "\"TagId\";\"TagName\";\"Value\";\"AlStatus\";\"AlType\";\"Quality\"\r\n\
+        1;\"Temperature\";21.5;0;0;65472\r\n\
+        2;\"Recipe\";\"PET-500\";0;0;65472\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("AST_Param", "$dtIV$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tags = client.read_tags("bea-test").await?;
+
+    assert_eq!(
+        tags.iter()
+            .map(|tag| (tag.id, tag.name.as_str(), &tag.value))
+            .collect::<Vec<(u32, &str, &tag::TagData)>>(),
+        vec![
+            (1, "Temperature", &tag::TagData::Number(21.5)),
+            (2, "Recipe", &tag::TagData::Text("PET-500".to_string())),
+        ]
+    );
+    assert!(tags.iter().all(|tag| tag.is_good_quality()));
+
+    Ok(())
+}
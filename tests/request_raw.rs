@@ -0,0 +1,80 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn request_raw_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getstatistics"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param("period", "week"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "requests": 1200,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (status, body) = client
+        .request_raw("getstatistics", Some(vec![("period", "week")]))
+        .await?;
+
+    assert_eq!(status, reqwest::StatusCode::OK);
+    assert_eq!(body["requests"], 1200);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_raw_unsuccessful_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(410).set_body_json(json!({
+            "code": 410,
+            "message": "Device [missing42] does not exist",
+            "success": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/logout"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (status, body) = client
+        .request_raw("getewon", Some(vec![("name", "missing42")]))
+        .await?;
+    assert_eq!(status, reqwest::StatusCode::GONE);
+    assert_eq!(body["message"], "Device [missing42] does not exist");
+
+    let (status, body) = client.request_raw("logout", None).await?;
+    assert_eq!(status, reqwest::StatusCode::NO_CONTENT);
+    assert_eq!(body, serde_json::Value::Null);
+
+    Ok(())
+}
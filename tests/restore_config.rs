@@ -0,0 +1,201 @@
+use libewon::m2web::{
+    client,
+    config::{ConfigBackup, ConfigFile},
+    error,
+};
+use serde_json::json;
+use std::time::SystemTime;
+use wiremock::{
+    matchers::{body_string_contains, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn backup() -> ConfigBackup {
+    ConfigBackup {
+        taken_at: SystemTime::UNIX_EPOCH,
+        config: ConfigFile::parse(
+            ":System\r\nIdentification:bea-old\r\n:TagList\r\n1;\"Temperature\"\r\n2;\"Pressure\"\r\n",
+        ),
+        comcfg: ConfigFile::parse(":ComCfg\r\nEthIP:10.0.0.99\r\n"),
+    }
+}
+
+async fn mount_current_config(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/config.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            ":System\r\nIdentification:bea-test\r\n:TagList\r\n1;\"Temperature\"\r\n",
+        ))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/comcfg.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(":ComCfg\r\nEthIP:10.0.0.53\r\n"))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn restore_config_forbidden_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let restore = match client.restore_config("bea-test", &backup()).await {
+        Ok(_) => panic!("restore_config should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", restore), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_config_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .and(body_string_contains("filename=\"config.txt\""))
+        .and(body_string_contains("Identification:bea-old"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .and(body_string_contains("filename=\"comcfg.txt\""))
+        .and(body_string_contains("EthIP:10.0.0.99"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.restore_config("bea-test", &backup()).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_config_by_id_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    // The eWON is requested once for both files.
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewon"))
+        .and(query_param("id", "1206698"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ewon": {
+                "id": 1206698,
+                "name": "bea-test",
+                "encodedName": "bea-test",
+                "status": "online",
+                "description": "",
+                "customAttributes": ["", "", ""],
+                "m2webServer": "",
+                "lanDevices": [],
+                "ewonServices": []
+            },
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    client.restore_config(1206698, &backup()).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn diff_config_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    mount_current_config(&server).await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let diff = client.diff_config("bea-test", &backup(), None).await?;
+    assert_eq!(diff.config.changed.len(), 2);
+    assert_eq!(diff.comcfg.changed[0].added_lines, ["EthIP:10.0.0.99"]);
+
+    let diff = client
+        .diff_config("bea-test", &backup(), Some(&["TagList"]))
+        .await?;
+    assert_eq!(diff.config.changed.len(), 1);
+    assert_eq!(diff.config.changed[0].added_lines, ["2;\"Pressure\""]);
+    assert!(diff.comcfg.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_config_sections_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    mount_current_config(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .and(body_string_contains("filename=\"config.txt\""))
+        .and(body_string_contains("Identification:bea-test"))
+        .and(body_string_contains("2;\"Pressure\""))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .and(body_string_contains("filename=\"comcfg.txt\""))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let diff = client
+        .restore_config_sections("bea-test", &backup(), &["TagList"])
+        .await?;
+    assert_eq!(diff.config.changed[0].name, "TagList");
+    assert!(diff.comcfg.is_empty());
+
+    Ok(())
+}
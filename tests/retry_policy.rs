@@ -0,0 +1,265 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{
+        client, error,
+        retry::{RetryPolicy, ThrottleEvent},
+    },
+};
+use serde_json::json;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .max_attempts(3)
+        .initial_backoff(Duration::from_millis(10))
+        .jitter(0.0)
+}
+
+#[tokio::test]
+async fn retry_policy_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "accountReference": "42424",
+            "accountName": "account2",
+            "company": "SMAG Graphique",
+            "customAttributes": ["customer", "site", ""],
+            "pools": [],
+            "accountType": "Pro",
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let account_info = client.get_account_info().await?;
+
+    assert_eq!(account_info.account_name, "account2");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_policy_exhausted_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(502).set_body_json(json!({
+            "message": "Bad Gateway",
+            "success": false
+        })))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    assert!(client.get_status().await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_policy_non_idempotent_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/sendofflineaction"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client.send_offline_action(1206698, "reboot").await.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn retry_policy_backoff_ok() {
+    let policy = RetryPolicy::new()
+        .initial_backoff(Duration::from_secs(1))
+        .max_backoff(Duration::from_secs(5));
+
+    assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    assert_eq!(policy.backoff(3), Duration::from_secs(4));
+    assert_eq!(policy.backoff(4), Duration::from_secs(5));
+    assert_eq!(RetryPolicy::never().attempts(), 1);
+}
+
+#[tokio::test]
+async fn retry_policy_throttled_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let events = Arc::new(Mutex::new(vec![]));
+    let throttle_events = events.clone();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(retry_policy().on_throttle(move |event| {
+            throttle_events.lock().unwrap().push(event.clone());
+        }))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "0")
+                .set_body_string("Too many requests"),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "historyCount": 1200,
+            "ewonCount": 2,
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.get_status().await?;
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![ThrottleEvent {
+            url_path: "/getstatus".to_string(),
+            attempt: 1,
+            delay: Duration::ZERO,
+            retrying: true,
+            message: "Too many requests".to_string(),
+        }]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_policy_throttled_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let events = Arc::new(Mutex::new(vec![]));
+    let throttle_events = events.clone();
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(
+            retry_policy()
+                .max_throttle_wait(Duration::from_secs(10))
+                .on_throttle(move |event| {
+                    throttle_events.lock().unwrap().push(event.clone());
+                }),
+        )
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+            "code": 429,
+            "message": "Too many requests, retry in 60 seconds",
+            "success": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = match client.get_account_info().await {
+        Ok(_) => panic!("get_account_info should have returned an error::Throttled"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", error),
+        "HTTP 429: Too many requests, retry in 60 seconds"
+    );
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].delay, Duration::from_secs(60));
+    assert!(!events[0].retrying);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retry_policy_throttled_oversized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let events = Arc::new(Mutex::new(vec![]));
+    let throttle_events = events.clone();
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .retry_policy(retry_policy().on_throttle(move |event| {
+            throttle_events.lock().unwrap().push(event.clone());
+        }))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .expect(1)
+        .named("first throttling")
+        .mount(&server)
+        .await;
+    // The delay of the second throttling overflows the time already spent throttled.
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", u64::MAX.to_string()))
+        .expect(1)
+        .named("oversized throttling")
+        .mount(&server)
+        .await;
+
+    let error = match client.get_account_info().await {
+        Ok(_) => panic!("get_account_info should have returned an error::Throttled"),
+        Err(err) => err,
+    };
+
+    assert!(matches!(error.kind(), error::ErrorKind::Throttled(_)));
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].delay, Duration::from_secs(u64::MAX));
+    assert!(!events[1].retrying);
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "scheduler")]
+
+use libewon::m2web::{
+    client,
+    ebd::{Ebd, EbdData},
+    error,
+    history::LogRecord,
+    scheduler::{ExportJob, Overlap, Schedule, ScheduledJob, Scheduler},
+};
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn scheduler_failed_run_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Export failed"))
+        .mount(&server)
+        .await;
+
+    let job = ExportJob::new(
+        Ebd::new(EbdData::RealTimeLog),
+        &["bea-test"],
+        |_: &str, _: Vec<LogRecord>| Ok(()),
+    );
+    let mut scheduler = Scheduler::new();
+    scheduler.add(
+        ScheduledJob::new("realtime", Schedule::every(Duration::from_millis(200)), job)
+            .retries(2)
+            .retry_delay(Duration::from_millis(10)),
+    );
+    let mut outcomes = scheduler.subscribe();
+
+    let _ = tokio::time::timeout(Duration::from_millis(500), scheduler.run(&client)).await;
+
+    let outcome = outcomes.try_recv().unwrap();
+    assert_eq!(outcome.job, "realtime");
+    assert_eq!(outcome.attempts, 3);
+    assert_eq!(
+        outcome.error.as_deref(),
+        Some("eWON HTTP 500: Export failed")
+    );
+    assert!(!outcome.is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn scheduler_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"TagId\";\"TagName\";\"TimeInt\";\"Value\"\r\n\
+        1;\"Temperature\";1700000000;21.5\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .and(path("/t2mapi/get/bea-spare/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Busy"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtHL$ftT"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .mount(&server)
+        .await;
+
+    let mut written = vec![];
+    let job = ExportJob::new(
+        Ebd::new(EbdData::HistoricalLog),
+        &["bea-test", "bea-spare"],
+        |ewon: &str, records: Vec<LogRecord>| {
+            written.push((ewon.to_string(), records.len()));
+            Ok(())
+        },
+    );
+    let mut scheduler = Scheduler::new();
+    scheduler.add(
+        ScheduledJob::new("history", Schedule::every(Duration::from_millis(300)), job)
+            .retries(1)
+            .overlap(Overlap::RunLate),
+    );
+    let mut outcomes = scheduler.subscribe();
+
+    let _ = tokio::time::timeout(Duration::from_millis(900), scheduler.run(&client)).await;
+    drop(scheduler);
+
+    let outcome = outcomes.try_recv().unwrap();
+    assert!(outcome.is_success());
+    assert_eq!(outcome.attempts, 2);
+    assert!(outcomes.try_recv().unwrap().is_success());
+
+    // The retry only exported the eWON which failed.
+    assert_eq!(
+        written[..3],
+        [
+            ("bea-test".to_string(), 1),
+            ("bea-spare".to_string(), 1),
+            ("bea-test".to_string(), 1),
+        ]
+    );
+
+    Ok(())
+}
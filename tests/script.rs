@@ -0,0 +1,94 @@
+use libewon::m2web::{client, device::ScriptState, error};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn start_script_forbidden_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ExeScriptForm"))
+        .and(query_param("Command1", "RUN"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let script = match client.start_script("bea-test").await {
+        Ok(_) => panic!("start_script should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", script), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn start_stop_script_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ExeScriptForm"))
+        .and(query_param("Command1", "HALT"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ExeScriptForm"))
+        .and(query_param("Command1", "RUN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.stop_script("bea-test").await?;
+    client.start_script("bea-test").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_script_state_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    let export = "\"Name\";\"Value\"\r\n\
+        \"SerNum\";\"1234-5678-90\"\r\n\
+        \"FwrVersion\";\"14.7s0\"\r\n\
+        \"PCode\";\"Flexy205\"\r\n\
+        \"UpTime\";\"3600\"\r\n\
+        \"BasicRunning\";\"0\"\r\n";
+
+    Mock::given(method("GET"))
+        .and(query_param("AST_Param", "$dtES$ftT"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/ParamForm"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(export, "text/plain"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert_eq!(
+        client.get_script_state("bea-test").await?,
+        ScriptState::Stopped
+    );
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn send_offline_action_missing_action_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Parameter [action] is missing",
+      "code": 400,
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("name", "bea-test"))
+        .and(query_param("action", ""))
+        .and(path("/t2mapi/sendofflineaction"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let action = match client.send_offline_action("bea-test", "").await {
+        Ok(_) => panic!("send_offline_action should have returned an error::Error 400"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", action),
+        "HTTP 400: Parameter [action] is missing"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_offline_action_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("id", "1206698"))
+        .and(query_param("action", "reboot"))
+        .and(path("/t2mapi/sendofflineaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send_offline_action(1206698, "reboot").await?;
+
+    Ok(())
+}
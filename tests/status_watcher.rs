@@ -0,0 +1,276 @@
+use libewon::m2web::{cache::InventoryCache, client, error, ewon, watcher};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn getewons_response(bea_test_status: &str, flexocolor_status: &str) -> serde_json::Value {
+    json!({
+        "ewons": [
+        {
+            "id": 1206698,
+            "name": "bea-test",
+            "encodedName": "bea-test",
+            "status": bea_test_status,
+            "description": "",
+            "customAttributes": [
+            "bea",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        },
+        {
+            "id": 639491,
+            "name": "eWON  FLEXOCOLOR SM2845",
+            "encodedName": "eWON++FLEXOCOLOR+SM2845",
+            "status": flexocolor_status,
+            "description": "SM2845 SIRIUS DEBOBINEUR1000",
+            "customAttributes": [
+            "FRANCE",
+            "",
+            ""
+            ],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        }
+        ],
+        "success": true
+    })
+}
+
+#[tokio::test]
+async fn status_watcher_poll_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("pool", "emea"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("online", "online")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .named("first poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("pool", "emea"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("offline", "online")),
+        )
+        .expect(1)
+        .named("second poll")
+        .mount(&server)
+        .await;
+
+    let mut watcher = watcher::StatusWatcher::new(Duration::from_secs(60)).pool("emea");
+    let mut events = watcher.subscribe();
+
+    // The first poll only records the statuses.
+    assert!(watcher.poll(&client).await?.is_empty());
+
+    let polled_events = watcher.poll(&client).await?;
+    assert_eq!(polled_events.len(), 1);
+
+    let event = events.recv().await.unwrap();
+    assert_eq!(event, polled_events[0]);
+    assert_eq!(event.ewon.id, 1206698);
+    assert_eq!(event.old, ewon::EwonStatus::Online);
+    assert_eq!(event.new, ewon::EwonStatus::Offline);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn status_watcher_poll_cached_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    // The cache outlives the polls, which must bypass it anyway.
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .inventory_cache(InventoryCache::new(Duration::from_secs(3600)))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("online", "online")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .named("first poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("online", "offline")),
+        )
+        .expect(1)
+        .named("second poll")
+        .mount(&server)
+        .await;
+
+    let mut watcher = watcher::StatusWatcher::new(Duration::from_secs(60));
+
+    assert!(watcher.poll(&client).await?.is_empty());
+
+    let events = watcher.poll(&client).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].ewon.id, 639491);
+    assert_eq!(events[0].new, ewon::EwonStatus::Offline);
+
+    // The cache holds the statuses of the last poll.
+    assert_eq!(
+        client.get_ewons(None).await?[1].status,
+        ewon::EwonStatus::Offline
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn status_watcher_run_failed_poll_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("online", "online")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .named("first poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({
+            "message": "Internal server error",
+            "success": false
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .named("failed poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("offline", "online")),
+        )
+        .named("next polls")
+        .mount(&server)
+        .await;
+
+    let errors = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let failed_polls = errors.clone();
+    let mut watcher = watcher::StatusWatcher::new(Duration::from_millis(50))
+        .on_error(move |err| failed_polls.lock().unwrap().push(err.to_string()));
+    let mut events = watcher.subscribe();
+
+    // The watcher keeps polling after the failed poll, until the future is dropped.
+    let _ = tokio::time::timeout(Duration::from_millis(300), watcher.run(&client)).await;
+
+    assert_eq!(
+        *errors.lock().unwrap(),
+        vec!["HTTP 500: Internal server error".to_string()]
+    );
+    let event = events.recv().await.unwrap();
+    assert_eq!(event.ewon.id, 1206698);
+    assert_eq!(event.new, ewon::EwonStatus::Offline);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flap_notifier_run_failed_poll_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("online", "online")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .named("first poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({
+            "message": "Internal server error",
+            "success": false
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .named("failed poll")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(getewons_response("offline", "online")),
+        )
+        .named("next polls")
+        .mount(&server)
+        .await;
+
+    let errors = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let failed_polls = errors.clone();
+    let mut notified = vec![];
+    let mut notifier = watcher::FlapNotifier::new(|event: &watcher::StatusEvent| {
+        notified.push((event.ewon.id, event.new.to_owned()))
+    })
+    .on_error(move |err| failed_polls.lock().unwrap().push(err.to_string()));
+
+    // The notifier keeps polling after the failed poll, until the future is dropped.
+    let _ = tokio::time::timeout(
+        Duration::from_millis(300),
+        notifier.run(&client, Duration::from_millis(50)),
+    )
+    .await;
+    drop(notifier);
+
+    assert_eq!(
+        *errors.lock().unwrap(),
+        vec!["HTTP 500: Internal server error".to_string()]
+    );
+    assert_eq!(notified, vec![(1206698, ewon::EwonStatus::Offline)]);
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use libewon::{
+    dmweb::{client, data::TagValue},
+    m2web::error,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn sync_data_unknown_transaction_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "999"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "success": false,
+            "code": 400,
+            "message": "Invalid lastTransactionId",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = match client.sync_data(Some(999), true).await {
+        Ok(_) => panic!("sync_data should have returned an error::MissingOrWrongParameter"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", data), "HTTP 400: Invalid lastTransactionId");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_data_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("createTransaction", "true"))
+        .and(query_param("lastTransactionId", "41"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": 42,
+            "moreDataAvailable": true,
+            "ewons": [{
+                "id": 7,
+                "name": "bea-test",
+                "lastSynchroDate": "2023-11-14T22:15:00Z",
+                "tags": [{
+                    "id": 12,
+                    "ewonTagId": 1,
+                    "name": "Temperature",
+                    "dataType": "Float",
+                    "description": "",
+                    "value": 22.0,
+                    "quality": "good",
+                    "history": [
+                        {"date": "2023-11-14T22:13:20Z", "value": 21.5, "quality": "good"},
+                        {"date": "2023-11-14T22:14:20Z", "value": 22.0, "quality": "good"},
+                    ],
+                }],
+            }],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let data = client.sync_data(Some(41), true).await?;
+
+    assert_eq!(data.transaction_id, Some(42));
+    assert!(data.more_data_available);
+    assert_eq!(data.ewons[0].name, "bea-test");
+    assert_eq!(data.ewons[0].tags[0].name, "Temperature");
+    assert_eq!(data.ewons[0].tags[0].history.len(), 2);
+    assert_eq!(
+        data.ewons[0].tags[0].history[0].value,
+        TagValue::Float(21.5)
+    );
+
+    Ok(())
+}
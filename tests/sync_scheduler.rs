@@ -0,0 +1,160 @@
+#![cfg(feature = "scheduler")]
+
+use libewon::{
+    dmweb::{
+        client,
+        data::DataRecord,
+        scheduler::{SyncBudget, SyncEvent, SyncScheduler},
+    },
+    m2web::error,
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Return the response holding the values of a tag of an eWON.
+fn data(ewon_id: u32, values: &[i64]) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "success": true,
+        "moreDataAvailable": false,
+        "ewons": [{
+            "id": ewon_id,
+            "name": format!("ewon{}", ewon_id),
+            "tags": [{
+                "id": 12,
+                "name": "Temperature",
+                "history": values
+                    .iter()
+                    .enumerate()
+                    .map(|(secs, value)| json!({
+                        "date": format!("2023-11-14T22:13:{:02}Z", 20 + secs),
+                        "value": value,
+                    }))
+                    .collect::<Vec<_>>(),
+            }],
+        }],
+    }))
+}
+
+#[tokio::test]
+async fn sync_scheduler_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "7"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "success": false,
+            "code": 400,
+            "message": "Unknown ewonId",
+        })))
+        .mount(&server)
+        .await;
+
+    let mut scheduler =
+        SyncScheduler::new(SyncBudget::new(20, Duration::from_secs(1))).device(7, None);
+    let mut events = scheduler.subscribe();
+    let _ = tokio::time::timeout(
+        Duration::from_millis(200),
+        scheduler.run(&client, |_: u32, _: Vec<DataRecord>| {
+            panic!("sync_scheduler should not have written any value")
+        }),
+    )
+    .await;
+
+    assert_eq!(
+        events.try_recv().unwrap(),
+        SyncEvent::Failed {
+            ewon_id: 7,
+            error: "HTTP 400: Unknown ewonId".to_string()
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_scheduler_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "7"))
+        .respond_with(ResponseTemplate::new(429).set_body_string("Too many requests"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "7"))
+        .respond_with(data(7, &[1, 2]))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/getdata"))
+        .and(query_param("ewonId", "8"))
+        .respond_with(data(8, &[3]))
+        .mount(&server)
+        .await;
+
+    let mut scheduler = SyncScheduler::new(SyncBudget::new(20, Duration::from_secs(1)))
+        .device(7, None)
+        .device(8, None);
+    let mut events = scheduler.subscribe();
+    let mut written = vec![];
+    let _ = tokio::time::timeout(
+        Duration::from_millis(500),
+        scheduler.run(&client, |ewon_id: u32, records: Vec<DataRecord>| {
+            written.push((ewon_id, records.len()));
+            Ok(())
+        }),
+    )
+    .await;
+
+    assert_eq!(
+        events.try_recv().unwrap(),
+        SyncEvent::Throttled {
+            ewon_id: 7,
+            backoff: Duration::from_millis(50)
+        }
+    );
+    assert_eq!(
+        events.try_recv().unwrap(),
+        SyncEvent::Synced {
+            ewon_id: 7,
+            values: 2
+        }
+    );
+    assert_eq!(
+        events.try_recv().unwrap(),
+        SyncEvent::Synced {
+            ewon_id: 8,
+            values: 1
+        }
+    );
+    // The values received again are dropped.
+    assert_eq!(
+        events.try_recv().unwrap(),
+        SyncEvent::Synced {
+            ewon_id: 7,
+            values: 0
+        }
+    );
+    assert_eq!(written, [(7, 2), (8, 1)]);
+
+    Ok(())
+}
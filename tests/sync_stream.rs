@@ -0,0 +1,135 @@
+#![cfg(feature = "fs")]
+
+use futures::StreamExt;
+use libewon::{
+    dmweb::{
+        client,
+        data::TagValue,
+        sync::{CheckpointStore, FileCheckpoint},
+    },
+    m2web::error,
+};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn sync_stream_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    let checkpoint = std::env::temp_dir().join(format!("libewon-sync-ko-{}", std::process::id()));
+    FileCheckpoint::new(&checkpoint).save(10)?;
+
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "10"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "success": false,
+            "code": 400,
+            "message": "Invalid lastTransactionId",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let records = client
+        .sync_stream(FileCheckpoint::new(&checkpoint))
+        .collect::<Vec<_>>()
+        .await;
+    let saved = FileCheckpoint::new(&checkpoint).load()?;
+    std::fs::remove_file(&checkpoint)?;
+
+    assert_eq!(records.len(), 1);
+    match &records[0] {
+        Ok(_) => panic!("sync_stream should have returned an error::MissingOrWrongParameter"),
+        Err(err) => assert_eq!(format!("{}", err), "HTTP 400: Invalid lastTransactionId"),
+    }
+    assert_eq!(saved, Some(10));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_stream_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    let checkpoint = std::env::temp_dir().join(format!("libewon-sync-ok-{}", std::process::id()));
+    FileCheckpoint::new(&checkpoint).save(10)?;
+
+    let ewons = |values: &[f64]| {
+        json!([{
+            "id": 7,
+            "name": "bea-test",
+            "tags": [{
+                "id": 12,
+                "name": "Temperature",
+                "history": values
+                    .iter()
+                    .map(|value| json!({"date": "2023-11-14T22:13:20Z", "value": value}))
+                    .collect::<Vec<_>>(),
+            }],
+        }])
+    };
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "10"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": 11,
+            "moreDataAvailable": true,
+            "ewons": ewons(&[21.5, 22.5]),
+        })))
+        .expect(1)
+        .named("first page")
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/syncdata"))
+        .and(query_param("lastTransactionId", "11"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": 12,
+            "moreDataAvailable": false,
+            "ewons": ewons(&[23.5]),
+        })))
+        .expect(1)
+        .named("last page")
+        .mount(&server)
+        .await;
+
+    let mut records = client.sync_stream(FileCheckpoint::new(&checkpoint));
+    let mut values = vec![];
+    while let Some(record) = records.next().await {
+        let record = record?;
+        assert_eq!(record.ewon_name, "bea-test");
+        assert_eq!(record.tag_name, "Temperature");
+        values.push(record.data_point.value);
+        // The transaction is only saved once all its values are consumed.
+        let saved = FileCheckpoint::new(&checkpoint).load()?;
+        assert_eq!(saved, Some(if values.len() <= 2 { 10 } else { 11 }));
+    }
+    let saved = FileCheckpoint::new(&checkpoint).load()?;
+    std::fs::remove_file(&checkpoint)?;
+
+    assert_eq!(
+        values,
+        [
+            TagValue::Float(21.5),
+            TagValue::Float(22.5),
+            TagValue::Float(23.5)
+        ]
+    );
+    assert_eq!(saved, Some(12));
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use libewon::{
+    dmweb::client::DmClientBuilder,
+    m2web::{client, error},
+};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn timeout_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getaccountinfo"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"success": true}))
+                .set_delay(Duration::from_secs(2)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = match client.get_account_info().await {
+        Ok(_) => panic!("get_account_info should have returned an error::Timeout"),
+        Err(err) => err,
+    };
+
+    assert!(format!("{}", error).starts_with("Timeout: Request to API timed out"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dm_timeout_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .read_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/getstatus"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "historyCount": 1200,
+                    "ewonCount": 2,
+                    "success": true
+                }))
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = client.get_status().await?;
+
+    assert_eq!(status.history_count, 1200);
+
+    Ok(())
+}
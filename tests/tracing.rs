@@ -0,0 +1,123 @@
+#![cfg(feature = "tracing")]
+
+use libewon::m2web::{client, error};
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+use tracing_core::span::Current;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Subscriber recording the spans, their fields and the events, one line each.
+#[derive(Clone, Default)]
+struct Recorder {
+    records: Arc<Mutex<Vec<String>>>,
+    spans: Arc<Mutex<HashMap<u64, &'static Metadata<'static>>>>,
+    entered: Arc<Mutex<Vec<span::Id>>>,
+}
+
+/// Visitor writing the fields to a record.
+struct FieldWriter<'r>(&'r mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        write!(self.0, " {}={}", field.name(), value).unwrap();
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        write!(self.0, " {}={:?}", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut spans = self.spans.lock().unwrap();
+        let id = spans.len() as u64 + 1;
+        spans.insert(id, span.metadata());
+        let mut record = format!("span {}", span.metadata().name());
+        span.record(&mut FieldWriter(&mut record));
+        self.records.lock().unwrap().push(record);
+
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        let mut record = format!(
+            "record {}",
+            self.spans.lock().unwrap()[&span.into_u64()].name()
+        );
+        values.record(&mut FieldWriter(&mut record));
+        self.records.lock().unwrap().push(record);
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut record = "event".to_string();
+        event.record(&mut FieldWriter(&mut record));
+        self.records.lock().unwrap().push(record);
+    }
+
+    fn enter(&self, span: &span::Id) {
+        self.entered.lock().unwrap().push(span.clone());
+    }
+
+    fn exit(&self, _: &span::Id) {
+        self.entered.lock().unwrap().pop();
+    }
+
+    fn current_span(&self) -> Current {
+        match self.entered.lock().unwrap().last() {
+            Some(span) => Current::new(span.clone(), self.spans.lock().unwrap()[&span.into_u64()]),
+            None => Current::none(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn tracing_ok() -> Result<(), error::Error> {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account1")
+        .t2m_username("username1")
+        .t2m_password("password1")
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/usr/mydata.csv"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_bytes(b"recipe;speed\nPET-500;12\n".to_vec()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.download_file("bea-test", "/usr/mydata.csv").await?;
+
+    let records = recorder.records.lock().unwrap();
+    assert!(records.contains(&"span download_file".to_string()));
+    assert!(records.contains(&"record proxy_get device=bea-test".to_string()));
+    assert!(records.iter().any(|record| record.starts_with(
+        "event message=request to API endpoint=/t2mapi/get/bea-test/usr/mydata.csv attempt=1 status=200"
+    ) && record.ends_with("outcome=success")));
+    assert!(records.iter().all(|record| !record.contains("password1")));
+
+    Ok(())
+}
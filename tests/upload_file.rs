@@ -0,0 +1,62 @@
+use libewon::m2web::{client, credentials::DeviceCredentials, error};
+use wiremock::{
+    matchers::{body_string_contains, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn upload_file_forbidden_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let upload = match client
+        .upload_file("bea-test", "/usr/recipe.txt", "PET-500;12\n")
+        .await
+    {
+        Ok(_) => panic!("upload_file should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", upload), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn upload_file_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UploadFileForm"))
+        .and(query_param("t2mdeviceusername", "adm"))
+        .and(body_string_contains("/usr/recipe.txt"))
+        .and(body_string_contains("filename=\"recipe.txt\""))
+        .and(body_string_contains("PET-500;12"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .upload_file("bea-test", "/usr/recipe.txt", "PET-500;12\n")
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "test-util")]
+
+use libewon::m2web::{client, error, vcr::VcrTransport};
+use serde_json::json;
+use std::fs;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn vcr_transport_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let fixture = std::env::temp_dir().join(format!("libewon-vcr-{}.json", std::process::id()));
+    let transport = VcrTransport::record(&fixture, reqwest::Client::new());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("740d0bae-91b0-4e55-8d0b-8a8f4b5c2b3a")
+        .http_service(transport.clone().into())
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/getewon"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param("name", "bea-test"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ewon": {
+                "id": 1206698,
+                "name": "bea-test",
+                "encodedName": "bea-test",
+                "status": "online",
+                "description": "",
+                "customAttributes": ["", "", ""],
+                "m2webServer": "eu2.m2web.talk2m.com",
+                "lanDevices": [],
+                "ewonServices": []
+            },
+            "success": true
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let recorded = client.get_ewon_by_name("bea-test").await?;
+    transport.save()?;
+
+    let cassette = fs::read_to_string(&fixture)?;
+    assert!(!cassette.contains("password2"));
+    assert!(!cassette.contains("740d0bae"));
+    assert!(cassette.contains("t2mpassword=scrubbed"));
+
+    let client = client::ClientBuilder::default()
+        .t2m_url("https://eu2.m2web.talk2m.com/t2mapi")
+        .http_service(VcrTransport::replay(&fixture)?.into())
+        .build()
+        .unwrap();
+    fs::remove_file(&fixture)?;
+
+    assert_eq!(client.get_ewon_by_name("bea-test").await?, recorded);
+    assert_eq!(
+        format!("{}", client.get_ewon_by_name("bea-test").await.unwrap_err()),
+        format!(
+            "Unknown error: HTTP service error: No recorded interaction for GET \
+             /t2mapi/getewon?t2maccount=scrubbed&t2musername=scrubbed&t2mpassword=scrubbed\
+             &t2mdeveloperid=scrubbed&name=bea-test in {}",
+            fixture.display()
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use libewon::m2web::{client, error, ewon};
+use serde_json::json;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn wakeup_unknown_ewon_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Device [ewon42] does not exist",
+      "code": 410,
+      "success": false
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("name", "ewon42"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(410).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let outcome = match client.wakeup("ewon42").await {
+        Ok(_) => panic!("wakeup should have returned an error::Error 410"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", outcome),
+        "HTTP 410: Device [ewon42] does not exist"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wakeup_by_name_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "message": "Wake-up SMS sent",
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("name", "bea-test"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let outcome = client.wakeup("bea-test").await?;
+
+    assert_eq!(
+        outcome,
+        ewon::WakeupOutcome {
+            message: Some("Wake-up SMS sent".to_string())
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wakeup_by_id_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response = json!({
+      "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let outcome = client.wakeup(1206698).await?;
+
+    assert_eq!(outcome, ewon::WakeupOutcome { message: None });
+
+    Ok(())
+}
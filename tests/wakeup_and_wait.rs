@@ -0,0 +1,101 @@
+use libewon::m2web::{client, error, ewon};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+fn getewon_response(status: &str) -> serde_json::Value {
+    json!({
+      "ewon": {
+        "id": 1206698,
+        "name": "bea-test",
+        "encodedName": "bea-test",
+        "status": status,
+        "description": "",
+        "customAttributes": ["bea", "", ""],
+        "m2webServer": "eu2.m2web.talk2m.com",
+        "lanDevices": [],
+        "ewonServices": []
+        },
+      "success": true
+    })
+}
+
+#[tokio::test]
+async fn wakeup_and_wait_timeout_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("name", "bea-test"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("wakeup")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("name", "bea-test"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(getewon_response("offline")))
+        .expect(1)
+        .named("getewon")
+        .mount(&server)
+        .await;
+
+    let ewon = match client.wakeup_and_wait("bea-test", Duration::ZERO).await {
+        Ok(_) => panic!("wakeup_and_wait should have returned an error::Timeout"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", ewon),
+        "Timeout: eWON [bea-test] is still offline after 0 seconds"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wakeup_and_wait_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("wakeup")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/getewon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(getewon_response("online")))
+        .expect(1)
+        .named("getewon")
+        .mount(&server)
+        .await;
+
+    let ewon = client
+        .wakeup_and_wait(1206698, Duration::from_secs(60))
+        .await?;
+
+    assert_eq!(ewon.id, 1206698);
+    assert_eq!(ewon.status, ewon::EwonStatus::Online);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use libewon::m2web::{client, error};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn wakeup_pool_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_device_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .build()
+        .unwrap();
+
+    let json_response_getewons = json!({
+        "ewons": [
+        {
+            "id": 1206698,
+            "name": "bea-test",
+            "encodedName": "bea-test",
+            "status": "offline",
+            "description": "",
+            "customAttributes": ["bea", "", ""],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        },
+        {
+            "id": 639491,
+            "name": "eWON  FLEXOCOLOR SM2845",
+            "encodedName": "eWON++FLEXOCOLOR+SM2845",
+            "status": "online",
+            "description": "SM2845 SIRIUS DEBOBINEUR1000",
+            "customAttributes": ["FRANCE", "", ""],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        },
+        {
+            "id": 639492,
+            "name": "eWON FLEXOCOLOR SM2846",
+            "encodedName": "eWON+FLEXOCOLOR+SM2846",
+            "status": "offline",
+            "description": "SM2846 SIRIUS DEBOBINEUR1000",
+            "customAttributes": ["FRANCE", "", ""],
+            "m2webServer": "eu2.m2web.talk2m.com",
+            "lanDevices": [],
+            "ewonServices": []
+        }
+        ],
+        "success": true
+    });
+
+    Mock::given(method("GET"))
+        .and(query_param("pool", "gsm-sites"))
+        .and(path("/t2mapi/getewons"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&json_response_getewons))
+        .expect(1)
+        .named("getewons")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "1206698"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": true})))
+        .expect(1)
+        .named("wakeup bea-test")
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(query_param("id", "639492"))
+        .and(path("/t2mapi/wakeup"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "message": "Device [639492] cannot be woken up",
+            "code": 400,
+            "success": false
+        })))
+        .expect(1)
+        .named("wakeup eWON FLEXOCOLOR SM2846")
+        .mount(&server)
+        .await;
+
+    let summary = client
+        .wakeup_pool("gsm-sites", Duration::from_millis(10))
+        .await?;
+
+    assert_eq!(
+        summary
+            .woken
+            .iter()
+            .map(|ewon| ewon.id)
+            .collect::<Vec<u32>>(),
+        vec![1206698]
+    );
+    assert_eq!(
+        summary
+            .already_online
+            .iter()
+            .map(|ewon| ewon.id)
+            .collect::<Vec<u32>>(),
+        vec![639491]
+    );
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!(summary.failed[0].0.id, 639492);
+    assert_eq!(
+        format!("{}", summary.failed[0].1),
+        "HTTP 400: Device [639492] cannot be woken up"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use libewon::m2web::{client, credentials::DeviceCredentials, error, tag};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+#[tokio::test]
+async fn write_tag_unauthorized_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UpdateTagForm"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("Unauthorized"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tag = match client.write_tag("bea-test", "Setpoint", 21.5).await {
+        Ok(_) => panic!("write_tag should have returned an error::DeviceError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(format!("{}", tag), "eWON HTTP 401: Unauthorized");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_tag_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .t2m_account("account2")
+        .t2m_username("username2")
+        .t2m_password("password2")
+        .t2m_developer_id("795f1844-2f5e-4d8b-9922-25c45d3e1c47")
+        .device_credentials(DeviceCredentials::new().default_credentials("adm", "adm"))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("t2maccount", "account2"))
+        .and(query_param("t2musername", "username2"))
+        .and(query_param("t2mpassword", "password2"))
+        .and(query_param(
+            "t2mdeveloperid",
+            "795f1844-2f5e-4d8b-9922-25c45d3e1c47",
+        ))
+        .and(query_param("t2mdeviceusername", "adm"))
+        .and(query_param("t2mdevicepassword", "adm"))
+        .and(query_param("TagName1", "Setpoint"))
+        .and(query_param("TagValue1", "21.5"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UpdateTagForm"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.write_tag("bea-test", "Setpoint", 21.5).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_tags_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = format!("{}/t2mapi", &server.uri());
+    let client = client::ClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(query_param("TagName1", "Recipe"))
+        .and(query_param("TagValue1", "PET-500"))
+        .and(query_param("TagName2", "Start"))
+        .and(query_param("TagValue2", "1"))
+        .and(path("/t2mapi/get/bea-test/rcgi.bin/UpdateTagForm"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .write_tags(
+            "bea-test",
+            &[
+                ("Recipe", tag::TagData::from("PET-500")),
+                ("Start", tag::TagData::from(true)),
+            ],
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use libewon::{
+    dmweb::{
+        client,
+        data::DataRecord,
+        sink::CsvSink,
+        sync::{CheckpointStore, MemoryCheckpoint},
+    },
+    m2web::error,
+};
+use serde_json::json;
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Store sharing its checkpoint with the test, once moved into the stream.
+#[derive(Clone, Default)]
+struct SharedCheckpoint(Arc<Mutex<MemoryCheckpoint>>);
+
+impl CheckpointStore for SharedCheckpoint {
+    fn load(&mut self) -> Result<Option<u64>, error::Error> {
+        self.0.lock().unwrap().load()
+    }
+
+    fn save(&mut self, transaction_id: u64) -> Result<(), error::Error> {
+        self.0.lock().unwrap().save(transaction_id)
+    }
+}
+
+/// Mount the responses of a synchronization in two pages.
+async fn mount_pages(server: &MockServer) {
+    for (last_transaction_id, more_data_available, value) in
+        [(None, true, 21.5), (Some(1), false, 22.5)]
+    {
+        let response = ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "transactionId": last_transaction_id.unwrap_or(0) + 1,
+            "moreDataAvailable": more_data_available,
+            "ewons": [{
+                "id": 7,
+                "name": "bea-test",
+                "tags": [{
+                    "id": 12,
+                    "name": "Temperature",
+                    "history": [{"date": "2023-11-14T22:13:20Z", "value": value, "quality": "good"}],
+                }],
+            }],
+        }));
+        let mock = Mock::given(method("GET")).and(path("/syncdata"));
+        match last_transaction_id {
+            Some(last_transaction_id) => mock
+                .and(query_param(
+                    "lastTransactionId",
+                    last_transaction_id.to_string(),
+                ))
+                .respond_with(response)
+                .with_priority(1),
+            None => mock.respond_with(response),
+        }
+        .mount(server)
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn write_to_sink_ko() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    mount_pages(&server).await;
+
+    let checkpoint = SharedCheckpoint::default();
+    let mut sink = |_: &[DataRecord]| -> Result<(), error::Error> {
+        Err(io::Error::other("disk full").into())
+    };
+    let written = match client
+        .sync_stream(checkpoint.clone())
+        .write_to(&mut sink)
+        .await
+    {
+        Ok(_) => panic!("write_to should have returned an error::InternalError"),
+        Err(err) => err,
+    };
+
+    assert_eq!(
+        format!("{}", written),
+        "Internal error: I/O error: disk full"
+    );
+    assert_eq!(checkpoint.0.lock().unwrap().transaction_id(), None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_to_sink_ok() -> Result<(), error::Error> {
+    let server = MockServer::start().await;
+    let server_uri = server.uri();
+    let client = client::DmClientBuilder::default()
+        .t2m_url(&server_uri)
+        .build()
+        .unwrap();
+    mount_pages(&server).await;
+
+    let checkpoint = SharedCheckpoint::default();
+    let mut sink = CsvSink::new(vec![]);
+    let written = client
+        .sync_stream(checkpoint.clone())
+        .write_to(&mut sink)
+        .await?;
+
+    assert_eq!(written, 2);
+    assert_eq!(
+        String::from_utf8(sink.into_inner()).unwrap(),
+        "ewon_id,ewon_name,tag_id,tag_name,date,value,quality\n\
+        7,bea-test,12,Temperature,2023-11-14T22:13:20Z,21.5,good\n\
+        7,bea-test,12,Temperature,2023-11-14T22:13:20Z,22.5,good\n"
+    );
+    assert_eq!(checkpoint.0.lock().unwrap().transaction_id(), Some(2));
+
+    Ok(())
+}